@@ -48,4 +48,30 @@ impl SplitNode {
     pub fn rect(&self) -> Rect {
         self.rect
     }
+
+    /// The fraction the top child is drawn with when the node is `size` pixels across:
+    /// ``fraction``, raised as far as needed to hold that child at ``absolute_size_left``.
+    /// ``fraction`` itself is left alone, so the layout recovers once there is room again.
+    pub fn effective_fraction(&self, size: f32) -> f32 {
+        match self.absolute_size_left.filter(|min_size| *min_size > 0.0) {
+            Some(min_size) if size > 0.0 => self.fraction.max((min_size / size).min(1.0)),
+            _ => self.fraction,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn minimum_size_holds_without_changing_the_fraction() {
+        let mut split = SplitNode::new(Rect::NOTHING, 0.2, false, 0);
+        split.absolute_size_left = Some(100.0);
+
+        assert_eq!(split.effective_fraction(250.0), 0.4);
+        assert_eq!(split.effective_fraction(1000.0), 0.2);
+        assert_eq!(split.effective_fraction(50.0), 1.0);
+        assert_eq!(split.fraction, 0.2);
+    }
 }
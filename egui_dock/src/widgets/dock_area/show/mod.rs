@@ -448,7 +448,9 @@ impl<Tab> DockArea<'_, Tab> {
                 debug_assert!(!rect.any_nan() && rect.is_finite());
                 let rect = expand_to_pixel(rect, pixels_per_point);
 
-                let midpoint = rect.min.dim_point + rect.dim_size() * split.fraction;
+                // Hold the left/top child at its minimum size, as far as the node allows
+                let fraction = split.effective_fraction(rect.dim_size());
+                let midpoint = rect.min.dim_point + rect.dim_size() * fraction;
                 let left_separator_border = map_to_pixel(
                     midpoint - style.separator.width * 0.5,
                     pixels_per_point,
@@ -500,7 +502,8 @@ impl<Tab> DockArea<'_, Tab> {
                 let rect = split.rect;
                 let mut separator = rect;
 
-                let midpoint = rect.min.dim_point + rect.dim_size() * split.fraction;
+                let fraction = split.effective_fraction(rect.dim_size());
+                let midpoint = rect.min.dim_point + rect.dim_size() * fraction;
                 separator.min.dim_point = midpoint - style.separator.width * 0.5;
                 separator.max.dim_point = midpoint + style.separator.width * 0.5;
 
@@ -540,7 +543,7 @@ impl<Tab> DockArea<'_, Tab> {
                     None
                 };
 
-                let midpoint = rect.min.dim_point + rect.dim_size() * split.fraction;
+                let midpoint = rect.min.dim_point + rect.dim_size() * fraction;
                 separator.min.dim_point = map_to_pixel(
                     midpoint - style.separator.width * 0.5,
                     pixels_per_point,
@@ -570,7 +573,11 @@ impl<Tab> DockArea<'_, Tab> {
                 let max = 1.0 - min;
                 let (min, max) = (min.min(max), max.max(min));
                 let delta = arrow_key_offset.unwrap_or(response.drag_delta()).dim_point;
-                split.fraction = (split.fraction + delta / range).clamp(min, max);
+                // A moved separator starts from where it was drawn, minimum size hold included
+                if delta != 0.0 {
+                    split.fraction = fraction + delta / range;
+                }
+                split.fraction = split.fraction.clamp(min, max);
 
                 if response.double_clicked() {
                     split.fraction = 0.5;
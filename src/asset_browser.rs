@@ -0,0 +1,188 @@
+use bevy::prelude::*;
+use bevy::tasks::{futures::check_ready, IoTaskPool, Task};
+use bevy_egui::egui;
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{channel, Receiver};
+use std::sync::Mutex;
+
+use crate::genome::GenomeData;
+
+/// Most swatches drawn in an entry's palette strip
+const MAX_PALETTE_SWATCHES: usize = 32;
+
+pub struct AssetBrowserPlugin;
+
+impl Plugin for AssetBrowserPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<AssetBrowser>()
+            .add_systems(Update, rescan_on_file_changes);
+    }
+}
+
+/// A genome file found in the browsed directory
+pub struct AssetEntry {
+    pub path: PathBuf,
+    /// Mode colors for the thumbnail (empty until read, or if the genome is unreadable)
+    pub palette: Vec<egui::Color32>,
+    /// Set if a genome file couldn't be parsed
+    pub error: Option<String>,
+}
+
+/// Palette or parse error of each genome file, read on the IO task pool
+type Thumbnails = Vec<(PathBuf, Result<Vec<egui::Color32>, String>)>;
+
+impl AssetEntry {
+    pub fn file_name(&self) -> String {
+        self.path.file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default()
+    }
+}
+
+struct DirectoryWatch {
+    // Kept alive for as long as we want events
+    _watcher: RecommendedWatcher,
+    events: Mutex<Receiver<notify::Result<notify::Event>>>,
+}
+
+/// State of the Assets panel: the chosen project directory and what's in it
+#[derive(Resource, Default)]
+pub struct AssetBrowser {
+    pub directory: Option<PathBuf>,
+    pub entries: Vec<AssetEntry>,
+    pub scan_error: Option<String>,
+    watch: Option<DirectoryWatch>,
+    /// Genome files of the last scan being parsed for their thumbnails
+    thumbnail_task: Option<Task<Thumbnails>>,
+}
+
+impl AssetBrowser {
+    /// Browse `directory`, scanning it now and watching it for changes
+    pub fn set_directory(&mut self, directory: PathBuf) {
+        self.watch = match watch_directory(&directory) {
+            Ok(watch) => Some(watch),
+            Err(err) => {
+                warn!("Failed to watch {:?}, changes won't show until rescanned: {}", directory, err);
+                None
+            }
+        };
+        self.directory = Some(directory);
+        self.rescan();
+    }
+
+    pub fn rescan(&mut self) {
+        let Some(directory) = &self.directory else {
+            return;
+        };
+        match scan_directory(directory) {
+            Ok(entries) => {
+                let genomes: Vec<PathBuf> = entries.iter().map(|entry| entry.path.clone()).collect();
+                // Replacing the task drops, and so cancels, one for an older scan
+                self.thumbnail_task = Some(IoTaskPool::get().spawn(async move { read_thumbnails(genomes) }));
+                self.entries = entries;
+                self.scan_error = None;
+            }
+            Err(err) => {
+                self.entries.clear();
+                self.thumbnail_task = None;
+                self.scan_error = Some(err.to_string());
+            }
+        }
+    }
+
+    /// Fill in the entries' thumbnails once their genomes have been read
+    fn poll_thumbnails(&mut self) {
+        let Some(thumbnails) = self.thumbnail_task.as_mut().and_then(check_ready) else {
+            return;
+        };
+        self.thumbnail_task = None;
+        for (path, thumbnail) in thumbnails {
+            if let Some(entry) = self.entries.iter_mut().find(|entry| entry.path == path) {
+                match thumbnail {
+                    Ok(palette) => entry.palette = palette,
+                    Err(err) => entry.error = Some(err),
+                }
+            }
+        }
+    }
+}
+
+fn watch_directory(directory: &Path) -> notify::Result<DirectoryWatch> {
+    let (sender, receiver) = channel();
+    let mut watcher = notify::recommended_watcher(sender)?;
+    watcher.watch(directory, RecursiveMode::NonRecursive)?;
+    Ok(DirectoryWatch {
+        _watcher: watcher,
+        events: Mutex::new(receiver),
+    })
+}
+
+/// List the `.json` genomes in `directory`, sorted by name. Only the names are read here; the
+/// genomes are parsed for their thumbnails by [`read_thumbnails`].
+fn scan_directory(directory: &Path) -> std::io::Result<Vec<AssetEntry>> {
+    let mut entries = Vec::new();
+
+    for dir_entry in std::fs::read_dir(directory)? {
+        let path = dir_entry?.path();
+        if !path.is_file() {
+            continue;
+        }
+        let is_genome = path.extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("json"));
+        if is_genome {
+            entries.push(AssetEntry { path, palette: Vec::new(), error: None });
+        }
+    }
+
+    entries.sort_by_key(|entry| entry.file_name().to_lowercase());
+    Ok(entries)
+}
+
+fn read_thumbnails(genomes: Vec<PathBuf>) -> Thumbnails {
+    genomes.into_iter()
+        .map(|path| {
+            let thumbnail = GenomeData::load_from_file(&path)
+                .map(|genome| genome_palette(&genome))
+                .map_err(|err| err.to_string());
+            (path, thumbnail)
+        })
+        .collect()
+}
+
+/// Evenly sampled mode colors used as a genome's thumbnail
+fn genome_palette(genome: &GenomeData) -> Vec<egui::Color32> {
+    let step = genome.modes.len().div_ceil(MAX_PALETTE_SWATCHES).max(1);
+    genome.modes.iter()
+        .step_by(step)
+        .map(|mode| egui::Color32::from_rgb(
+            (mode.color.x * 255.0) as u8,
+            (mode.color.y * 255.0) as u8,
+            (mode.color.z * 255.0) as u8,
+        ))
+        .collect()
+}
+
+fn rescan_on_file_changes(mut browser: ResMut<AssetBrowser>) {
+    if browser.thumbnail_task.is_some() {
+        browser.poll_thumbnails();
+    }
+    let Some(watch) = &browser.watch else {
+        return;
+    };
+
+    let mut changed = false;
+    if let Ok(events) = watch.events.lock() {
+        for event in events.try_iter() {
+            match event {
+                Ok(event) => changed |= !event.kind.is_access(),
+                Err(err) => warn!("File watcher error: {}", err),
+            }
+        }
+    }
+
+    if changed {
+        browser.rescan();
+    }
+}
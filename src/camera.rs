@@ -0,0 +1,157 @@
+use bevy::prelude::*;
+use bevy_egui::EguiContext;
+
+use crate::drag::DragState;
+use crate::scene::DraggableSphere;
+
+/// Key that flips the viewport camera between the selected sphere and the whole scene
+const TOGGLE_VIEW_KEY: KeyCode = KeyCode::KeyF;
+pub const TOGGLE_VIEW_SHORTCUT_TEXT: &str = "F";
+
+/// How long the camera takes to fly to the other view
+const TRANSITION_SECS: f32 = 0.35;
+/// Distance from the selected sphere's center in the close view, in sphere radii
+const CLOSE_VIEW_RADII: f32 = 5.0;
+/// Room left around the scene in the overview, as a factor of its bounding radius
+const OVERVIEW_MARGIN: f32 = 1.2;
+/// Field of view assumed for cameras without a perspective projection
+const FALLBACK_FOV: f32 = std::f32::consts::FRAC_PI_4;
+
+pub struct CameraPlugin;
+
+impl Plugin for CameraPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<CameraView>()
+            .add_systems(Update, (toggle_camera_view, animate_camera_transition).chain());
+    }
+}
+
+/// Which view the hotkey last switched to, and the flight there if it's still under way
+#[derive(Resource, Default)]
+pub struct CameraView {
+    close: bool,
+    transition: Option<CameraTransition>,
+}
+
+struct CameraTransition {
+    from: Transform,
+    to: Transform,
+    elapsed: f32,
+}
+
+/// Center and radius of a sphere around every `(center, radius)` sphere, or None for an empty scene
+fn scene_bounds(spheres: impl IntoIterator<Item = (Vec3, f32)>) -> Option<(Vec3, f32)> {
+    let spheres: Vec<(Vec3, f32)> = spheres.into_iter().collect();
+    let (min, max) = spheres.iter().fold(
+        (Vec3::splat(f32::INFINITY), Vec3::splat(f32::NEG_INFINITY)),
+        |(min, max), &(center, radius)| (min.min(center - radius), max.max(center + radius)),
+    );
+    let center = (min + max) * 0.5;
+    let radius = spheres.iter().map(|&(position, radius)| position.distance(center) + radius).reduce(f32::max)?;
+    Some((center, radius))
+}
+
+/// How far from its center a sphere of `radius` must be to fit a view `fov` radians wide
+fn framing_distance(radius: f32, fov: f32) -> f32 {
+    radius / (fov * 0.5).sin().max(0.01)
+}
+
+/// Camera `distance` from `target`, looking at it along `forward`
+fn view_of(target: Vec3, distance: f32, forward: Vec3) -> Transform {
+    Transform::from_translation(target - forward * distance).looking_at(target, Vec3::Y)
+}
+
+fn toggle_camera_view(
+    keys: Res<ButtonInput<KeyCode>>,
+    mut view: ResMut<CameraView>,
+    drag_state: Res<DragState>,
+    camera_query: Query<(&Transform, &Projection), With<Camera3d>>,
+    sphere_query: Query<(Entity, &GlobalTransform, &DraggableSphere)>,
+    mut egui_context: Query<&mut EguiContext>,
+) {
+    if !keys.just_pressed(TOGGLE_VIEW_KEY) {
+        return;
+    }
+    // Typing an F into a text field, or a shortcut like Ctrl+Shift+F
+    let modifiers = [KeyCode::ControlLeft, KeyCode::ControlRight, KeyCode::AltLeft, KeyCode::AltRight, KeyCode::SuperLeft, KeyCode::SuperRight];
+    if keys.any_pressed(modifiers) || egui_context.single_mut().is_ok_and(|mut ctx| ctx.get_mut().wants_keyboard_input()) {
+        return;
+    }
+    let Ok((camera_transform, projection)) = camera_query.single() else {
+        return;
+    };
+
+    let close = !view.close;
+    let forward = *camera_transform.forward();
+    let target = if close {
+        // The sphere being dragged or the last one picked up, otherwise any sphere
+        let selected = drag_state.dragging.or(drag_state.selected).and_then(|entity| sphere_query.get(entity).ok());
+        selected.or_else(|| sphere_query.iter().next())
+            .map(|(_, transform, sphere)| view_of(transform.translation(), sphere.radius * CLOSE_VIEW_RADII, forward))
+    } else {
+        let fov = match projection {
+            Projection::Perspective(perspective) => perspective.fov,
+            _ => FALLBACK_FOV,
+        };
+        scene_bounds(sphere_query.iter().map(|(_, transform, sphere)| (transform.translation(), sphere.radius)))
+            .map(|(center, radius)| view_of(center, framing_distance(radius * OVERVIEW_MARGIN, fov), forward))
+    };
+
+    if let Some(to) = target {
+        view.close = close;
+        view.transition = Some(CameraTransition { from: *camera_transform, to, elapsed: 0.0 });
+    }
+}
+
+/// Ease the camera towards the view the hotkey switched to
+fn animate_camera_transition(
+    time: Res<Time>,
+    mut view: ResMut<CameraView>,
+    mut camera_query: Query<&mut Transform, With<Camera3d>>,
+) {
+    let Some(transition) = view.transition.as_mut() else {
+        return;
+    };
+    let Ok(mut camera_transform) = camera_query.single_mut() else {
+        view.transition = None;
+        return;
+    };
+
+    transition.elapsed += time.delta_secs();
+    let t = (transition.elapsed / TRANSITION_SECS).min(1.0);
+    let eased = t * t * (3.0 - 2.0 * t);
+    camera_transform.translation = transition.from.translation.lerp(transition.to.translation, eased);
+    camera_transform.rotation = transition.from.rotation.slerp(transition.to.rotation, eased);
+    if t >= 1.0 {
+        view.transition = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bounds_hold_every_sphere() {
+        assert_eq!(scene_bounds([]), None);
+        assert_eq!(scene_bounds([(Vec3::ONE, 0.5)]), Some((Vec3::ONE, 0.5)));
+
+        let spheres = [(Vec3::new(-2.0, 0.5, 0.0), 0.5), (Vec3::new(3.0, 0.5, 1.0), 1.0), (Vec3::new(0.0, 4.0, 0.0), 0.5)];
+        let (center, radius) = scene_bounds(spheres).unwrap();
+        for (position, sphere_radius) in spheres {
+            assert!(position.distance(center) + sphere_radius <= radius + 1e-5);
+        }
+    }
+
+    #[test]
+    fn framed_spheres_fit_the_view() {
+        let fov = 60f32.to_radians();
+        let distance = framing_distance(2.0, fov);
+        // The edge of the sphere is exactly at the edge of the view
+        assert!(((2.0 / distance).asin() - fov * 0.5).abs() < 1e-5);
+
+        let camera = view_of(Vec3::Y, distance, Vec3::NEG_Z);
+        assert!((camera.translation - Vec3::new(0.0, 1.0, distance)).length() < 1e-5);
+        assert!((*camera.forward() - Vec3::NEG_Z).length() < 1e-5);
+    }
+}
@@ -1,11 +1,18 @@
 use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
 use egui_dock::DockState;
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
+use serde::de::{EnumAccess, IntoDeserializer, VariantAccess, Visitor};
+use std::collections::HashMap;
 use std::fs;
-use std::path::Path;
+use std::path::PathBuf;
 use std::time::Duration;
 
 const DOCK_STATE_FILE: &str = "dock_state.ron";
+/// Copy of the layout from before the last reset
+const DOCK_STATE_BACKUP_FILE: &str = "dock_state.ron.bak";
+/// Closed tabs remembered for "Reopen Closed Panel"
+const MAX_RECENTLY_CLOSED: usize = 10;
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
 pub enum Panel {
@@ -25,6 +32,9 @@ pub enum Panel {
     ThemeEditor,
     CameraSettings,
     LightingSettings,
+    CellTypeSettings,
+    LibrarySearch,
+    Settings,
     
     // Legacy names for compatibility
     Inspector,
@@ -44,6 +54,33 @@ impl Panel {
     pub fn is_placeholder(&self) -> bool {
         matches!(self, Panel::LeftPanel | Panel::RightPanel | Panel::BottomPanel)
     }
+
+    /// Panels that can be open more than once. There is only one viewport to draw into.
+    pub fn can_duplicate(&self) -> bool {
+        !self.is_placeholder() && *self != Panel::Viewport
+    }
+
+    /// What can only be done from this panel, for panels whose last copy shouldn't close unnoticed
+    pub fn sole_means_of(&self) -> Option<&'static str> {
+        match self {
+            Panel::Modes => Some("select, add or delete modes"),
+            Panel::NameTypeEditor => Some("rename the genome or set its seed"),
+            _ => None,
+        }
+    }
+
+    /// Panels that edit one mode (normally the selected one), and so can be pinned to a mode
+    pub fn shows_mode(&self) -> bool {
+        matches!(
+            self,
+            Panel::CellTypeSettings
+                | Panel::CircleSliders
+                | Panel::QuaternionBall
+                | Panel::NameTypeEditor
+                | Panel::AdhesionSettings
+                | Panel::ParentSettings
+        )
+    }
 }
 
 impl std::fmt::Display for Panel {
@@ -63,6 +100,9 @@ impl std::fmt::Display for Panel {
             Panel::ThemeEditor => write!(f, "Theme Editor"),
             Panel::CameraSettings => write!(f, "Camera Settings"),
             Panel::LightingSettings => write!(f, "Lighting Settings"),
+            Panel::CellTypeSettings => write!(f, "Cell Type Settings"),
+            Panel::LibrarySearch => write!(f, "Library Search"),
+            Panel::Settings => write!(f, "Settings"),
             // Legacy names
             Panel::Inspector => write!(f, "Inspector"),
             Panel::Console => write!(f, "Console"),
@@ -79,115 +119,674 @@ impl std::fmt::Display for Panel {
     }
 }
 
+/// A tab in the dock: the panel it shows, plus state that belongs to this tab alone so that
+/// copies of one panel can differ. Saved layouts record only the panel.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct DockTab {
+    pub panel: Panel,
+    /// Mode shown instead of the selected one, for panels that [show a mode](Panel::shows_mode)
+    pub pinned_mode: Option<usize>,
+    id: TabId,
+}
+
+/// Tells one tab from every other for as long as the app runs, copies of the same panel included
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TabId(u64);
+
+impl TabId {
+    fn next() -> Self {
+        static NEXT: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(0);
+        Self(NEXT.fetch_add(1, std::sync::atomic::Ordering::Relaxed))
+    }
+}
+
+impl DockTab {
+    pub fn id(&self) -> TabId {
+        self.id
+    }
+
+    /// Another tab showing the same thing, with an id of its own
+    pub fn copy(&self) -> Self {
+        Self { id: TabId::next(), ..self.clone() }
+    }
+}
+
+impl From<Panel> for DockTab {
+    fn from(panel: Panel) -> Self {
+        Self { panel, pinned_mode: None, id: TabId::next() }
+    }
+}
+
+impl Serialize for DockTab {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.panel.serialize(serializer)
+    }
+}
+
+/// Folder holding `dock_state.ron` and other app config
+pub fn config_dir() -> PathBuf {
+    std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."))
+}
+
+fn dock_state_path() -> PathBuf {
+    config_dir().join(DOCK_STATE_FILE)
+}
+
+fn dock_state_backup_path() -> PathBuf {
+    config_dir().join(DOCK_STATE_BACKUP_FILE)
+}
+
 #[derive(Resource)]
 pub struct DockResource {
-    pub tree: DockState<Panel>,
+    pub tree: DockState<DockTab>,
     pub all_hidden: bool,
+    /// Whether the "Reset Layout" confirmation is showing
+    pub confirm_reset: bool,
+    /// Panel whose last copy is waiting on a close confirmation
+    pub confirm_close: Option<Panel>,
+    /// Tab shown on its own next to the viewport, and the layout to put back afterwards
+    solo: Option<(DockTab, DockState<DockTab>)>,
+    /// Backup written by the last reset this session, which "Undo Reset" loads
+    reset_backup: Option<PathBuf>,
+    /// Most recently closed tabs last, for "Reopen Closed Panel"
+    recently_closed: Vec<ClosedTab>,
 }
 
-pub fn load_dock_state() -> Option<DockState<Panel>> {
-    if Path::new(DOCK_STATE_FILE).exists() {
-        let data = fs::read_to_string(DOCK_STATE_FILE).ok()?;
-        ron::from_str(&data).ok()
-    } else {
+/// A closed tab and enough of where it was to put it back there
+struct ClosedTab {
+    tab: DockTab,
+    place: Option<ClosedPlace>,
+}
+
+/// Where a closed tab was, relative to a tab that was open beside it. Node indices change as the
+/// layout does, but the neighbour can be found again wherever it has moved.
+enum ClosedPlace {
+    /// Among other tabs of a leaf: one of them, and the closed tab's position
+    InLeaf { neighbour: DockTab, index: usize },
+    /// Alone in its leaf: a tab from the other side of the split that held it, how far that tab
+    /// sat below the top of its side, and the split to recreate with its minimum size
+    BesideSplit { neighbour: DockTab, depth: usize, split: egui_dock::Split, fraction: f32, min_size: Option<f32> },
+}
+
+/// Where the tab at `(surface, node, tab_index)` is, for reopening it there after it is closed
+fn closed_place(
+    tree: &DockState<DockTab>,
+    (surface, node, tab_index): (egui_dock::SurfaceIndex, egui_dock::NodeIndex, egui_dock::TabIndex),
+) -> Option<ClosedPlace> {
+    let tabs = tree[surface][node].tabs()?;
+    if let Some((_, neighbour)) = tabs.iter().enumerate().find(|(i, _)| *i != tab_index.0) {
+        return Some(ClosedPlace::InLeaf { neighbour: neighbour.clone(), index: tab_index.0 });
+    }
+
+    let parent = node.parent()?;
+    let (sibling, split, split_node) = match (&tree[surface][parent], node.is_left()) {
+        (egui_dock::Node::Horizontal(split), true) => (parent.right(), egui_dock::Split::Left, split),
+        (egui_dock::Node::Horizontal(split), false) => (parent.left(), egui_dock::Split::Right, split),
+        (egui_dock::Node::Vertical(split), true) => (parent.right(), egui_dock::Split::Above, split),
+        (egui_dock::Node::Vertical(split), false) => (parent.left(), egui_dock::Split::Below, split),
+        _ => return None,
+    };
+    let is_under_sibling = |mut index: egui_dock::NodeIndex| loop {
+        if index == sibling {
+            return true;
+        }
+        match index.parent() {
+            Some(parent) => index = parent,
+            None => return false,
+        }
+    };
+    let (neighbour_node, neighbour) = tree[surface].iter().enumerate()
+        .filter(|(index, _)| is_under_sibling(egui_dock::NodeIndex(*index)))
+        .find_map(|(index, node)| Some((egui_dock::NodeIndex(index), node.tabs()?.first()?)))?;
+    Some(ClosedPlace::BesideSplit {
+        neighbour: neighbour.clone(),
+        depth: neighbour_node.level() - sibling.level(),
+        split,
+        fraction: split_node.fraction,
+        min_size: split_node.absolute_size_left,
+    })
+}
+
+impl DockResource {
+    pub fn new(tree: DockState<DockTab>) -> Self {
+        Self {
+            tree,
+            all_hidden: false,
+            confirm_reset: false,
+            confirm_close: None,
+            solo: None,
+            reset_backup: None,
+            recently_closed: Vec::new(),
+        }
+    }
+
+    /// Close the first open copy of `panel`
+    pub fn close_panel(&mut self, panel: &Panel) {
+        let tab = self.tree.iter_all_tabs().find(|(_, tab)| tab.panel == *panel).map(|(_, tab)| tab.clone());
+        if let Some(tab) = tab {
+            self.close_tab(&tab);
+        }
+    }
+
+    /// Remove `tab` from the layout, remembering where it was for [`DockResource::reopen_closed`]
+    pub fn close_tab(&mut self, tab: &DockTab) {
+        let Some(location) = self.tree.find_tab(tab) else {
+            return;
+        };
+        let place = closed_place(&self.tree, location);
+        let Some(tab) = self.tree.remove_tab(location) else {
+            return;
+        };
+        if self.recently_closed.len() == MAX_RECENTLY_CLOSED {
+            self.recently_closed.remove(0);
+        }
+        self.recently_closed.push(ClosedTab { tab, place });
+    }
+
+    /// The panel "Reopen Closed Panel" would bring back
+    pub fn last_closed(&self) -> Option<&Panel> {
+        self.recently_closed.last().map(|closed| &closed.tab.panel)
+    }
+
+    /// Open the most recently closed tab where it was, next to the same neighbour, or in the
+    /// focused leaf if that neighbour has been closed too. Returns the reopened panel.
+    pub fn reopen_closed(&mut self) -> Option<Panel> {
+        let closed = self.recently_closed.pop()?;
+        // The solo layout is thrown away on exit, and the reopened tab with it
+        self.exit_solo();
+        let panel = closed.tab.panel.clone();
+        if let Some(tab) = self.reopen_in_place(closed) {
+            self.tree.push_to_focused_leaf(tab);
+        }
+        Some(panel)
+    }
+
+    /// Put a closed tab back in its old place, or hand it back if that place is gone
+    fn reopen_in_place(&mut self, closed: ClosedTab) -> Option<DockTab> {
+        let Some(place) = closed.place else {
+            return Some(closed.tab);
+        };
+        match place {
+            ClosedPlace::InLeaf { neighbour, index } => {
+                let Some((surface, node, _)) = self.find_neighbour(&neighbour) else {
+                    return Some(closed.tab);
+                };
+                let leaf = self.tree[surface][node].get_leaf_mut()?;
+                leaf.insert_tab(index.min(leaf.tabs().len()), closed.tab);
+                self.tree.set_focused_node_and_surface((surface, node));
+            }
+            ClosedPlace::BesideSplit { neighbour, depth, split, fraction, min_size } => {
+                let Some((surface, mut node, _)) = self.find_neighbour(&neighbour) else {
+                    return Some(closed.tab);
+                };
+                for _ in 0..depth {
+                    node = node.parent().unwrap_or(node);
+                }
+                let [_, new] = self.tree[surface].split(node, split, fraction, egui_dock::Node::leaf(closed.tab));
+                if let egui_dock::Node::Horizontal(split) | egui_dock::Node::Vertical(split) = &mut self.tree[surface][node] {
+                    split.absolute_size_left = min_size;
+                }
+                self.tree.set_focused_node_and_surface((surface, new));
+            }
+        }
         None
     }
+
+    /// `neighbour` itself, or a copy of its panel if the layout has been reloaded since
+    fn find_neighbour(&self, neighbour: &DockTab) -> Option<(egui_dock::SurfaceIndex, egui_dock::NodeIndex, egui_dock::TabIndex)> {
+        self.tree.find_tab(neighbour).or_else(|| self.tree.find_tab_from(|tab| tab.panel == neighbour.panel))
+    }
+
+    /// Bring `panel` to the front and focus it, opening it in the focused leaf if it isn't open
+    pub fn focus_panel(&mut self, panel: &Panel) {
+        match self.tree.find_tab_from(|tab| tab.panel == *panel) {
+            Some((surface, node, tab)) => {
+                self.tree.set_active_tab((surface, node, tab));
+                self.tree.set_focused_node_and_surface((surface, node));
+            }
+            None => {
+                open_panel(&mut self.tree, panel);
+                self.forget_closed(panel);
+            }
+        }
+    }
+
+    /// Opened by other means, so it no longer needs reopening
+    pub fn forget_closed(&mut self, panel: &Panel) {
+        self.recently_closed.retain(|closed| closed.tab.panel != *panel);
+    }
+
+    /// Open panels with a `sole_means_of` that have no other copy, so closing them needs a confirmation
+    pub fn last_essential_panels(&self) -> Vec<Panel> {
+        let mut counts: HashMap<&Panel, usize> = HashMap::new();
+        for (_, tab) in self.tree.iter_all_tabs() {
+            *counts.entry(&tab.panel).or_default() += 1;
+        }
+        counts.into_iter()
+            .filter(|(panel, count)| *count == 1 && panel.sole_means_of().is_some())
+            .map(|(panel, _)| panel.clone())
+            .collect()
+    }
+
+    pub fn solo_panel(&self) -> Option<&Panel> {
+        self.solo.as_ref().map(|(tab, _)| &tab.panel)
+    }
+
+    /// Hide everything except `tab` and the viewport until [`DockResource::exit_solo`]
+    pub fn enter_solo(&mut self, tab: DockTab) {
+        self.exit_solo();
+        let saved = std::mem::replace(&mut self.tree, create_solo_layout(&tab));
+        info!("Soloing panel {}", tab.panel);
+        self.solo = Some((tab, saved));
+    }
+
+    /// Put back the layout from before the panel was soloed
+    pub fn exit_solo(&mut self) {
+        if let Some((_, saved)) = self.solo.take() {
+            self.tree = saved;
+        }
+    }
+
+    pub fn toggle_solo(&mut self, tab: DockTab) {
+        if self.solo_panel() == Some(&tab.panel) {
+            self.exit_solo();
+        } else {
+            self.enter_solo(tab);
+        }
+    }
+
+    /// Keep pinned tabs on the same modes after they were renumbered; tabs pinned to a
+    /// removed mode follow the selection again
+    pub fn remap_pinned_modes(&mut self, remap: &[Option<usize>]) {
+        let saved = self.solo.as_mut().map(|(_, saved)| saved);
+        for tree in std::iter::once(&mut self.tree).chain(saved) {
+            for (_, tab) in tree.iter_all_tabs_mut() {
+                if let Some(mode) = tab.pinned_mode {
+                    tab.pinned_mode = remap.get(mode).copied().flatten();
+                }
+            }
+        }
+    }
+
+    /// Drop pins to modes at or past `mode_count`, so those tabs follow the selection again
+    pub fn unpin_missing_modes(&mut self, mode_count: usize) {
+        let saved = self.solo.as_mut().map(|(_, saved)| saved);
+        for tree in std::iter::once(&mut self.tree).chain(saved) {
+            for (_, tab) in tree.iter_all_tabs_mut() {
+                if tab.pinned_mode.is_some_and(|mode| mode >= mode_count) {
+                    tab.pinned_mode = None;
+                }
+            }
+        }
+    }
+
+    /// Switch to the default layout for a `window_width` wide window, first writing the current
+    /// layout to `backup`. Nothing changes if the backup can't be written.
+    pub fn reset_layout(&mut self, backup: PathBuf, window_width: f32) -> Result<(), Box<dyn std::error::Error>> {
+        self.exit_solo();
+        fs::write(&backup, serialize_dock_state(&self.tree)?)?;
+        self.tree = create_default_layout(window_width);
+        info!("Reset the dock layout, previous layout saved to {}", backup.display());
+        self.reset_backup = Some(backup);
+        Ok(())
+    }
+
+    pub fn can_undo_reset(&self) -> bool {
+        self.reset_backup.is_some()
+    }
+
+    /// Put back the layout backed up by the last [`DockResource::reset_layout`]
+    pub fn undo_reset(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+        let Some(backup) = &self.reset_backup else {
+            return Ok(());
+        };
+        let (mut tree, dropped) = parse_dock_state(&fs::read_to_string(backup)?)?;
+        if !dropped.is_empty() {
+            warn!("Dropped unknown panels from {}: {}", backup.display(), dropped.join(", "));
+        }
+        restore_placeholders(&mut tree);
+        self.exit_solo();
+        self.tree = tree;
+        self.reset_backup = None;
+        info!("Restored the dock layout from before the reset");
+        Ok(())
+    }
+
+    /// The user's real layout, i.e. not the temporary solo one
+    pub fn layout_to_save(&self) -> &DockState<DockTab> {
+        self.solo.as_ref().map_or(&self.tree, |(_, saved)| saved)
+    }
+}
+
+/// Just `tab` to the left of the viewport
+fn create_solo_layout(tab: &DockTab) -> DockState<DockTab> {
+    let mut tree = DockState::new(vec![DockTab::from(Panel::Viewport)]);
+    if tab.panel != Panel::Viewport {
+        tree.main_surface_mut().split_left(egui_dock::NodeIndex::root(), 0.3, vec![tab.clone()]);
+    }
+    tree
+}
+
+/// A tab as read from disk - either a panel this build knows about or the name of one it doesn't
+enum StoredPanel {
+    Known(Panel),
+    Unknown(String),
+}
+
+/// Variant name read as an identifier (RON won't hand identifiers to a plain `String`)
+struct VariantName(String);
+
+impl<'de> Deserialize<'de> for VariantName {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct VariantNameVisitor;
+
+        impl Visitor<'_> for VariantNameVisitor {
+            type Value = VariantName;
+
+            fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                write!(f, "a variant name")
+            }
+
+            fn visit_str<E: serde::de::Error>(self, name: &str) -> Result<Self::Value, E> {
+                Ok(VariantName(name.to_string()))
+            }
+        }
+
+        deserializer.deserialize_identifier(VariantNameVisitor)
+    }
+}
+
+impl<'de> Deserialize<'de> for StoredPanel {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct StoredPanelVisitor;
+
+        impl<'de> Visitor<'de> for StoredPanelVisitor {
+            type Value = StoredPanel;
+
+            fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                write!(f, "a Panel variant")
+            }
+
+            fn visit_enum<A: EnumAccess<'de>>(self, data: A) -> Result<Self::Value, A::Error> {
+                let (VariantName(name), variant) = data.variant()?;
+                variant.unit_variant()?;
+                let known = Panel::deserialize(name.as_str().into_deserializer())
+                    .map_err(|_: serde::de::value::Error| ());
+                Ok(match known {
+                    Ok(panel) => StoredPanel::Known(panel),
+                    Err(()) => StoredPanel::Unknown(name),
+                })
+            }
+        }
+
+        deserializer.deserialize_enum("Panel", &[], StoredPanelVisitor)
+    }
+}
+
+/// Parse a saved dock layout, dropping any tabs whose panel no longer exists.
+/// Returns the layout along with the names of the dropped tabs.
+pub fn parse_dock_state(data: &str) -> Result<(DockState<DockTab>, Vec<String>), ron::error::SpannedError> {
+    let stored: DockState<StoredPanel> = ron::from_str(data)?;
+    let mut dropped = Vec::new();
+    let mut tree = stored.filter_map_tabs(|tab| match tab {
+        StoredPanel::Known(panel) => Some(DockTab::from(panel.clone())),
+        StoredPanel::Unknown(name) => {
+            dropped.push(name.clone());
+            None
+        }
+    });
+    apply_panel_minimums(&mut tree);
+    Ok((tree, dropped))
+}
+
+/// Reopen the default layout's panels that are missing from `tree`, for a saved layout that
+/// lost tabs when it was loaded. Returns the reopened panels.
+pub fn reopen_default_panels(tree: &mut DockState<DockTab>) -> Vec<Panel> {
+    let defaults = create_default_layout(DEFAULT_LAYOUT_WIDTH);
+    let missing: Vec<Panel> = defaults.iter_all_tabs()
+        .map(|(_, tab)| tab.panel.clone())
+        .filter(|panel| !panel.is_placeholder() && !is_panel_open(tree, panel))
+        .collect();
+    for panel in &missing {
+        tree.main_surface_mut().push_to_first_leaf(DockTab::from(panel.clone()));
+    }
+    missing
 }
 
-pub fn save_dock_state(tree: &DockState<Panel>) {
-    if let Ok(serialized) = ron::ser::to_string_pretty(tree, Default::default()) {
-        let _ = fs::write(DOCK_STATE_FILE, serialized);
+pub fn load_dock_state() -> Option<DockState<DockTab>> {
+    let path = dock_state_path();
+    if !path.exists() {
+        return None;
+    }
+
+    let data = fs::read_to_string(&path).ok()?;
+    match parse_dock_state(&data) {
+        Ok((mut tree, dropped)) => {
+            if !dropped.is_empty() {
+                warn!("Dropped unknown panels from {}: {}", DOCK_STATE_FILE, dropped.join(", "));
+                let reopened = reopen_default_panels(&mut tree);
+                if !reopened.is_empty() {
+                    let names: Vec<String> = reopened.iter().map(|panel| panel.to_string()).collect();
+                    info!("Reopened default panels: {}", names.join(", "));
+                }
+            }
+            Some(tree)
+        }
+        Err(err) => {
+            warn!("Failed to parse {}, falling back to default layout: {}", DOCK_STATE_FILE, err);
+            None
+        }
     }
 }
 
-pub fn create_default_layout() -> DockState<Panel> {
+fn serialize_dock_state(tree: &DockState<DockTab>) -> Result<String, ron::Error> {
+    ron::ser::to_string_pretty(tree, Default::default())
+}
+
+pub fn save_dock_state(tree: &DockState<DockTab>) -> Result<(), Box<dyn std::error::Error>> {
+    fs::write(dock_state_path(), serialize_dock_state(tree)?)?;
+    Ok(())
+}
+
+/// Default widths of the side panels, in logical pixels
+const MODES_WIDTH: f32 = 120.0;
+const LEFT_PANEL_WIDTH: f32 = 400.0;
+const RIGHT_PANEL_WIDTH: f32 = 350.0;
+/// Narrowest the Modes strip and the left panel can be dragged
+const MODES_MIN_WIDTH: f32 = 60.0;
+const LEFT_PANEL_MIN_WIDTH: f32 = 300.0;
+/// Share of the window the viewport keeps when the panels don't fit at their default widths
+const MIN_VIEWPORT_SHARE: f32 = 0.4;
+/// Window width the default layout is planned for when the real one isn't known
+pub const DEFAULT_LAYOUT_WIDTH: f32 = 1920.0;
+
+/// Pixel widths of the Modes, left and right panels in a window `window_width` wide.
+/// The panels shrink together when they would leave the viewport less than its share.
+fn default_panel_widths(window_width: f32) -> [f32; 3] {
+    let widths = [MODES_WIDTH, LEFT_PANEL_WIDTH, RIGHT_PANEL_WIDTH];
+    let scale = (window_width * (1.0 - MIN_VIEWPORT_SHARE) / widths.iter().sum::<f32>()).min(1.0);
+    widths.map(|width| width * scale)
+}
+
+pub fn create_default_layout(window_width: f32) -> DockState<DockTab> {
+    let window_width = window_width.max(1.0);
+    let [modes_width, left_width, right_width] = default_panel_widths(window_width);
+
     // Create the initial layout with Viewport in the center
-    let mut tree = DockState::new(vec![Panel::Viewport]);
+    let mut tree = DockState::new(vec![DockTab::from(Panel::Viewport)]);
     let surface = tree.main_surface_mut();
 
-    // Build structure: Modes | Left (full height) | (Viewport / Bottom) | Right (full height)
-    
-    // First: Add Modes panel on the far left (6% width - reduced from 10%)
-    let [_modes, rest] = surface.split_left(
-        egui_dock::NodeIndex::root(),
-        0.06,
-        vec![Panel::Modes]
+    // Build structure: Modes | Left (full height) | (Viewport | Right) / Bottom.
+    // A split's fraction is the share of its left (or top) child, and each split divides
+    // what the previous one left over.
+
+    let modes_split = egui_dock::NodeIndex::root();
+    let [rest, _modes] = surface.split_left(
+        modes_split,
+        modes_width / window_width,
+        vec![DockTab::from(Panel::Modes)]
     );
-    
-    // Second: Add left panel (20% of remaining width, full height)
-    let [_left_node, rest] = surface.split_left(
+
+    let left_split = rest;
+    let remaining = window_width - modes_width;
+    let [rest, _left] = surface.split_left(
         rest,
-        0.22,
-        vec![Panel::LeftPanel]
+        left_width / remaining,
+        vec![DockTab::from(Panel::LeftPanel)]
     );
 
-    // Third: Split bottom panel from viewport (70% top, 30% bottom)
+    // 70% top, 30% bottom
     let [viewport, _bottom] = surface.split_below(
         rest,
         0.7,
-        vec![Panel::BottomPanel]
+        vec![DockTab::from(Panel::BottomPanel)]
     );
 
-    // Fourth: Add right panel to the right of viewport only (25% of remaining width)
-    let [_viewport, _right] = surface.split_right(
+    // The viewport keeps what the right panel doesn't take
+    let remaining = remaining - left_width;
+    surface.split_right(
         viewport,
-        0.25,
-        vec![Panel::RightPanel]
+        1.0 - right_width / remaining,
+        vec![DockTab::from(Panel::RightPanel)]
     );
 
-    // Set absolute minimum size for left panel (300 pixels)
-    // The parent of left_node is the horizontal split that contains it
-    if let egui_dock::Node::Horizontal(split) = &mut surface[egui_dock::NodeIndex::root()] {
-        split.absolute_size_left = Some(0.0); // Will be initialized to current size on first render
+    // Keep the Modes strip and the left panel usable when their splits are dragged,
+    // unless the window is too narrow
+    for (split_node, min_width) in [(modes_split, MODES_MIN_WIDTH.min(modes_width)), (left_split, LEFT_PANEL_MIN_WIDTH.min(left_width))] {
+        if let egui_dock::Node::Horizontal(split) = &mut surface[split_node] {
+            split.absolute_size_left = Some(min_width);
+        }
     }
 
     tree
 }
 
-pub fn setup_dock(mut commands: Commands) {
+/// Width the panel keeps when it's the left side of a split, in logical pixels
+fn panel_min_width(panel: &Panel) -> Option<f32> {
+    match panel {
+        Panel::Modes => Some(MODES_MIN_WIDTH),
+        Panel::LeftPanel => Some(LEFT_PANEL_MIN_WIDTH),
+        _ => None,
+    }
+}
+
+/// Give the splits holding the Modes strip and the left panel their minimum widths where the
+/// layout has none, e.g. one saved before minimums were stored or a split re-created around a
+/// restored placeholder. A saved fraction of zero would otherwise hide the panel for good.
+/// Minimums the layout already has, like a narrow window's smaller ones, are kept.
+pub fn apply_panel_minimums(tree: &mut DockState<DockTab>) {
+    let surface = tree.main_surface_mut();
+    for panel in [Panel::Modes, Panel::LeftPanel] {
+        let (Some(min_width), Some((node, _))) = (panel_min_width(&panel), surface.find_tab_from(|tab| tab.panel == panel)) else {
+            continue;
+        };
+        let Some(parent) = node.parent().filter(|_| node.is_left()) else {
+            continue;
+        };
+        if let egui_dock::Node::Horizontal(split) = &mut surface[parent]
+            && split.absolute_size_left.is_none_or(|size| size <= 0.0)
+        {
+            split.absolute_size_left = Some(min_width);
+        }
+    }
+}
+
+/// Placeholders that give the layout its structure, in the order they are restored
+const PLACEHOLDERS: [Panel; 3] = [Panel::LeftPanel, Panel::BottomPanel, Panel::RightPanel];
+
+/// Placeholders that are not in the main surface (closed, or dragged out into a window)
+pub fn missing_placeholders(tree: &DockState<DockTab>) -> Vec<Panel> {
+    PLACEHOLDERS.into_iter()
+        .filter(|panel| tree.main_surface().find_tab_from(|tab| tab.panel == *panel).is_none())
+        .collect()
+}
+
+/// Put every missing placeholder back into the main surface, next to the viewport on its
+/// usual side. Returns the placeholders that were restored.
+pub fn restore_placeholders(tree: &mut DockState<DockTab>) -> Vec<Panel> {
+    let missing = missing_placeholders(tree);
+    for panel in &missing {
+        // Dragged out into a floating window
+        if let Some(location) = tree.find_tab_from(|tab| tab.panel == *panel) {
+            tree.remove_tab(location);
+        }
+
+        let surface = tree.main_surface_mut();
+        if surface.is_empty() {
+            surface.push_to_first_leaf(DockTab::from(panel.clone()));
+            continue;
+        }
+        let anchor = surface.find_tab_from(|tab| tab.panel == Panel::Viewport).map_or(egui_dock::NodeIndex::root(), |(node, _)| node);
+        let tabs = vec![DockTab::from(panel.clone())];
+        match panel {
+            Panel::LeftPanel => surface.split_left(anchor, 0.22, tabs),
+            Panel::BottomPanel => surface.split_below(anchor, 0.7, tabs),
+            _ => surface.split_right(anchor, 0.75, tabs),
+        };
+    }
+    apply_panel_minimums(tree);
+    missing
+}
+
+/// Keep the placeholders in place after tabs are dragged or closed
+pub fn enforce_placeholders(mut dock_resource: ResMut<DockResource>) {
+    // The solo layout leaves them out on purpose
+    if dock_resource.solo_panel().is_some() || missing_placeholders(&dock_resource.tree).is_empty() {
+        return;
+    }
+    let restored = restore_placeholders(&mut dock_resource.tree);
+    let names: Vec<String> = restored.iter().map(|panel| panel.to_string()).collect();
+    warn!("Restored missing layout placeholders: {}", names.join(", "));
+}
+
+pub fn setup_dock(mut commands: Commands, windows: Query<&Window, With<PrimaryWindow>>) {
     // Spawn a camera to enable egui rendering
     commands.spawn(Camera2d);
 
-    let tree = load_dock_state().unwrap_or_else(|| {
-        info!("Creating default dock layout");
-        create_default_layout()
+    let mut tree = load_dock_state().unwrap_or_else(|| {
+        let width = windows.single().map_or(DEFAULT_LAYOUT_WIDTH, |window| window.width());
+        info!("Creating default dock layout for a {}px wide window", width);
+        create_default_layout(width)
     });
+    let restored = restore_placeholders(&mut tree);
+    if !restored.is_empty() {
+        let names: Vec<String> = restored.iter().map(|panel| panel.to_string()).collect();
+        warn!("Saved layout was missing placeholders, restored: {}", names.join(", "));
+    }
     
     info!("Dock state initialized");
-    commands.insert_resource(DockResource { 
-        tree,
-        all_hidden: false,
-    });
+    commands.insert_resource(DockResource::new(tree));
     commands.init_resource::<crate::ui::ViewportRect>();
     commands.init_resource::<crate::ui::WidgetDemoState>();
 }
 
-pub fn is_panel_open(tree: &DockState<Panel>, panel: &Panel) -> bool {
+pub fn is_panel_open(tree: &DockState<DockTab>, panel: &Panel) -> bool {
     // Use public API to check all tabs
-    tree.iter_all_tabs().any(|(_, tab)| tab == panel)
+    tree.iter_all_tabs().any(|(_, tab)| tab.panel == *panel)
 }
 
-pub fn close_panel(tree: &mut DockState<Panel>, panel: &Panel) {
-    // Find the panel location
-    if let Some((surface_index, node_index, tab_index)) = tree.find_tab(panel) {
-        tree[surface_index].remove_tab((node_index, tab_index));
-    }
-}
-
-pub fn open_panel(tree: &mut DockState<Panel>, panel: &Panel) {
+pub fn open_panel(tree: &mut DockState<DockTab>, panel: &Panel) {
     // Add the panel to the focused leaf
-    tree.main_surface_mut().push_to_focused_leaf(panel.clone());
+    tree.main_surface_mut().push_to_focused_leaf(DockTab::from(panel.clone()));
 }
 
+/// Autosave timer and write status; the interval is re-read from preferences each tick
 #[derive(Resource)]
 pub struct SaveTimer {
     timer: Timer,
+    /// Layout as last written successfully. Anything else is (re)written on the next tick,
+    /// which is also how a failed write gets retried.
+    last_saved: Option<String>,
+    /// Set from a failed write until one succeeds, so the failure is only reported once
+    failing: bool,
 }
 
 impl Default for SaveTimer {
     fn default() -> Self {
         Self {
             timer: Timer::new(Duration::from_secs(2), TimerMode::Repeating),
+            last_saved: None,
+            failing: false,
         }
     }
 }
@@ -196,28 +795,66 @@ pub fn auto_save_dock_state(
     time: Res<Time>,
     mut save_timer: Local<SaveTimer>,
     dock_resource: Res<DockResource>,
+    preferences: Res<crate::preferences::Preferences>,
+    mut toasts: ResMut<crate::toast::Toasts>,
 ) {
+    let Some(interval) = preferences.dock_autosave_interval() else {
+        return;
+    };
+    if save_timer.timer.duration() != interval {
+        save_timer.timer.set_duration(interval);
+        save_timer.timer.reset();
+    }
+
     save_timer.timer.tick(time.delta());
 
-    if save_timer.timer.just_finished() {
-        save_dock_state(&dock_resource.tree);
+    if !save_timer.timer.just_finished() {
+        return;
+    }
+    let serialized = match serialize_dock_state(dock_resource.layout_to_save()) {
+        Ok(serialized) => serialized,
+        Err(err) => {
+            warn!("Failed to serialize dock layout: {}", err);
+            return;
+        }
+    };
+    if save_timer.last_saved.as_ref() == Some(&serialized) {
+        return;
     }
-}
 
-pub fn save_on_exit(
-    dock_resource: Res<DockResource>,
-    mut exit_events: MessageReader<bevy::app::AppExit>,
-) {
-    for _ in exit_events.read() {
-        save_dock_state(&dock_resource.tree);
-        info!("Saved dock state on exit");
+    match fs::write(dock_state_path(), &serialized) {
+        Ok(()) => {
+            if std::mem::take(&mut save_timer.failing) {
+                info!("Saving {} works again", DOCK_STATE_FILE);
+                toasts.info("Layout saved");
+            }
+            save_timer.last_saved = Some(serialized);
+        }
+        Err(err) => {
+            if !std::mem::replace(&mut save_timer.failing, true) {
+                warn!("Failed to save {}: {}", DOCK_STATE_FILE, err);
+                toasts.error(format!("Could not save the layout: {}. Retrying in the background.", err));
+            }
+        }
     }
 }
 
-pub fn show_windows_menu(ui: &mut bevy_egui::egui::Ui, dock_resource: &mut DockResource, _global_ui_state: &crate::ui::GlobalUiState) {
-    // List of dynamic windows that can be toggled
+pub fn show_windows_menu(
+    ui: &mut bevy_egui::egui::Ui,
+    dock_resource: &mut DockResource,
+    _global_ui_state: &crate::ui::GlobalUiState,
+    toasts: &mut crate::toast::Toasts,
+) {
+    // List of dynamic windows that can be toggled. Panels with a `sole_means_of` are listed
+    // so they can always be brought back after being closed.
     let dynamic_windows = [
+        Panel::Modes,
+        Panel::NameTypeEditor,
         Panel::GenomeEditor,
+        Panel::CellTypeSettings,
+        Panel::LibrarySearch,
+        Panel::Assets,
+        Panel::Settings,
         Panel::CellInspector,
         Panel::SceneManager,
         Panel::TimeScrubber,
@@ -228,14 +865,18 @@ pub fn show_windows_menu(ui: &mut bevy_egui::egui::Ui, dock_resource: &mut DockR
         Panel::LightingSettings,
     ];
 
+    let last_essential = dock_resource.last_essential_panels();
     for panel in &dynamic_windows {
         let is_open = is_panel_open(&dock_resource.tree, panel);
 
         if ui.selectable_label(is_open, format!("{}", panel)).clicked() {
-            if is_open {
-                close_panel(&mut dock_resource.tree, panel);
+            if is_open && last_essential.contains(panel) {
+                dock_resource.confirm_close = Some(panel.clone());
+            } else if is_open {
+                dock_resource.close_panel(panel);
             } else {
                 open_panel(&mut dock_resource.tree, panel);
+                dock_resource.forget_closed(panel);
             }
             ui.close();
         }
@@ -253,4 +894,484 @@ pub fn show_windows_menu(ui: &mut bevy_egui::egui::Ui, dock_resource: &mut DockR
         dock_resource.all_hidden = !dock_resource.all_hidden;
         ui.close();
     }
+
+    if dock_resource.solo_panel().is_some() {
+        if ui.button("Exit Solo").clicked() {
+            dock_resource.exit_solo();
+            ui.close();
+        }
+    } else if ui.add(bevy_egui::egui::Button::new("Solo Focused Panel").shortcut_text(SOLO_SHORTCUT_TEXT)).clicked() {
+        solo_focused_panel(dock_resource);
+        ui.close();
+    }
+    let can_duplicate = dock_resource.solo_panel().is_none();
+    let duplicate_button = bevy_egui::egui::Button::new("Duplicate Focused Panel").shortcut_text(DUPLICATE_SHORTCUT_TEXT);
+    if ui.add_enabled(can_duplicate, duplicate_button).clicked() {
+        duplicate_focused_panel(dock_resource);
+        ui.close();
+    }
+
+    let reopen_button = bevy_egui::egui::Button::new("Reopen Closed Panel").shortcut_text(REOPEN_SHORTCUT_TEXT);
+    let mut reopen = ui.add_enabled(dock_resource.last_closed().is_some(), reopen_button);
+    if let Some(panel) = dock_resource.last_closed() {
+        reopen = reopen.on_hover_text(format!("Reopen {}", panel));
+    }
+    if reopen.clicked() {
+        dock_resource.reopen_closed();
+        ui.close();
+    }
+
+    ui.separator();
+    ui.menu_button("Layout Map", |ui| show_layout_minimap(ui, dock_resource));
+    if ui.button("Reset Layout...").clicked() {
+        dock_resource.confirm_reset = true;
+        ui.close();
+    }
+    if dock_resource.can_undo_reset() && ui.button("Undo Reset").clicked() {
+        if let Err(err) = dock_resource.undo_reset() {
+            warn!("Failed to restore {}: {}", DOCK_STATE_BACKUP_FILE, err);
+            toasts.error(format!("Could not restore the previous layout: {}", err));
+        } else {
+            toasts.info("Restored the previous layout");
+        }
+        ui.close();
+    }
+}
+
+/// Confirmation before the last copy of a panel with a `sole_means_of` is closed
+pub fn show_close_panel_dialog(ctx: &bevy_egui::egui::Context, dock_resource: &mut DockResource) {
+    let Some(panel) = dock_resource.confirm_close.clone() else {
+        return;
+    };
+    let result = crate::widgets::modal_dialog(ctx, &format!("Close {}?", panel), |ui| {
+        ui.label(format!(
+            "This is the only {} panel, and without it there is no way to {}.",
+            panel,
+            panel.sole_means_of().unwrap_or("use it"),
+        ));
+        ui.label("It can be reopened from the Windows menu.");
+        None
+    });
+
+    match result {
+        crate::widgets::DialogResult::Open => {}
+        crate::widgets::DialogResult::Confirmed => {
+            dock_resource.confirm_close = None;
+            dock_resource.close_panel(&panel);
+        }
+        crate::widgets::DialogResult::Cancelled => dock_resource.confirm_close = None,
+    }
+}
+
+/// Confirmation for "Reset Layout"; the current layout is backed up before it is replaced
+pub fn show_reset_layout_dialog(ctx: &bevy_egui::egui::Context, dock_resource: &mut DockResource, toasts: &mut crate::toast::Toasts) {
+    let result = crate::widgets::modal_dialog(ctx, "Reset Layout", |ui| {
+        ui.label("Replace the current layout with the default one?");
+        ui.label(format!("The current layout is saved to {} and can be restored with Windows > Undo Reset.", DOCK_STATE_BACKUP_FILE));
+        None
+    });
+
+    match result {
+        crate::widgets::DialogResult::Open => {}
+        crate::widgets::DialogResult::Confirmed => {
+            dock_resource.confirm_reset = false;
+            match dock_resource.reset_layout(dock_state_backup_path(), ctx.content_rect().width()) {
+                Ok(()) => toasts.info("Layout reset"),
+                Err(err) => {
+                    warn!("Failed to back up the layout to {}: {}", DOCK_STATE_BACKUP_FILE, err);
+                    toasts.error(format!("Layout not reset, the backup could not be written: {}", err));
+                }
+            }
+        }
+        crate::widgets::DialogResult::Cancelled => dock_resource.confirm_reset = false,
+    }
+}
+
+/// Screen-space rect of every leaf in `tree`, splitting `rect` by each split node's fraction
+pub fn layout_regions(tree: &egui_dock::Tree<DockTab>, rect: bevy_egui::egui::Rect) -> Vec<(egui_dock::NodeIndex, bevy_egui::egui::Rect)> {
+    use egui_dock::{Node, NodeIndex};
+
+    fn walk(tree: &egui_dock::Tree<DockTab>, node: NodeIndex, rect: bevy_egui::egui::Rect, regions: &mut Vec<(NodeIndex, bevy_egui::egui::Rect)>) {
+        if node.0 >= tree.len() {
+            return;
+        }
+        match &tree[node] {
+            Node::Empty => {}
+            Node::Leaf(_) => regions.push((node, rect)),
+            Node::Horizontal(split) => {
+                let x = rect.left() + rect.width() * split.fraction;
+                walk(tree, node.left(), bevy_egui::egui::Rect::from_x_y_ranges(rect.left()..=x, rect.y_range()), regions);
+                walk(tree, node.right(), bevy_egui::egui::Rect::from_x_y_ranges(x..=rect.right(), rect.y_range()), regions);
+            }
+            Node::Vertical(split) => {
+                let y = rect.top() + rect.height() * split.fraction;
+                walk(tree, node.left(), bevy_egui::egui::Rect::from_x_y_ranges(rect.x_range(), rect.top()..=y), regions);
+                walk(tree, node.right(), bevy_egui::egui::Rect::from_x_y_ranges(rect.x_range(), y..=rect.bottom()), regions);
+            }
+        }
+    }
+
+    let mut regions = Vec::new();
+    walk(tree, NodeIndex::root(), rect, &mut regions);
+    regions
+}
+
+/// Schematic of the main dock surface. Each region shows its active tab; clicking one focuses it.
+pub fn show_layout_minimap(ui: &mut bevy_egui::egui::Ui, dock_resource: &mut DockResource) {
+    use bevy_egui::egui;
+
+    let width = 220.0;
+    let aspect = ui.ctx().content_rect().aspect_ratio().clamp(1.0, 2.5);
+    let (rect, _) = ui.allocate_exact_size(egui::vec2(width, width / aspect), egui::Sense::hover());
+    let painter = ui.painter_at(rect);
+    let visuals = ui.visuals().clone();
+    let focused = dock_resource.tree.focused_leaf();
+    let surface = dock_resource.tree.main_surface();
+
+    let mut clicked = None;
+    for (node, region) in layout_regions(surface, rect) {
+        let Some(leaf) = surface[node].get_leaf() else {
+            continue;
+        };
+        let region = region.shrink(1.0);
+        let response = ui.interact(region, ui.id().with(("layout_minimap", node.0)), egui::Sense::click());
+        let is_focused = focused == Some((egui_dock::SurfaceIndex::main(), node));
+        let fill = if is_focused {
+            visuals.selection.bg_fill
+        } else if response.hovered() {
+            visuals.widgets.hovered.bg_fill
+        } else {
+            visuals.widgets.inactive.bg_fill
+        };
+        painter.rect_filled(region, 2.0, fill);
+        painter.rect_stroke(region, 2.0, visuals.widgets.noninteractive.bg_stroke, egui::StrokeKind::Inside);
+
+        let names: Vec<String> = leaf.tabs.iter().map(|tab| tab.panel.to_string()).collect();
+        let active = names.get(leaf.active.0).cloned().unwrap_or_default();
+        let label = if names.len() > 1 { format!("{} +{}", active, names.len() - 1) } else { active };
+        painter.with_clip_rect(region).text(
+            region.center(),
+            egui::Align2::CENTER_CENTER,
+            label,
+            egui::FontId::proportional(10.0),
+            visuals.text_color(),
+        );
+        if response.on_hover_text(names.join("\n")).clicked() {
+            clicked = Some(node);
+        }
+    }
+
+    if let Some(node) = clicked {
+        dock_resource.tree.set_focused_node_and_surface((egui_dock::SurfaceIndex::main(), node));
+        ui.close();
+    }
+}
+
+/// Label for the solo hotkey handled in `ui_system`
+pub const SOLO_SHORTCUT_TEXT: &str = "Ctrl+Shift+F";
+
+/// Solo the active tab of the focused leaf, or leave solo if already soloing
+pub fn solo_focused_panel(dock_resource: &mut DockResource) {
+    if dock_resource.solo_panel().is_some() {
+        dock_resource.exit_solo();
+        return;
+    }
+    let focused = dock_resource.tree.find_active_focused().map(|(_, tab)| tab.clone());
+    match focused {
+        Some(tab) if !tab.panel.is_placeholder() => dock_resource.enter_solo(tab),
+        _ => info!("No panel focused to solo"),
+    }
+}
+
+/// Label for the reopen closed panel hotkey handled in `ui_system`
+pub const REOPEN_SHORTCUT_TEXT: &str = "Ctrl+Shift+T";
+
+/// Label for the duplicate hotkey handled in `ui_system`
+pub const DUPLICATE_SHORTCUT_TEXT: &str = "Ctrl+Shift+D";
+
+/// Open a second copy of the focused panel in a split to its right, pinned to the same mode.
+/// Returns the duplicated panel, or `None` if nothing that can be duplicated is focused.
+pub fn duplicate_focused_panel(dock_resource: &mut DockResource) -> Option<Panel> {
+    // The solo layout is thrown away on exit, and the copy with it
+    if dock_resource.solo_panel().is_some() {
+        info!("Leave solo to duplicate a panel");
+        return None;
+    }
+    let (surface, node) = dock_resource.tree.focused_leaf()?;
+    let tab = dock_resource.tree.find_active_focused().map(|(_, tab)| tab.clone())?;
+    if !tab.panel.can_duplicate() {
+        info!("{} can't be duplicated", tab.panel);
+        return None;
+    }
+    let panel = tab.panel.clone();
+    dock_resource.tree[surface].split_right(node, 0.5, vec![tab.copy()]);
+    info!("Duplicated {}", panel);
+    Some(panel)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unknown_panels_are_dropped_on_load() {
+        let mut tree = DockState::new(vec![DockTab::from(Panel::Viewport), DockTab::from(Panel::Console)]);
+        tree.main_surface_mut().split_left(egui_dock::NodeIndex::root(), 0.5, vec![DockTab::from(Panel::Modes)]);
+        let data = ron::ser::to_string(&tree).unwrap().replace("Console", "RemovedPanel");
+
+        let (loaded, dropped) = parse_dock_state(&data).unwrap();
+
+        assert_eq!(dropped, vec!["RemovedPanel".to_string()]);
+        assert!(is_panel_open(&loaded, &Panel::Viewport));
+        assert!(is_panel_open(&loaded, &Panel::Modes));
+        assert_eq!(loaded.iter_all_tabs().count(), 2);
+    }
+
+    #[test]
+    fn default_panels_are_reopened_after_dropping_tabs() {
+        let tree = DockState::new(vec![DockTab::from(Panel::Console)]);
+        let data = ron::ser::to_string(&tree).unwrap().replace("Console", "RemovedPanel");
+        let (mut loaded, dropped) = parse_dock_state(&data).unwrap();
+        assert_eq!(dropped.len(), 1);
+
+        let reopened = reopen_default_panels(&mut loaded);
+
+        assert!(reopened.contains(&Panel::Viewport));
+        assert!(!reopened.iter().any(Panel::is_placeholder));
+        for (_, tab) in create_default_layout(DEFAULT_LAYOUT_WIDTH).iter_all_tabs().filter(|(_, tab)| !tab.panel.is_placeholder()) {
+            assert!(is_panel_open(&loaded, &tab.panel), "{} was not reopened", tab.panel);
+        }
+    }
+
+    #[test]
+    fn focused_panel_is_duplicated_beside_itself() {
+        let mut dock_resource = DockResource::new(create_default_layout(DEFAULT_LAYOUT_WIDTH));
+        let (node, _) = dock_resource.tree.main_surface().find_tab_from(|tab| tab.panel == Panel::Modes).unwrap();
+        dock_resource.tree.set_focused_node_and_surface((egui_dock::SurfaceIndex::main(), node));
+
+        assert_eq!(duplicate_focused_panel(&mut dock_resource), Some(Panel::Modes));
+        assert_eq!(dock_resource.tree.iter_all_tabs().filter(|(_, tab)| tab.panel == Panel::Modes).count(), 2);
+        // The original and its copy share what was the original's leaf
+        let (original, _) = dock_resource.tree.main_surface().find_tab_from(|tab| tab.panel == Panel::Modes).unwrap();
+        assert_eq!(original, node.left());
+        let copy = dock_resource.tree.main_surface()[node.right()].tabs().unwrap();
+        assert_eq!(copy.len(), 1);
+        assert_eq!(copy[0].panel, Panel::Modes);
+        // A copy is a tab of its own, so it has its own id
+        let (_, modes) = dock_resource.tree.main_surface().find_tab_from(|tab| tab.panel == Panel::Modes).unwrap();
+        assert_ne!(dock_resource.tree.main_surface()[original].tabs().unwrap()[modes.0].id(), copy[0].id());
+
+        let (viewport, _) = dock_resource.tree.main_surface().find_tab_from(|tab| tab.panel == Panel::Viewport).unwrap();
+        dock_resource.tree.set_focused_node_and_surface((egui_dock::SurfaceIndex::main(), viewport));
+        assert_eq!(duplicate_focused_panel(&mut dock_resource), None);
+    }
+
+    #[test]
+    fn closing_the_last_essential_panel_needs_confirmation() {
+        let mut dock_resource = DockResource::new(create_default_layout(DEFAULT_LAYOUT_WIDTH));
+        assert!(dock_resource.last_essential_panels().contains(&Panel::Modes));
+        assert!(!dock_resource.last_essential_panels().contains(&Panel::Viewport));
+
+        let (node, _) = dock_resource.tree.main_surface().find_tab_from(|tab| tab.panel == Panel::Modes).unwrap();
+        dock_resource.tree.set_focused_node_and_surface((egui_dock::SurfaceIndex::main(), node));
+        duplicate_focused_panel(&mut dock_resource);
+        assert!(!dock_resource.last_essential_panels().contains(&Panel::Modes));
+    }
+
+    #[test]
+    fn closed_panels_reopen_where_they_were() {
+        let mut tree = create_default_layout(DEFAULT_LAYOUT_WIDTH);
+        let (surface, node, _) = tree.find_tab_from(|tab| tab.panel == Panel::Modes).unwrap();
+        tree.set_focused_node_and_surface((surface, node));
+        open_panel(&mut tree, &Panel::Settings);
+        let layout_before = serialize_dock_state(&tree).unwrap();
+        let mut dock_resource = DockResource::new(tree);
+
+        // Settings shares a leaf with Modes, which is then alone in a split of its own
+        dock_resource.close_panel(&Panel::Settings);
+        dock_resource.close_panel(&Panel::Modes);
+        assert!(!is_panel_open(&dock_resource.tree, &Panel::Modes));
+        assert_eq!(dock_resource.last_closed(), Some(&Panel::Modes));
+
+        assert_eq!(dock_resource.reopen_closed(), Some(Panel::Modes));
+        assert_eq!(dock_resource.reopen_closed(), Some(Panel::Settings));
+        assert_eq!(dock_resource.reopen_closed(), None);
+        assert_eq!(serialize_dock_state(&dock_resource.tree).unwrap(), layout_before);
+
+        // Opening a panel from the Windows menu takes it off the stack
+        dock_resource.close_panel(&Panel::Settings);
+        open_panel(&mut dock_resource.tree, &Panel::Settings);
+        dock_resource.forget_closed(&Panel::Settings);
+        assert_eq!(dock_resource.last_closed(), None);
+    }
+
+    #[test]
+    fn closing_a_copy_closes_that_copy() {
+        let mut dock_resource = DockResource::new(create_default_layout(DEFAULT_LAYOUT_WIDTH));
+        let (node, _) = dock_resource.tree.main_surface().find_tab_from(|tab| tab.panel == Panel::Modes).unwrap();
+        dock_resource.tree.set_focused_node_and_surface((egui_dock::SurfaceIndex::main(), node));
+        duplicate_focused_panel(&mut dock_resource);
+        let ids = |dock_resource: &DockResource| -> Vec<TabId> {
+            dock_resource.tree.iter_all_tabs().filter(|(_, tab)| tab.panel == Panel::Modes).map(|(_, tab)| tab.id()).collect()
+        };
+        let [original, copy] = ids(&dock_resource)[..] else {
+            panic!("expected two copies of Modes");
+        };
+
+        let second = dock_resource.tree.iter_all_tabs().find(|(_, tab)| tab.id() == copy).map(|(_, tab)| tab.clone()).unwrap();
+        dock_resource.close_tab(&second);
+        assert_eq!(ids(&dock_resource), vec![original]);
+
+        dock_resource.reopen_closed();
+        assert_eq!(ids(&dock_resource), vec![original, copy]);
+    }
+
+    #[test]
+    fn copies_of_a_panel_keep_their_own_pins() {
+        let pinned = |mode| DockTab { pinned_mode: Some(mode), ..DockTab::from(Panel::ParentSettings) };
+        let mut tree = DockState::new(vec![DockTab::from(Panel::Viewport)]);
+        tree.main_surface_mut().split_left(egui_dock::NodeIndex::root(), 0.5, vec![pinned(1), pinned(2), pinned(4)]);
+        let mut dock_resource = DockResource::new(tree);
+        let pins = |dock_resource: &DockResource| -> Vec<Option<usize>> {
+            dock_resource.tree.iter_all_tabs()
+                .filter(|(_, tab)| tab.panel == Panel::ParentSettings)
+                .map(|(_, tab)| tab.pinned_mode)
+                .collect()
+        };
+
+        dock_resource.unpin_missing_modes(3);
+        assert_eq!(pins(&dock_resource), vec![Some(1), Some(2), None]);
+
+        // Modes 0 and 1 swapped places and mode 2 was deleted
+        dock_resource.remap_pinned_modes(&[Some(1), Some(0), None]);
+        assert_eq!(pins(&dock_resource), vec![Some(0), None, None]);
+
+        // Layouts are saved without pins, in the same format as before tabs had them
+        let data = serialize_dock_state(&dock_resource.tree).unwrap();
+        let (loaded, dropped) = parse_dock_state(&data).unwrap();
+        assert!(dropped.is_empty());
+        assert_eq!(loaded.iter_all_tabs().filter(|(_, tab)| tab.panel == Panel::ParentSettings && tab.pinned_mode.is_none()).count(), 3);
+    }
+
+    #[test]
+    fn reset_layout_can_be_undone_from_the_backup() {
+        let backup = std::env::temp_dir().join(format!("dock_state_reset_test_{}.ron.bak", std::process::id()));
+        let mut customized = create_default_layout(DEFAULT_LAYOUT_WIDTH);
+        open_panel(&mut customized, &Panel::Console);
+        let mut dock_resource = DockResource::new(customized);
+        assert!(!dock_resource.can_undo_reset());
+
+        dock_resource.reset_layout(backup.clone(), DEFAULT_LAYOUT_WIDTH).unwrap();
+        assert!(!is_panel_open(&dock_resource.tree, &Panel::Console));
+        assert!(dock_resource.can_undo_reset());
+
+        dock_resource.undo_reset().unwrap();
+        std::fs::remove_file(&backup).unwrap();
+        assert!(is_panel_open(&dock_resource.tree, &Panel::Console));
+        assert!(!dock_resource.can_undo_reset());
+    }
+
+    #[test]
+    fn missing_placeholders_are_restored() {
+        let mut tree = create_default_layout(DEFAULT_LAYOUT_WIDTH);
+        let bottom = tree.find_tab_from(|tab| tab.panel == Panel::BottomPanel).unwrap();
+        tree.remove_tab(bottom);
+        let (node, tab) = tree.main_surface().find_tab_from(|tab| tab.panel == Panel::RightPanel).unwrap();
+        let right = tree.main_surface_mut().remove_tab((node, tab)).unwrap();
+        tree.add_window(vec![right]);
+        assert_eq!(missing_placeholders(&tree), vec![Panel::BottomPanel, Panel::RightPanel]);
+
+        let restored = restore_placeholders(&mut tree);
+
+        assert_eq!(restored, vec![Panel::BottomPanel, Panel::RightPanel]);
+        assert!(missing_placeholders(&tree).is_empty());
+        assert_eq!(tree.iter_all_tabs().filter(|(_, tab)| tab.panel == Panel::RightPanel).count(), 1);
+    }
+
+    #[test]
+    fn default_layout_panels_get_their_pixel_widths() {
+        use bevy_egui::egui::{pos2, Rect};
+
+        let width_of = |tree: &DockState<DockTab>, window_width: f32, panel: Panel| {
+            let (node, _) = tree.main_surface().find_tab_from(|tab| tab.panel == panel).unwrap();
+            let area = Rect::from_min_max(pos2(0.0, 0.0), pos2(window_width, 1000.0));
+            let regions = layout_regions(tree.main_surface(), area);
+            regions.iter().find(|(index, _)| *index == node).unwrap().1.width()
+        };
+
+        for window_width in [1920.0, 2560.0] {
+            let tree = create_default_layout(window_width);
+            assert!((width_of(&tree, window_width, Panel::Modes) - MODES_WIDTH).abs() < 0.5);
+            assert!((width_of(&tree, window_width, Panel::LeftPanel) - LEFT_PANEL_WIDTH).abs() < 0.5);
+            assert!((width_of(&tree, window_width, Panel::RightPanel) - RIGHT_PANEL_WIDTH).abs() < 0.5);
+            let viewport = width_of(&tree, window_width, Panel::Viewport);
+            assert!((viewport - (window_width - MODES_WIDTH - LEFT_PANEL_WIDTH - RIGHT_PANEL_WIDTH)).abs() < 0.5);
+        }
+
+        // Too narrow for the default widths: the panels shrink and the viewport keeps its share
+        let tree = create_default_layout(1000.0);
+        let viewport = width_of(&tree, 1000.0, Panel::Viewport);
+        assert!((viewport - 1000.0 * MIN_VIEWPORT_SHARE).abs() < 0.5);
+        let left = width_of(&tree, 1000.0, Panel::LeftPanel);
+        assert!((left / width_of(&tree, 1000.0, Panel::Modes) - LEFT_PANEL_WIDTH / MODES_WIDTH).abs() < 0.01);
+    }
+
+    /// Minimum width of the split `panel` is the left side of
+    fn min_width_of(tree: &DockState<DockTab>, panel: Panel) -> Option<f32> {
+        let (node, _) = tree.main_surface().find_tab_from(|tab| tab.panel == panel).unwrap();
+        match &tree.main_surface()[node.parent()?] {
+            egui_dock::Node::Horizontal(split) => split.absolute_size_left,
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn default_layout_sets_the_left_panel_minimum() {
+        let tree = create_default_layout(1920.0);
+        assert_eq!(min_width_of(&tree, Panel::LeftPanel), Some(LEFT_PANEL_MIN_WIDTH));
+        assert_eq!(min_width_of(&tree, Panel::Modes), Some(MODES_MIN_WIDTH));
+        // Never more than the panel starts out with
+        let narrow = min_width_of(&create_default_layout(800.0), Panel::LeftPanel).unwrap();
+        assert!(narrow < LEFT_PANEL_MIN_WIDTH && narrow > 0.0);
+    }
+
+    #[test]
+    fn panel_minimums_survive_saving_and_old_layouts_get_them() {
+        let narrow = create_default_layout(400.0);
+        let (loaded, _) = parse_dock_state(&serialize_dock_state(&narrow).unwrap()).unwrap();
+        for panel in [Panel::Modes, Panel::LeftPanel] {
+            assert_eq!(min_width_of(&loaded, panel.clone()), min_width_of(&narrow, panel));
+        }
+
+        // Saved before minimums were stored, with the Modes strip dragged shut
+        let mut old = create_default_layout(DEFAULT_LAYOUT_WIDTH);
+        for node in old.main_surface_mut().iter_mut() {
+            if let egui_dock::Node::Horizontal(split) = node {
+                split.absolute_size_left = None;
+            }
+        }
+        if let egui_dock::Node::Horizontal(split) = &mut old.main_surface_mut()[egui_dock::NodeIndex::root()] {
+            split.fraction = 0.0;
+        }
+        let (loaded, _) = parse_dock_state(&serialize_dock_state(&old).unwrap()).unwrap();
+        assert_eq!(min_width_of(&loaded, Panel::Modes), Some(MODES_MIN_WIDTH));
+        assert_eq!(min_width_of(&loaded, Panel::LeftPanel), Some(LEFT_PANEL_MIN_WIDTH));
+    }
+
+    #[test]
+    fn layout_regions_follow_split_fractions() {
+        use bevy_egui::egui::{pos2, Rect};
+
+        let mut tree = DockState::new(vec![DockTab::from(Panel::Viewport)]);
+        let [viewport, _] = tree.main_surface_mut().split_left(egui_dock::NodeIndex::root(), 0.25, vec![DockTab::from(Panel::Modes)]);
+        tree.main_surface_mut().split_below(viewport, 0.5, vec![DockTab::from(Panel::Console)]);
+
+        let area = Rect::from_min_max(pos2(0.0, 0.0), pos2(100.0, 40.0));
+        let regions: Vec<Rect> = layout_regions(tree.main_surface(), area).into_iter().map(|(_, rect)| rect).collect();
+
+        assert_eq!(regions.len(), 3);
+        assert!(regions.contains(&Rect::from_min_max(pos2(0.0, 0.0), pos2(25.0, 40.0))));
+        assert!(regions.contains(&Rect::from_min_max(pos2(25.0, 0.0), pos2(100.0, 20.0))));
+        assert!(regions.contains(&Rect::from_min_max(pos2(25.0, 20.0), pos2(100.0, 40.0))));
+    }
 }
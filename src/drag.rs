@@ -1,14 +1,76 @@
 use bevy::prelude::*;
 use bevy::window::PrimaryWindow;
 use bevy_egui::EguiContext;
+use std::ops::RangeInclusive;
+use crate::genome::{ranges, AdhesionSettings, CurrentGenome};
+use crate::preferences::Preferences;
 use crate::scene::DraggableSphere;
 use crate::ui::ViewportRect;
 
 #[derive(Resource, Default)]
-struct DragState {
-    dragging: Option<Entity>,
+pub struct DragState {
+    pub dragging: Option<Entity>,
+    /// Last sphere picked up, which stays selected after it's dropped
+    pub selected: Option<Entity>,
     drag_offset: Vec3,
-    drag_plane_distance: f32,
+    /// Plane the grabbed point moves in: through the hit point, facing the camera at pick time
+    drag_plane_point: Vec3,
+    drag_plane_normal: Vec3,
+    /// Farthest from the camera the grabbed point can go, from [`drag_reach`] at pick time
+    drag_reach: f32,
+    /// Sphere let go of this frame
+    released: Option<Entity>,
+}
+
+/// Farthest a sphere can be dragged from the camera, however close it was picked up
+const MIN_DRAG_REACH: f32 = 50.0;
+/// A sphere picked up farther away can be dragged this many times its pick distance away
+const DRAG_REACH_PER_PICK_DISTANCE: f32 = 2.0;
+/// Below this |cos| between the cursor ray and the drag plane's normal, the ray is treated as
+/// parallel to the plane and the sphere stays put
+const MIN_DRAG_RAY_COS: f32 = 0.05;
+
+/// How strongly a dragged sphere glows in its own base color
+const DRAG_HIGHLIGHT_EMISSIVE: f32 = 0.6;
+
+/// A dropped sphere only settles against a neighbour within this many rest distances
+const SETTLE_RANGE: f32 = 2.0;
+/// Give up on a spring that hasn't come to rest by then
+const SETTLE_MAX_SECS: f32 = 3.0;
+/// Fixed spring step, small enough for stiff adhesion settings
+const SETTLE_SUBSTEP: f32 = 1.0 / 240.0;
+
+/// Dropped sphere springing towards its rest distance from `partner` (visual only)
+#[derive(Component)]
+struct Settling {
+    partner: Entity,
+    velocity: Vec3,
+    elapsed: f32,
+}
+
+/// Sphere stuck to `partner` by the selected mode's adhesion after being dropped next to it
+#[derive(Component)]
+struct Adhered {
+    partner: Entity,
+    /// Center distance the spring is slack at: the rest distance, or the drop distance if farther
+    slack_length: f32,
+}
+
+/// Outline marking where a dragged sphere will snap to
+const SNAP_MARKER_COLOR: Color = Color::srgba(1.0, 1.0, 1.0, 0.6);
+
+/// Adhesion spring colors
+const BREAKABLE_ADHESION_COLOR: Color = Color::srgb(1.0, 0.6, 0.2);
+const UNBREAKABLE_ADHESION_COLOR: Color = Color::srgb(0.3, 0.8, 1.0);
+/// Coils drawn on the softest and the stiffest adhesion spring
+const ADHESION_COILS: RangeInclusive<f32> = 3.0..=16.0;
+/// Sideways reach of the spring's zigzag as a fraction of the smaller sphere's radius
+const ADHESION_COIL_WIDTH: f32 = 0.2;
+
+/// Material a sphere had before it was picked up, put back when the drag ends
+#[derive(Component)]
+struct DragHighlight {
+    original: Handle<StandardMaterial>,
 }
 
 pub struct DragPlugin;
@@ -16,7 +78,17 @@ pub struct DragPlugin;
 impl Plugin for DragPlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<DragState>()
-            .add_systems(Update, (handle_mouse_input, update_drag_position).chain());
+            .add_systems(Update, (
+                handle_mouse_input,
+                update_drag_position,
+                highlight_dragged_sphere,
+                draw_snap_marker,
+                snap_released_sphere,
+                start_settling,
+                update_settling,
+                break_adhesions,
+                draw_adhesions,
+            ).chain());
     }
 }
 
@@ -25,7 +97,7 @@ fn handle_mouse_input(
     mouse_button: Res<ButtonInput<MouseButton>>,
     windows: Query<&Window, With<PrimaryWindow>>,
     camera_query: Query<(&Camera, &GlobalTransform), With<Camera3d>>,
-    sphere_query: Query<(Entity, &GlobalTransform), With<DraggableSphere>>,
+    sphere_query: Query<(Entity, &GlobalTransform, &DraggableSphere)>,
     viewport_rect: Res<ViewportRect>,
     mut egui_context: Query<&mut EguiContext>,
 ) {
@@ -59,20 +131,18 @@ fn handle_mouse_input(
                     return;
                 }
 
-                // Raycast to check if we hit the sphere
+                // Raycast to pick the nearest sphere under the cursor
                 if let Ok(ray) = camera.viewport_to_world(camera_transform, cursor_position) {
-                    // Check if ray hits any sphere
-                    for (entity, sphere_transform) in sphere_query.iter() {
-                        let sphere_pos = sphere_transform.translation();
-                        let sphere_radius = 0.5;
-
-                        if let Some(distance) = ray_sphere_intersection(ray.origin, *ray.direction, sphere_pos, sphere_radius) {
-                            let hit_point = ray.origin + *ray.direction * distance;
-                            drag_state.dragging = Some(entity);
-                            drag_state.drag_offset = sphere_pos - hit_point;
-                            drag_state.drag_plane_distance = distance;
-                            break;
-                        }
+                    let spheres = sphere_query.iter()
+                        .map(|(entity, sphere_transform, sphere)| ((entity, sphere_transform.translation()), sphere_transform.translation(), sphere.radius));
+                    if let Some(((entity, sphere_pos), distance)) = nearest_sphere_hit(ray.origin, *ray.direction, spheres) {
+                        let hit_point = ray.origin + *ray.direction * distance;
+                        drag_state.dragging = Some(entity);
+                        drag_state.selected = Some(entity);
+                        drag_state.drag_offset = sphere_pos - hit_point;
+                        drag_state.drag_plane_point = hit_point;
+                        drag_state.drag_plane_normal = *camera_transform.forward();
+                        drag_state.drag_reach = drag_reach(distance);
                     }
                 }
             }
@@ -81,40 +151,370 @@ fn handle_mouse_input(
 
     // Handle mouse release - stop dragging
     if mouse_button.just_released(MouseButton::Left) {
-        drag_state.dragging = None;
+        drag_state.released = drag_state.dragging.take();
     }
 }
 
 fn update_drag_position(
     drag_state: Res<DragState>,
     windows: Query<&Window, With<PrimaryWindow>>,
-    camera_query: Query<(&Camera, &GlobalTransform), With<Camera3d>>,
-    mut sphere_query: Query<&mut Transform, With<DraggableSphere>>,
+    camera_query: Query<(&Camera, &GlobalTransform, &Projection), With<Camera3d>>,
+    mut sphere_query: Query<(&mut Transform, &DraggableSphere)>,
+    preferences: Res<Preferences>,
 ) {
     if let Some(dragged_entity) = drag_state.dragging {
         let Ok(window) = windows.single() else {
             return;
         };
 
-        let Ok((camera, camera_transform)) = camera_query.single() else {
+        let Ok((camera, camera_transform, projection)) = camera_query.single() else {
             return;
         };
 
-        if let Some(cursor_position) = window.cursor_position() {
-            if let Ok(ray) = camera.viewport_to_world(camera_transform, cursor_position) {
-                // Project cursor onto a plane at the drag distance
-                let new_position = ray.origin + *ray.direction * drag_state.drag_plane_distance;
-
-                if let Ok(mut transform) = sphere_query.get_mut(dragged_entity) {
-                    transform.translation = new_position + drag_state.drag_offset;
-                    // Clamp Y position to stay above ground
-                    transform.translation.y = transform.translation.y.max(0.5);
-                }
+        if let Some(cursor_position) = window.cursor_position()
+            && let Ok(ray) = camera.viewport_to_world(camera_transform, cursor_position)
+            && let Ok((mut transform, sphere)) = sphere_query.get_mut(dragged_entity)
+        {
+            let near = match projection {
+                Projection::Perspective(perspective) => perspective.near,
+                _ => 0.0,
+            };
+            // Grazing the plane: hold the sphere where it is rather than fling it
+            let Some(distance) = drag_plane_distance(
+                ray.origin,
+                *ray.direction,
+                drag_state.drag_plane_point,
+                drag_state.drag_plane_normal,
+                near + sphere.radius,
+                drag_state.drag_reach,
+            ) else {
+                return;
+            };
+            transform.translation = ray.origin + *ray.direction * distance + drag_state.drag_offset;
+            // Keep the sphere resting on the ground rather than sinking into it
+            if preferences.clamp_drag_to_ground {
+                transform.translation.y = transform.translation.y.max(preferences.ground_height + sphere.radius);
             }
         }
     }
 }
 
+/// Nearest grid point to `position`, kept `min_y` or higher so a clamped sphere doesn't snap into the ground
+fn snap_to_grid(position: Vec3, spacing: f32, min_y: Option<f32>) -> Vec3 {
+    let mut snapped = (position / spacing).round() * spacing;
+    if let Some(min_y) = min_y {
+        snapped.y = snapped.y.max(min_y);
+    }
+    snapped
+}
+
+/// Lowest a sphere of `radius` may be dropped, if dragging is kept above ground
+fn drop_min_y(preferences: &Preferences, radius: f32) -> Option<f32> {
+    preferences.clamp_drag_to_ground.then_some(preferences.ground_height + radius)
+}
+
+/// Outline the dragged sphere at the grid point it would snap to if let go now
+fn draw_snap_marker(
+    mut gizmos: Gizmos,
+    drag_state: Res<DragState>,
+    preferences: Res<Preferences>,
+    sphere_query: Query<(&Transform, &DraggableSphere)>,
+) {
+    let Some(spacing) = preferences.drag_grid_spacing() else {
+        return;
+    };
+    if !preferences.show_drag_snap_marker {
+        return;
+    }
+    let Some(Ok((transform, sphere))) = drag_state.dragging.map(|entity| sphere_query.get(entity)) else {
+        return;
+    };
+    let target = snap_to_grid(transform.translation, spacing, drop_min_y(&preferences, sphere.radius));
+    gizmos.sphere(Isometry3d::from_translation(target), sphere.radius, SNAP_MARKER_COLOR);
+    gizmos.cross(Isometry3d::from_translation(target), sphere.radius * 0.25, SNAP_MARKER_COLOR);
+}
+
+/// Move a sphere let go of this frame onto the grid, before it looks for a neighbour to settle against
+fn snap_released_sphere(
+    drag_state: Res<DragState>,
+    preferences: Res<Preferences>,
+    mut sphere_query: Query<(&mut Transform, &DraggableSphere)>,
+) {
+    let Some(spacing) = preferences.drag_grid_spacing() else {
+        return;
+    };
+    if let Some(released) = drag_state.released
+        && let Ok((mut transform, sphere)) = sphere_query.get_mut(released)
+    {
+        transform.translation = snap_to_grid(transform.translation, spacing, drop_min_y(&preferences, sphere.radius));
+    }
+}
+
+type SphereMaterialItem<'a> = (Entity, &'a mut MeshMaterial3d<StandardMaterial>, Option<&'a DragHighlight>);
+
+/// Swap the dragged sphere to a glowing copy of its material, restoring the original on release
+fn highlight_dragged_sphere(
+    mut commands: Commands,
+    drag_state: Res<DragState>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut sphere_query: Query<SphereMaterialItem, With<DraggableSphere>>,
+) {
+    for (entity, mut material, highlight) in sphere_query.iter_mut() {
+        let is_dragged = drag_state.dragging == Some(entity);
+        match (is_dragged, highlight) {
+            (true, None) => {
+                let Some(mut highlighted) = materials.get(&material.0).cloned() else {
+                    continue;
+                };
+                highlighted.emissive = highlighted.base_color.to_linear() * DRAG_HIGHLIGHT_EMISSIVE;
+                let original = std::mem::replace(&mut material.0, materials.add(highlighted));
+                commands.entity(entity).insert(DragHighlight { original });
+            }
+            (false, Some(highlight)) => {
+                // The highlight material is freed once its last handle is dropped here
+                material.0 = highlight.original.clone();
+                commands.entity(entity).remove::<DragHighlight>();
+            }
+            _ => {}
+        }
+    }
+}
+
+/// The sphere the ray hits first, with its hit distance. Spheres are `(id, center, radius)`.
+fn nearest_sphere_hit<T>(
+    ray_origin: Vec3,
+    ray_direction: Vec3,
+    spheres: impl Iterator<Item = (T, Vec3, f32)>,
+) -> Option<(T, f32)> {
+    spheres
+        .filter_map(|(id, center, radius)| {
+            ray_sphere_intersection(ray_origin, ray_direction, center, radius).map(|distance| (id, distance))
+        })
+        .min_by(|(_, a), (_, b)| a.total_cmp(b))
+}
+
+/// How far from the camera a sphere picked up `pick_distance` away can be dragged, so a sphere
+/// in a large scene doesn't jump towards the camera as soon as it's moved
+fn drag_reach(pick_distance: f32) -> f32 {
+    MIN_DRAG_REACH.max(pick_distance * DRAG_REACH_PER_PICK_DISTANCE)
+}
+
+/// Distance along a unit-length ray to the drag plane, kept between `min_distance` and
+/// `max_distance` so a plane that ends up behind the camera holds the sphere just in front
+/// of it. None when the ray is nearly parallel to the plane.
+fn drag_plane_distance(
+    ray_origin: Vec3,
+    ray_direction: Vec3,
+    plane_point: Vec3,
+    plane_normal: Vec3,
+    min_distance: f32,
+    max_distance: f32,
+) -> Option<f32> {
+    let cos = ray_direction.dot(plane_normal);
+    if cos.abs() < MIN_DRAG_RAY_COS {
+        return None;
+    }
+    let distance = (plane_point - ray_origin).dot(plane_normal) / cos;
+    Some(distance.clamp(min_distance, max_distance.max(min_distance)))
+}
+
+/// Center distance a dropped sphere settles at: the rest length, but never overlapping
+fn settle_distance(rest_length: f32, radius: f32, partner_radius: f32) -> f32 {
+    rest_length.max(radius + partner_radius)
+}
+
+/// Start settling a just-dropped sphere against its nearest neighbour, if one is close enough
+fn start_settling(
+    mut commands: Commands,
+    mut drag_state: ResMut<DragState>,
+    preferences: Res<Preferences>,
+    current_genome: Res<CurrentGenome>,
+    sphere_query: Query<(Entity, &Transform, &DraggableSphere)>,
+) {
+    let Some(released) = drag_state.released.take() else {
+        return;
+    };
+    let Some(mode) = current_genome.genome.modes.get(current_genome.selected_mode_index as usize) else {
+        return;
+    };
+    let Ok((_, transform, sphere)) = sphere_query.get(released) else {
+        return;
+    };
+
+    let rest_length = mode.adhesion_settings.rest_length;
+    let partner = sphere_query.iter()
+        .filter(|(entity, _, _)| *entity != released)
+        .map(|(entity, other, other_sphere)| {
+            let distance = other.translation.distance(transform.translation);
+            (entity, distance, settle_distance(rest_length, sphere.radius, other_sphere.radius))
+        })
+        .filter(|(_, distance, rest)| *distance <= rest * SETTLE_RANGE)
+        .min_by(|(_, a, _), (_, b, _)| a.total_cmp(b));
+    let Some((partner, distance, rest)) = partner else {
+        return;
+    };
+    commands.entity(released).insert(Adhered { partner, slack_length: distance.max(rest) });
+    if preferences.settle_on_release {
+        commands.entity(released).insert(Settling { partner, velocity: Vec3::ZERO, elapsed: 0.0 });
+    }
+}
+
+/// Advance settling spheres with the selected mode's adhesion spring until they come to rest
+fn update_settling(
+    mut commands: Commands,
+    time: Res<Time>,
+    drag_state: Res<DragState>,
+    preferences: Res<Preferences>,
+    current_genome: Res<CurrentGenome>,
+    mut settling_query: Query<(Entity, &mut Transform, &DraggableSphere, &mut Settling)>,
+    partner_query: Query<(&Transform, &DraggableSphere), Without<Settling>>,
+) {
+    let adhesion = current_genome.genome.modes.get(current_genome.selected_mode_index as usize)
+        .map(|mode| &mode.adhesion_settings);
+
+    for (entity, mut transform, sphere, mut settling) in settling_query.iter_mut() {
+        settling.elapsed += time.delta_secs();
+        let partner = partner_query.get(settling.partner);
+        let (Some(adhesion), Ok((partner_transform, partner_sphere))) = (adhesion, partner) else {
+            commands.entity(entity).remove::<Settling>();
+            continue;
+        };
+        // Picked up again, switched off, or taking too long
+        if drag_state.dragging == Some(entity) || !preferences.settle_on_release || settling.elapsed > SETTLE_MAX_SECS {
+            commands.entity(entity).remove::<Settling>();
+            continue;
+        }
+
+        let rest = settle_distance(adhesion.rest_length, sphere.radius, partner_sphere.radius);
+        let steps = (time.delta_secs() / SETTLE_SUBSTEP).ceil().max(1.0) as usize;
+        let dt = time.delta_secs() / steps as f32;
+        let (mut position, mut velocity) = (transform.translation, settling.velocity);
+        for _ in 0..steps {
+            (position, velocity) = settle_step(
+                position,
+                velocity,
+                partner_transform.translation,
+                rest,
+                adhesion.linear_spring_stiffness,
+                adhesion.linear_spring_damping,
+                dt,
+            );
+        }
+        if preferences.clamp_drag_to_ground {
+            position.y = position.y.max(preferences.ground_height + sphere.radius);
+        }
+        transform.translation = position;
+        settling.velocity = velocity;
+
+        let at_rest = (position.distance(partner_transform.translation) - rest).abs() < 1e-3 && velocity.length() < 1e-2;
+        if at_rest {
+            commands.entity(entity).remove::<Settling>();
+        }
+    }
+}
+
+/// Drop adhesions whose sphere was picked up again or whose partner is gone, and breakable
+/// ones pulled past the selected mode's break force
+fn break_adhesions(
+    mut commands: Commands,
+    drag_state: Res<DragState>,
+    current_genome: Res<CurrentGenome>,
+    adhered_query: Query<(Entity, &Transform, &Adhered)>,
+    partner_query: Query<&Transform, With<DraggableSphere>>,
+) {
+    let adhesion = current_genome.genome.modes.get(current_genome.selected_mode_index as usize)
+        .map(|mode| &mode.adhesion_settings);
+    for (entity, transform, adhered) in adhered_query.iter() {
+        let broken = drag_state.dragging == Some(entity)
+            || match partner_query.get(adhered.partner) {
+                Ok(partner_transform) => adhesion.is_some_and(|adhesion| {
+                    let distance = transform.translation.distance(partner_transform.translation);
+                    adhesion_breaks(adhesion, distance, adhered.slack_length)
+                }),
+                Err(_) => true,
+            };
+        if broken {
+            commands.entity(entity).remove::<Adhered>();
+        }
+    }
+}
+
+/// Whether a spring stretched to `distance` from `slack_length` pulls harder than the adhesion can hold
+fn adhesion_breaks(adhesion: &AdhesionSettings, distance: f32, slack_length: f32) -> bool {
+    adhesion.can_break && adhesion.linear_spring_stiffness * (distance - slack_length) > adhesion.break_force
+}
+
+/// Draw each adhesion as a zigzag spring between the two spheres' surfaces
+fn draw_adhesions(
+    mut gizmos: Gizmos,
+    preferences: Res<Preferences>,
+    current_genome: Res<CurrentGenome>,
+    adhered_query: Query<(&GlobalTransform, &DraggableSphere, &Adhered)>,
+    sphere_query: Query<(&GlobalTransform, &DraggableSphere)>,
+) {
+    let Some(mode) = current_genome.genome.modes.get(current_genome.selected_mode_index as usize) else {
+        return;
+    };
+    if !preferences.show_adhesions {
+        return;
+    }
+    let adhesion = &mode.adhesion_settings;
+    let color = if adhesion.can_break { BREAKABLE_ADHESION_COLOR } else { UNBREAKABLE_ADHESION_COLOR };
+    let stiffness = (adhesion.linear_spring_stiffness - ranges::LINEAR_SPRING_STIFFNESS.start())
+        / (ranges::LINEAR_SPRING_STIFFNESS.end() - ranges::LINEAR_SPRING_STIFFNESS.start());
+    let coils = (ADHESION_COILS.start() + stiffness.clamp(0.0, 1.0) * (ADHESION_COILS.end() - ADHESION_COILS.start())).round() as usize;
+
+    for (transform, sphere, adhered) in adhered_query.iter() {
+        let Ok((partner_transform, partner_sphere)) = sphere_query.get(adhered.partner) else {
+            continue;
+        };
+        let (from, to) = (transform.translation(), partner_transform.translation());
+        let Some(direction) = (to - from).try_normalize() else {
+            continue;
+        };
+        let start = from + direction * sphere.radius;
+        let end = to - direction * partner_sphere.radius;
+        if (end - start).dot(direction) <= 0.0 {
+            continue;
+        }
+        let width = ADHESION_COIL_WIDTH * sphere.radius.min(partner_sphere.radius);
+        gizmos.linestrip(spring_points(start, end, coils, width), color);
+    }
+}
+
+/// Zigzag from `start` to `end` with `coils` full back-and-forth swings of `width` either side
+fn spring_points(start: Vec3, end: Vec3, coils: usize, width: f32) -> Vec<Vec3> {
+    let side = (end - start).normalize_or_zero().any_orthonormal_vector() * width;
+    let segments = coils * 2;
+    (0..=segments)
+        .map(|i| {
+            let along = start.lerp(end, i as f32 / segments as f32);
+            // Straight at both ends so the spring meets the spheres head on
+            let offset = if i == 0 || i == segments { 0.0 } else if i % 2 == 1 { 1.0 } else { -1.0 };
+            along + side * offset
+        })
+        .collect()
+}
+
+/// One semi-implicit Euler step of a unit mass on a damped spring of length `rest` anchored at `anchor`
+fn settle_step(
+    position: Vec3,
+    velocity: Vec3,
+    anchor: Vec3,
+    rest: f32,
+    stiffness: f32,
+    damping: f32,
+    dt: f32,
+) -> (Vec3, Vec3) {
+    let offset = position - anchor;
+    let distance = offset.length();
+    // Coincident centers have no direction to push apart along; pick one
+    let direction = if distance > f32::EPSILON { offset / distance } else { Vec3::Y };
+    let force = -stiffness * (distance - rest) * direction - damping * velocity;
+    let velocity = velocity + force * dt;
+    (position + velocity * dt, velocity)
+}
+
 fn ray_sphere_intersection(
     ray_origin: Vec3,
     ray_direction: Vec3,
@@ -123,18 +523,144 @@ fn ray_sphere_intersection(
 ) -> Option<f32> {
     let oc = ray_origin - sphere_center;
     let a = ray_direction.dot(ray_direction);
+    if a <= f32::EPSILON {
+        // Degenerate direction - there's no ray to cast
+        return None;
+    }
     let b = 2.0 * oc.dot(ray_direction);
     let c = oc.dot(oc) - sphere_radius * sphere_radius;
     let discriminant = b * b - 4.0 * a * c;
 
     if discriminant < 0.0 {
-        None
+        return None;
+    }
+
+    let sqrt_discriminant = discriminant.sqrt();
+    let near = (-b - sqrt_discriminant) / (2.0 * a);
+    let far = (-b + sqrt_discriminant) / (2.0 * a);
+    // A ray starting inside the sphere has its near root behind the origin; it still hits on the way out
+    if near > 0.0 {
+        Some(near)
+    } else if far > 0.0 {
+        Some(far)
     } else {
-        let t = (-b - discriminant.sqrt()) / (2.0 * a);
-        if t > 0.0 {
-            Some(t)
-        } else {
-            None
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ray_missing_sphere() {
+        assert_eq!(ray_sphere_intersection(Vec3::new(0.0, 2.0, -5.0), Vec3::Z, Vec3::ZERO, 1.0), None);
+    }
+
+    #[test]
+    fn ray_tangent_to_sphere() {
+        let t = ray_sphere_intersection(Vec3::new(0.0, 1.0, -5.0), Vec3::Z, Vec3::ZERO, 1.0).unwrap();
+        assert!((t - 5.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn ray_from_outside_hits_near_side() {
+        let t = ray_sphere_intersection(Vec3::new(0.0, 0.0, -5.0), Vec3::Z, Vec3::ZERO, 1.0).unwrap();
+        assert!((t - 4.0).abs() < 1e-5);
+
+        // Unnormalized directions give t in units of the direction's length
+        let t = ray_sphere_intersection(Vec3::new(0.0, 0.0, -5.0), Vec3::Z * 2.0, Vec3::ZERO, 1.0).unwrap();
+        assert!((t - 2.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn ray_from_inside_hits_far_side() {
+        let t = ray_sphere_intersection(Vec3::new(0.0, 0.0, 0.5), Vec3::Z, Vec3::ZERO, 1.0).unwrap();
+        assert!((t - 0.5).abs() < 1e-5);
+    }
+
+    #[test]
+    fn ray_pointing_away_from_sphere() {
+        assert_eq!(ray_sphere_intersection(Vec3::new(0.0, 0.0, 5.0), Vec3::Z, Vec3::ZERO, 1.0), None);
+    }
+
+    #[test]
+    fn nearest_sphere_wins_regardless_of_order() {
+        let spheres = [("far", Vec3::new(0.0, 0.0, 5.0), 1.0), ("near", Vec3::new(0.0, 0.0, 2.0), 0.5), ("off", Vec3::X * 3.0, 1.0)];
+        let (hit, t) = nearest_sphere_hit(Vec3::ZERO, Vec3::Z, spheres.into_iter()).unwrap();
+        assert_eq!(hit, "near");
+        assert!((t - 1.5).abs() < 1e-5);
+    }
+
+    #[test]
+    fn settle_step_comes_to_rest_at_rest_length() {
+        let (mut position, mut velocity) = (Vec3::new(2.5, 0.0, 0.0), Vec3::ZERO);
+        for _ in 0..2400 {
+            (position, velocity) = settle_step(position, velocity, Vec3::ZERO, 1.0, 150.0, 5.0, SETTLE_SUBSTEP);
         }
+        assert!((position.length() - 1.0).abs() < 1e-3);
+        assert!(velocity.length() < 1e-2);
+        // Stays on the line it started on
+        assert!(position.y.abs() < 1e-6 && position.z.abs() < 1e-6);
+    }
+
+    #[test]
+    fn drag_plane_distance_stays_in_front_and_in_reach() {
+        let plane_point = Vec3::new(0.0, 0.0, -5.0);
+        let normal = Vec3::NEG_Z;
+        let reach = drag_reach(5.0);
+        assert_eq!(drag_plane_distance(Vec3::ZERO, Vec3::NEG_Z, plane_point, normal, 0.5, reach), Some(5.0));
+        // Camera moved past the plane: the sphere stays just in front of it
+        assert_eq!(drag_plane_distance(Vec3::new(0.0, 0.0, -8.0), Vec3::NEG_Z, plane_point, normal, 0.5, reach), Some(0.5));
+        // Shallow angle: far away but capped
+        let shallow = Vec3::new(1.0, 0.0, -0.06).normalize();
+        assert_eq!(drag_plane_distance(Vec3::ZERO, shallow, plane_point, normal, 0.5, reach), Some(MIN_DRAG_REACH));
+        // Parallel to the plane
+        assert_eq!(drag_plane_distance(Vec3::ZERO, Vec3::X, plane_point, normal, 0.5, reach), None);
+
+        // A sphere picked up beyond the usual reach can still be moved where it is
+        let far_plane = Vec3::new(0.0, 0.0, -80.0);
+        let reach = drag_reach(80.0);
+        let nudged = Vec3::new(0.05, 0.0, -1.0).normalize();
+        let distance = drag_plane_distance(Vec3::ZERO, nudged, far_plane, normal, 0.5, reach).unwrap();
+        assert!((distance * nudged.z + 80.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn only_breakable_adhesions_break_past_their_force() {
+        let adhesion = AdhesionSettings { break_force: 10.0, linear_spring_stiffness: 100.0, ..Default::default() };
+        assert!(!adhesion_breaks(&adhesion, 1.05, 1.0));
+        assert!(adhesion_breaks(&adhesion, 1.2, 1.0));
+        // Pushing the spheres together never breaks it
+        assert!(!adhesion_breaks(&adhesion, 0.5, 1.0));
+        assert!(!adhesion_breaks(&AdhesionSettings { can_break: false, ..adhesion }, 5.0, 1.0));
+    }
+
+    #[test]
+    fn spring_points_zigzag_between_the_ends() {
+        let points = spring_points(Vec3::ZERO, Vec3::X * 4.0, 2, 0.5);
+        assert_eq!(points.len(), 5);
+        assert_eq!((points[0], points[4]), (Vec3::ZERO, Vec3::X * 4.0));
+        assert!((points[1].x - 1.0).abs() < 1e-6 && (points[1] - points[3]).length() > 0.99);
+        assert!(points.iter().all(|p| (p.y * p.y + p.z * p.z).sqrt() <= 0.5 + 1e-6));
+    }
+
+    #[test]
+    fn snapping_rounds_to_the_grid_above_the_ground() {
+        let position = Vec3::new(0.74, 0.2, -1.3);
+        assert_eq!(snap_to_grid(position, 0.5, None), Vec3::new(0.5, 0.0, -1.5));
+        // Rounding down would put the sphere's bottom below the ground
+        assert_eq!(snap_to_grid(position, 0.5, Some(0.25)), Vec3::new(0.5, 0.25, -1.5));
+    }
+
+    #[test]
+    fn settle_distance_never_overlaps() {
+        assert_eq!(settle_distance(2.0, 0.5, 0.5), 2.0);
+        assert_eq!(settle_distance(0.2, 0.5, 0.3), 0.8);
+    }
+
+    #[test]
+    fn zero_direction_never_hits() {
+        assert_eq!(ray_sphere_intersection(Vec3::ZERO, Vec3::ZERO, Vec3::ZERO, 1.0), None);
     }
 }
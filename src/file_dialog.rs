@@ -0,0 +1,326 @@
+use bevy::prelude::*;
+use bevy::tasks::futures::check_ready;
+use bevy::tasks::futures_lite::FutureExt;
+use bevy::tasks::{IoTaskPool, Task};
+use bevy_egui::egui;
+use std::panic::AssertUnwindSafe;
+use std::path::PathBuf;
+
+use crate::dock::config_dir;
+use crate::widgets;
+
+/// What a requested path is for, so it can be handled once the user has picked one
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum FileTarget {
+    ImportArchive,
+    ExportLibrary,
+    SaveGenome,
+    LoadGenome,
+    /// Add another genome's modes to the current one, merging modes that duplicate existing ones if set
+    AppendModes { merge_duplicates: bool },
+    AssetFolder,
+    ExportMode(usize),
+    ImportMode(usize),
+    ExportWorkspace,
+    ImportWorkspace,
+    ExportModeCard(usize),
+    ExportPreferences,
+    ImportPreferences,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum PickKind {
+    OpenFile,
+    SaveFile,
+    Folder,
+}
+
+/// A request for a path, answered by the native dialog or the in-app prompt
+pub struct FileRequest {
+    pub target: FileTarget,
+    pub kind: PickKind,
+    pub title: &'static str,
+    /// (description, extensions) shown as the dialog filter
+    pub filter: Option<(&'static str, &'static [&'static str])>,
+    /// Suggested name for saves
+    pub file_name: Option<String>,
+}
+
+struct PathPrompt {
+    request: FileRequest,
+    buffer: String,
+    error: Option<String>,
+    /// Existing file the user has been warned about; confirming again with the same path overwrites it
+    overwrite_warned: Option<PathBuf>,
+}
+
+/// Native dialog open on the IO task pool, so the app keeps drawing while it's up.
+/// Its task yields Err if the dialog panicked.
+struct NativeDialog {
+    request: FileRequest,
+    task: Task<std::thread::Result<Option<PathBuf>>>,
+}
+
+/// Native file dialogs with a typed-path fallback for when no dialog backend is available
+#[derive(Resource, Default)]
+pub struct FileDialogs {
+    /// Set once the native dialog panicked or can't have a backend; later requests go straight to the prompt
+    backend_unavailable: bool,
+    native: Option<NativeDialog>,
+    prompt: Option<PathPrompt>,
+    picked: Option<(FileTarget, PathBuf)>,
+}
+
+impl FileDialogs {
+    /// Ask the user for a path. The result is collected with [`FileDialogs::take_picked`] once
+    /// the user has picked one, usually some frames later.
+    pub fn request(&mut self, request: FileRequest) {
+        if self.native.is_some() || self.prompt.is_some() {
+            info!("Ignoring {:?} file request while another dialog is open", request.target);
+            return;
+        }
+        if !self.backend_unavailable {
+            if !native_backend_missing() {
+                let task = IoTaskPool::get().spawn(AssertUnwindSafe(native_dialog(&request)).catch_unwind());
+                self.native = Some(NativeDialog { request, task });
+                return;
+            }
+            warn!("No file dialog backend is available");
+            self.fall_back_to_prompt();
+        }
+        self.open_prompt(request);
+    }
+
+    fn fall_back_to_prompt(&mut self) {
+        info!("Falling back to typed path entry for file dialogs");
+        self.backend_unavailable = true;
+    }
+
+    /// Collect the native dialog's answer once it has closed. rfd reports a failed backend the
+    /// same way as a cancel, so only a panic switches to the prompt; a dialog closed quickly
+    /// may just have been cancelled quickly.
+    fn poll_native(&mut self) {
+        let Some(result) = self.native.as_mut().and_then(|native| check_ready(&mut native.task)) else {
+            return;
+        };
+        let Some(NativeDialog { request, .. }) = self.native.take() else {
+            return;
+        };
+        match result {
+            Ok(Some(path)) => self.picked = Some((request.target, path)),
+            Ok(None) => {}
+            Err(_) => {
+                warn!("File dialog panicked; assuming no dialog backend is available");
+                self.fall_back_to_prompt();
+                self.open_prompt(request);
+            }
+        }
+    }
+
+    fn open_prompt(&mut self, request: FileRequest) {
+        let suggested = match &request.file_name {
+            Some(name) => config_dir().join(name),
+            None => config_dir(),
+        };
+        self.prompt = Some(PathPrompt {
+            buffer: suggested.display().to_string(),
+            request,
+            error: None,
+            overwrite_warned: None,
+        });
+    }
+
+    /// The path picked for a request, if one is ready
+    pub fn take_picked(&mut self) -> Option<(FileTarget, PathBuf)> {
+        self.poll_native();
+        self.picked.take()
+    }
+
+    /// Draw the typed-path prompt while one is open
+    pub fn show_prompt(&mut self, ctx: &egui::Context) {
+        let Some(prompt) = self.prompt.as_mut() else {
+            return;
+        };
+
+        let result = widgets::modal_dialog(ctx, prompt.request.title, |ui| {
+            ui.label("No system file dialog is available. Enter a path:");
+            if let Some((description, extensions)) = prompt.request.filter {
+                ui.weak(format!("{} (.{})", description, extensions.join(", .")));
+            }
+            let response = ui.add(egui::TextEdit::singleline(&mut prompt.buffer).desired_width(400.0));
+            if let Some(error) = &prompt.error {
+                ui.colored_label(egui::Color32::LIGHT_RED, error);
+            } else if let Some(path) = &prompt.overwrite_warned {
+                ui.colored_label(
+                    egui::Color32::YELLOW,
+                    format!("⚠ {} already exists. Press OK again to replace it.", path.display()),
+                );
+            }
+            Some(response)
+        });
+
+        match result {
+            widgets::DialogResult::Open => {}
+            widgets::DialogResult::Confirmed => {
+                let path = PathBuf::from(prompt.buffer.trim());
+                match validate_path(&path, prompt.request.kind) {
+                    // The native save dialog asks before overwriting; the typed path has to as well
+                    Ok(()) if prompt.request.kind == PickKind::SaveFile
+                        && path.is_file()
+                        && prompt.overwrite_warned.as_ref() != Some(&path) =>
+                    {
+                        prompt.error = None;
+                        prompt.overwrite_warned = Some(path);
+                    }
+                    Ok(()) => {
+                        self.picked = Some((prompt.request.target, path));
+                        self.prompt = None;
+                    }
+                    Err(error) => {
+                        prompt.error = Some(error);
+                        prompt.overwrite_warned = None;
+                    }
+                }
+            }
+            widgets::DialogResult::Cancelled => self.prompt = None,
+        }
+    }
+}
+
+/// The native dialog for `request`, resolving to the picked path once it closes
+fn native_dialog(request: &FileRequest) -> impl Future<Output = Option<PathBuf>> + Send + 'static {
+    let mut dialog = rfd::AsyncFileDialog::new().set_title(request.title);
+    if let Some((description, extensions)) = request.filter {
+        dialog = dialog.add_filter(description, extensions);
+    }
+    if let Some(file_name) = &request.file_name {
+        dialog = dialog.set_file_name(file_name);
+    }
+    let kind = request.kind;
+    async move {
+        let picked = match kind {
+            PickKind::OpenFile => dialog.pick_file().await,
+            PickKind::SaveFile => dialog.save_file().await,
+            PickKind::Folder => dialog.pick_folder().await,
+        };
+        picked.map(|handle| handle.path().to_path_buf())
+    }
+}
+
+/// Whether rfd has nothing to show a dialog with. On Linux it asks the XDG desktop portal and
+/// falls back to zenity when that fails, so both have to be missing.
+#[cfg(target_os = "linux")]
+fn native_backend_missing() -> bool {
+    let session_bus = std::env::var_os("DBUS_SESSION_BUS_ADDRESS").is_some()
+        || std::env::var_os("XDG_RUNTIME_DIR").is_some_and(|dir| PathBuf::from(dir).join("bus").exists());
+    let portal = session_bus && portal_file_chooser_installed(&xdg_data_dirs());
+    let zenity = std::env::var_os("PATH")
+        .is_some_and(|paths| std::env::split_paths(&paths).any(|dir| dir.join("zenity").is_file()));
+    !portal && !zenity
+}
+
+/// `$XDG_DATA_HOME` and `$XDG_DATA_DIRS`, with their defaults, where the portal is installed to
+#[cfg(target_os = "linux")]
+fn xdg_data_dirs() -> Vec<PathBuf> {
+    let data_home = std::env::var_os("XDG_DATA_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".local/share")));
+    let data_dirs = std::env::var_os("XDG_DATA_DIRS")
+        .filter(|dirs| !dirs.is_empty())
+        .unwrap_or_else(|| "/usr/local/share:/usr/share".into());
+    data_home.into_iter().chain(std::env::split_paths(&data_dirs)).collect()
+}
+
+/// Whether the desktop portal service is installed along with a backend that implements its
+/// file chooser. Without one, the portal is there but can't show a dialog.
+#[cfg(target_os = "linux")]
+fn portal_file_chooser_installed(data_dirs: &[PathBuf]) -> bool {
+    let service = data_dirs.iter()
+        .any(|dir| dir.join("dbus-1/services/org.freedesktop.portal.Desktop.service").is_file());
+    let file_chooser = data_dirs.iter().any(|dir| {
+        std::fs::read_dir(dir.join("xdg-desktop-portal/portals"))
+            .into_iter()
+            .flatten()
+            .flatten()
+            .filter(|entry| entry.path().extension().is_some_and(|ext| ext == "portal"))
+            .any(|entry| std::fs::read_to_string(entry.path())
+                .is_ok_and(|text| text.contains("org.freedesktop.impl.portal.FileChooser")))
+    });
+    service && file_chooser
+}
+
+#[cfg(not(target_os = "linux"))]
+fn native_backend_missing() -> bool {
+    false
+}
+
+/// Check a typed path the way the native dialog would have constrained it
+fn validate_path(path: &std::path::Path, kind: PickKind) -> Result<(), String> {
+    if path.as_os_str().is_empty() {
+        return Err("Enter a path".to_string());
+    }
+    match kind {
+        PickKind::OpenFile if !path.is_file() => Err("No such file".to_string()),
+        PickKind::Folder if !path.is_dir() => Err("No such folder".to_string()),
+        PickKind::SaveFile if path.is_dir() => Err("That is a folder, enter a file name".to_string()),
+        PickKind::SaveFile => match path.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() && !parent.is_dir() => {
+                Err(format!("Folder {} doesn't exist", parent.display()))
+            }
+            _ => Ok(()),
+        },
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn typed_paths_are_checked_like_the_native_dialog() {
+        let dir = std::env::temp_dir().join(format!("validate_path_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("genome.json");
+        std::fs::write(&file, "{}").unwrap();
+        let missing = dir.join("missing.json");
+
+        assert!(validate_path(std::path::Path::new(""), PickKind::OpenFile).is_err());
+        assert!(validate_path(&file, PickKind::OpenFile).is_ok());
+        assert!(validate_path(&missing, PickKind::OpenFile).is_err());
+        assert!(validate_path(&dir, PickKind::OpenFile).is_err());
+
+        assert!(validate_path(&dir, PickKind::Folder).is_ok());
+        assert!(validate_path(&file, PickKind::Folder).is_err());
+
+        // Saves may name a new or existing file, but not a folder or one in a missing folder
+        assert!(validate_path(&missing, PickKind::SaveFile).is_ok());
+        assert!(validate_path(&file, PickKind::SaveFile).is_ok());
+        assert!(validate_path(&dir, PickKind::SaveFile).is_err());
+        assert!(validate_path(&dir.join("nowhere").join("genome.json"), PickKind::SaveFile).is_err());
+        assert!(validate_path(std::path::Path::new("genome.json"), PickKind::SaveFile).is_ok());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn portal_needs_a_file_chooser_backend() {
+        let dir = std::env::temp_dir().join(format!("portal_probe_{}", std::process::id()));
+        let services = dir.join("dbus-1/services");
+        let portals = dir.join("xdg-desktop-portal/portals");
+        std::fs::create_dir_all(&services).unwrap();
+        std::fs::create_dir_all(&portals).unwrap();
+        let data_dirs = [dir.clone()];
+
+        assert!(!portal_file_chooser_installed(&data_dirs));
+        std::fs::write(services.join("org.freedesktop.portal.Desktop.service"), "[D-BUS Service]").unwrap();
+        std::fs::write(portals.join("wlr.portal"), "Interfaces=org.freedesktop.impl.portal.Screenshot").unwrap();
+        assert!(!portal_file_chooser_installed(&data_dirs));
+        std::fs::write(portals.join("gtk.portal"), "Interfaces=org.freedesktop.impl.portal.FileChooser;").unwrap();
+        assert!(portal_file_chooser_installed(&data_dirs));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,341 @@
+use bevy::prelude::*;
+use bevy::tasks::{futures::check_ready, IoTaskPool, Task};
+use std::collections::HashSet;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use super::{GenomeData, GenomeLibrary};
+
+/// Largest single archive entry we are willing to decompress (guards against zip bombs)
+const MAX_ENTRY_SIZE: u64 = 16 * 1024 * 1024;
+
+/// Result of loading one file out of an archive
+pub struct ArchiveEntryResult {
+    pub file_name: String,
+    /// Genome name on success, error message on failure
+    pub result: Result<String, String>,
+}
+
+/// Everything read out of a genome archive
+#[derive(Default)]
+pub struct ArchiveImportReport {
+    pub genomes: Vec<GenomeData>,
+    pub entries: Vec<ArchiveEntryResult>,
+}
+
+/// Background archive work and the report of the last finished job
+#[derive(Resource, Default)]
+pub struct GenomeArchiveState {
+    import_task: Option<Task<Result<ArchiveImportReport, String>>>,
+    pub last_import: Option<ArchiveImportSummary>,
+    export_task: Option<Task<Result<(), String>>>,
+    pub last_export: Option<ArchiveExportSummary>,
+}
+
+/// What the UI shows once an import has finished
+pub struct ArchiveImportSummary {
+    pub archive_path: PathBuf,
+    /// `Err` if the archive itself could not be opened
+    pub entries: Result<Vec<ArchiveEntryResult>, String>,
+}
+
+/// Progress and outcome of a library export
+pub struct ArchiveExportSummary {
+    pub archive_path: PathBuf,
+    pub total: usize,
+    /// Number of genomes written so far, shared with the export task
+    pub written: Arc<AtomicUsize>,
+    /// `None` while the export is running
+    pub result: Option<Result<(), String>>,
+}
+
+impl GenomeArchiveState {
+    pub fn is_importing(&self) -> bool {
+        self.import_task.is_some()
+    }
+
+    /// Start reading `path` on the IO task pool
+    pub fn start_import(&mut self, path: PathBuf) {
+        let task_path = path.clone();
+        self.import_task = Some(IoTaskPool::get().spawn(async move {
+            read_genome_archive(&task_path).map_err(|err| err.to_string())
+        }));
+        self.last_import = Some(ArchiveImportSummary {
+            archive_path: path,
+            entries: Ok(Vec::new()),
+        });
+    }
+
+    pub fn is_exporting(&self) -> bool {
+        self.export_task.is_some()
+    }
+
+    /// Start writing `genomes` to a zip at `path` on the IO task pool
+    pub fn start_export(&mut self, path: PathBuf, genomes: Vec<GenomeData>) {
+        let written = Arc::new(AtomicUsize::new(0));
+        let task_path = path.clone();
+        let task_written = written.clone();
+        let total = genomes.len();
+        self.export_task = Some(IoTaskPool::get().spawn(async move {
+            write_genome_archive(&task_path, &genomes, &task_written).map_err(|err| err.to_string())
+        }));
+        self.last_export = Some(ArchiveExportSummary {
+            archive_path: path,
+            total,
+            written,
+            result: None,
+        });
+    }
+}
+
+/// Write each genome as a pretty-printed JSON file named after the genome.
+/// `written` is bumped after every file so the UI can show progress.
+pub fn write_genome_archive(
+    path: &Path,
+    genomes: &[GenomeData],
+    written: &AtomicUsize,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let file = std::fs::File::create(path)?;
+    let mut archive = zip::ZipWriter::new(file);
+    let options = zip::write::SimpleFileOptions::default()
+        .compression_method(zip::CompressionMethod::Deflated);
+    let mut used_names = HashSet::new();
+
+    for genome in genomes {
+        let file_name = unique_file_name(&genome.name, &mut used_names);
+        let json = serde_json::to_string_pretty(genome)?;
+        archive.start_file(file_name, options)?;
+        archive.write_all(json.as_bytes())?;
+        written.fetch_add(1, Ordering::Relaxed);
+    }
+
+    archive.finish()?;
+    Ok(())
+}
+
+/// Build a `<name>.json` file name that is safe on disk and not already taken in this archive
+fn unique_file_name(genome_name: &str, used_names: &mut HashSet<String>) -> String {
+    let sanitized: String = genome_name
+        .trim()
+        .chars()
+        .map(|c| if c.is_control() || "/\\:*?\"<>|".contains(c) { '_' } else { c })
+        .collect();
+    let base = if sanitized.is_empty() { "genome".to_string() } else { sanitized };
+
+    let mut candidate = format!("{}.json", base);
+    let mut suffix = 2;
+    // Compare case-insensitively so the archive also extracts cleanly on Windows/macOS
+    while !used_names.insert(candidate.to_lowercase()) {
+        candidate = format!("{} ({}).json", base, suffix);
+        suffix += 1;
+    }
+    candidate
+}
+
+/// Read every `.json`/`.ron` genome out of a zip archive.
+/// Entries that fail to load are recorded in the report rather than aborting the import.
+pub fn read_genome_archive(path: &Path) -> Result<ArchiveImportReport, Box<dyn std::error::Error + Send + Sync>> {
+    let file = std::fs::File::open(path)?;
+    let mut archive = zip::ZipArchive::new(file)?;
+    let mut report = ArchiveImportReport::default();
+
+    for i in 0..archive.len() {
+        let mut entry = match archive.by_index(i) {
+            Ok(entry) => entry,
+            Err(err) => {
+                report.entries.push(ArchiveEntryResult {
+                    file_name: format!("entry #{}", i),
+                    result: Err(err.to_string()),
+                });
+                continue;
+            }
+        };
+
+        if entry.is_dir() {
+            continue;
+        }
+        let file_name = entry.name().to_string();
+        let Some(format) = GenomeFileFormat::from_path(Path::new(&file_name)) else {
+            continue;
+        };
+
+        let result = if entry.size() > MAX_ENTRY_SIZE {
+            Err(format!("file is larger than {} MiB", MAX_ENTRY_SIZE / (1024 * 1024)))
+        } else {
+            let mut contents = String::new();
+            match entry.by_ref().take(MAX_ENTRY_SIZE).read_to_string(&mut contents) {
+                Ok(_) => format.parse(&contents),
+                Err(err) => Err(err.to_string()),
+            }
+        };
+
+        let result = result.map(|genome| {
+            let name = genome.name.clone();
+            report.genomes.push(genome);
+            name
+        });
+        report.entries.push(ArchiveEntryResult { file_name, result });
+    }
+
+    Ok(report)
+}
+
+/// Genome file encodings recognised inside archives
+#[derive(Clone, Copy)]
+enum GenomeFileFormat {
+    Json,
+    Ron,
+}
+
+impl GenomeFileFormat {
+    fn from_path(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+        match extension.as_str() {
+            "json" => Some(Self::Json),
+            "ron" => Some(Self::Ron),
+            _ => None,
+        }
+    }
+
+    fn parse(self, contents: &str) -> Result<GenomeData, String> {
+        match self {
+            Self::Json => GenomeData::from_json(contents).map_err(|err| err.to_string()),
+            Self::Ron => GenomeData::from_ron(contents).map_err(|err| err.to_string()),
+        }
+    }
+}
+
+/// Move genomes from a finished import into the library and record finished exports
+pub fn poll_archive_tasks(
+    mut archive_state: ResMut<GenomeArchiveState>,
+    mut library: ResMut<GenomeLibrary>,
+) {
+    if let Some(result) = archive_state.export_task.as_mut().and_then(check_ready) {
+        archive_state.export_task = None;
+        match &result {
+            Ok(()) => info!("Exported genome library to archive"),
+            Err(err) => warn!("Failed to export genome library: {}", err),
+        }
+        if let Some(summary) = archive_state.last_export.as_mut() {
+            summary.result = Some(result);
+        }
+    }
+
+    let Some(task) = archive_state.import_task.as_mut() else {
+        return;
+    };
+    let Some(result) = check_ready(task) else {
+        return;
+    };
+    archive_state.import_task = None;
+
+    let entries = match result {
+        Ok(report) => {
+            let loaded = report.genomes.len();
+            for genome in report.genomes {
+                library.add_genome(genome);
+            }
+            info!("Imported {} genome(s) from archive ({} file(s) failed)",
+                loaded, report.entries.iter().filter(|e| e.result.is_err()).count());
+            Ok(report.entries)
+        }
+        Err(err) => {
+            warn!("Failed to read genome archive: {}", err);
+            Err(err)
+        }
+    };
+
+    if let Some(summary) = archive_state.last_import.as_mut() {
+        summary.entries = entries;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_archive(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("{}_{}.zip", name, std::process::id()))
+    }
+
+    /// Two genomes with the same name, so the second is written under a deduplicated file name
+    fn sample_genomes() -> Vec<GenomeData> {
+        let first = GenomeData::default();
+        let mut second = GenomeData::default();
+        let count = second.modes.len();
+        for (i, mode) in second.modes.iter_mut().enumerate() {
+            mode.split_mass = 1.0 + (i % 20) as f32 * 0.1;
+            mode.child_a.mode_number = ((i + 1) % count) as i32;
+            mode.parent_make_adhesion = i % 3 == 0;
+        }
+        second.initial_mode = 5;
+        second.seed = Some(42);
+        vec![first, second]
+    }
+
+    #[test]
+    fn exported_genomes_import_unchanged() {
+        let path = temp_archive("archive_round_trip");
+        let genomes = sample_genomes();
+        let written = AtomicUsize::new(0);
+        write_genome_archive(&path, &genomes, &written).unwrap();
+        let report = read_genome_archive(&path);
+        std::fs::remove_file(&path).unwrap();
+
+        let report = report.unwrap();
+        assert_eq!(written.load(Ordering::Relaxed), genomes.len());
+        assert!(report.entries.iter().all(|entry| entry.result.is_ok()));
+        assert!(report.genomes == genomes);
+    }
+
+    #[test]
+    fn truncated_archive_fails_to_open() {
+        let path = temp_archive("archive_truncated");
+        write_genome_archive(&path, &sample_genomes(), &AtomicUsize::new(0)).unwrap();
+        let bytes = std::fs::read(&path).unwrap();
+        std::fs::write(&path, &bytes[..bytes.len() / 2]).unwrap();
+        let report = read_genome_archive(&path);
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(report.is_err());
+    }
+
+    #[test]
+    fn genomes_in_another_format_version_are_reported() {
+        let path = temp_archive("archive_wrong_version");
+        let mut archive = zip::ZipWriter::new(std::fs::File::create(&path).unwrap());
+        let options = zip::write::SimpleFileOptions::default();
+        archive.start_file("future.json", options).unwrap();
+        archive.write_all(br#"{"format_version": 2, "genome": {"name": "Future", "modes": "packed"}}"#).unwrap();
+        archive.start_file("future.ron", options).unwrap();
+        archive.write_all(b"GenomeV2(name: \"Future\", modes: [])").unwrap();
+        archive.finish().unwrap();
+        let report = read_genome_archive(&path);
+        std::fs::remove_file(&path).unwrap();
+
+        let report = report.unwrap();
+        assert!(report.genomes.is_empty());
+        assert_eq!(report.entries.len(), 2);
+        assert!(report.entries.iter().all(|entry| entry.result.is_err()));
+    }
+
+    #[test]
+    fn entry_missing_its_data_is_reported() {
+        let path = temp_archive("archive_missing_entry");
+        write_genome_archive(&path, &sample_genomes(), &AtomicUsize::new(0)).unwrap();
+        // The first entry's local header starts the file; without it the entry can't be found
+        let mut bytes = std::fs::read(&path).unwrap();
+        bytes[..4].fill(0);
+        std::fs::write(&path, &bytes).unwrap();
+        let report = read_genome_archive(&path);
+        std::fs::remove_file(&path).unwrap();
+
+        let report = report.unwrap();
+        assert_eq!(report.entries.len(), 2);
+        assert!(report.entries[0].result.is_err());
+        assert!(report.entries[1].result.is_ok());
+        assert_eq!(report.genomes.len(), 1);
+    }
+}
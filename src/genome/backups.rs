@@ -0,0 +1,126 @@
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use bevy::prelude::*;
+
+use super::{backup_path, rotate_backups, CurrentGenome, GenomeData};
+
+/// Newest safety backup, inside the config folder. Older ones are numbered, e.g. `genome.2.autobackup`.
+const AUTO_BACKUP_FILE: &str = "genome.autobackup";
+
+/// Ring of genome snapshots taken before destructive operations, newest first
+pub struct Backups {
+    path: PathBuf,
+}
+
+/// One readable snapshot, as listed in the Restore from Backup menu
+#[derive(Clone)]
+pub struct BackupEntry {
+    pub path: PathBuf,
+    pub modified: Option<SystemTime>,
+    pub genome_name: String,
+    pub mode_count: usize,
+}
+
+impl BackupEntry {
+    /// Menu label, e.g. "My Genome (4 modes), 5m ago"
+    pub fn label(&self) -> String {
+        let age = self.modified
+            .and_then(|modified| SystemTime::now().duration_since(modified).ok())
+            .map(|age| crate::performance::relative_time(age.as_secs_f64()))
+            .unwrap_or_else(|| "unknown time".to_string());
+        let modes = if self.mode_count == 1 { "mode" } else { "modes" };
+        format!("{} ({} {}), {}", self.genome_name, self.mode_count, modes, age)
+    }
+}
+
+impl Default for Backups {
+    fn default() -> Self {
+        Self::at(crate::dock::config_dir().join(AUTO_BACKUP_FILE))
+    }
+}
+
+impl Backups {
+    /// Backups whose newest snapshot is `path`
+    pub fn at(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    /// Write `genome` as the newest snapshot, keeping at most `keep` of them
+    pub fn snapshot(&self, genome: &GenomeData, keep: usize) -> Result<(), Box<dyn std::error::Error>> {
+        if keep == 0 {
+            return Ok(());
+        }
+        rotate_backups(&self.path, keep - 1)?;
+        genome
+            .save_to_file(&self.path)
+            .map_err(|err| format!("{}: {}", self.path.display(), err).into())
+    }
+
+    /// The snapshots that can still be loaded, newest first
+    pub fn list(&self) -> Vec<BackupEntry> {
+        std::iter::once(self.path.clone())
+            .chain((1..).map(|n| backup_path(&self.path, n)).take_while(|path| path.exists()))
+            .filter_map(|path| read_entry(&path))
+            .collect()
+    }
+}
+
+fn read_entry(path: &Path) -> Option<BackupEntry> {
+    let genome = GenomeData::load_from_file(path).ok()?;
+    Some(BackupEntry {
+        path: path.to_path_buf(),
+        modified: std::fs::metadata(path).and_then(|metadata| metadata.modified()).ok(),
+        genome_name: genome.name,
+        mode_count: genome.modes.len(),
+    })
+}
+
+/// Write the snapshots queued by [`CurrentGenome::back_up`], skipping one identical to the last written
+pub fn write_safety_backups(
+    mut current_genome: ResMut<CurrentGenome>,
+    mut last_written: Local<Option<GenomeData>>,
+    preferences: Res<crate::preferences::Preferences>,
+) {
+    // Draining the queue isn't an edit, so don't wake the systems watching the genome
+    let pending = current_genome.bypass_change_detection().take_pending_backups();
+    let keep = preferences.genome_safety_backups();
+    if keep == 0 {
+        return;
+    }
+    let backups = Backups::default();
+    for genome in pending {
+        if last_written.as_ref() == Some(&genome) {
+            continue;
+        }
+        match backups.snapshot(&genome, keep) {
+            Ok(()) => *last_written = Some(genome),
+            Err(err) => warn!("Failed to back up genome: {}", err),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::genome::ModeSettings;
+
+    #[test]
+    fn snapshots_rotate_newest_first() {
+        let dir = std::env::temp_dir().join(format!("safety_backups_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let backups = Backups::at(dir.join(AUTO_BACKUP_FILE));
+
+        for i in 0..4 {
+            let genome = GenomeData {
+                name: format!("Genome {}", i),
+                modes: vec![ModeSettings::default()],
+                ..Default::default()
+            };
+            backups.snapshot(&genome, 3).unwrap();
+        }
+        let names: Vec<String> = backups.list().into_iter().map(|entry| entry.genome_name).collect();
+        let _ = std::fs::remove_dir_all(&dir);
+        assert_eq!(names, ["Genome 3", "Genome 2", "Genome 1"]);
+    }
+}
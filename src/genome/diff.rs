@@ -0,0 +1,114 @@
+//! Field-by-field comparison of genome data through its serialized form, so new fields are
+//! picked up without touching this module.
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+/// One field of two compared values, e.g. `adhesion_settings.break_force`.
+/// A field only one side has is `Null` on the other.
+pub struct FieldDiff {
+    pub path: String,
+    pub left: Value,
+    pub right: Value,
+}
+
+impl FieldDiff {
+    pub fn differs(&self) -> bool {
+        self.left != self.right
+    }
+}
+
+/// Every field of `left` and `right`, sorted by path (fields only `right` has come last)
+pub fn diff_fields<T: Serialize>(left: &T, right: &T) -> Result<Vec<FieldDiff>, serde_json::Error> {
+    let left = flatten(&serde_json::to_value(left)?);
+    let mut right = flatten(&serde_json::to_value(right)?);
+
+    let mut diffs: Vec<FieldDiff> = left.into_iter()
+        .map(|(path, left)| {
+            let right = right.iter().position(|(other, _)| *other == path)
+                .map_or(Value::Null, |i| right.remove(i).1);
+            FieldDiff { path, left, right }
+        })
+        .collect();
+    diffs.extend(right.into_iter().map(|(path, right)| FieldDiff { path, left: Value::Null, right }));
+    Ok(diffs)
+}
+
+/// Set the field at `path` in `to` to its value in `from`
+pub fn copy_field<T: Serialize + DeserializeOwned>(from: &T, to: &mut T, path: &str) -> Result<(), serde_json::Error> {
+    let source = serde_json::to_value(from)?;
+    let mut target = serde_json::to_value(&*to)?;
+    let value = lookup(&source, path).cloned().unwrap_or(Value::Null);
+    if let Some(slot) = lookup_mut(&mut target, path) {
+        *slot = value;
+    }
+    *to = serde_json::from_value(target)?;
+    Ok(())
+}
+
+/// Leaf fields of `value` keyed by dotted path. Vectors and quaternions (`{x, y, z(, w)}`)
+/// stay whole so they are compared and copied as one value.
+fn flatten(value: &Value) -> Vec<(String, Value)> {
+    fn walk(prefix: &str, value: &Value, out: &mut Vec<(String, Value)>) {
+        match value {
+            Value::Object(fields) if !is_vector(fields) => {
+                for (key, field) in fields {
+                    let path = if prefix.is_empty() { key.clone() } else { format!("{}.{}", prefix, key) };
+                    walk(&path, field, out);
+                }
+            }
+            _ => out.push((prefix.to_string(), value.clone())),
+        }
+    }
+
+    let mut out = Vec::new();
+    walk("", value, &mut out);
+    out
+}
+
+fn is_vector(fields: &Map<String, Value>) -> bool {
+    !fields.is_empty()
+        && fields.iter().all(|(key, value)| matches!(key.as_str(), "x" | "y" | "z" | "w") && value.is_number())
+}
+
+fn lookup<'a>(value: &'a Value, path: &str) -> Option<&'a Value> {
+    path.split('.').try_fold(value, |value, key| value.get(key))
+}
+
+fn lookup_mut<'a>(value: &'a mut Value, path: &str) -> Option<&'a mut Value> {
+    path.split('.').try_fold(value, |value, key| value.get_mut(key))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::genome::ModeSettings;
+
+    #[test]
+    fn diff_reports_nested_fields_and_keeps_vectors_whole() {
+        let left = ModeSettings::new_self_splitting(0, "A".to_string());
+        let mut right = left.clone();
+        right.adhesion_settings.break_force = 42.0;
+        right.color.y = 0.25;
+
+        let diffs = diff_fields(&left, &right).unwrap();
+        let differing: Vec<&str> = diffs.iter().filter(|diff| diff.differs()).map(|diff| diff.path.as_str()).collect();
+        assert_eq!(differing, vec!["adhesion_settings.break_force", "color"]);
+        assert!(diffs.iter().any(|diff| diff.path == "child_a.orientation"));
+    }
+
+    #[test]
+    fn copy_field_changes_only_that_field() {
+        let mut source = ModeSettings::new_self_splitting(0, "Source".to_string());
+        source.adhesion_settings.break_force = 42.0;
+        source.split_mass = 3.0;
+        let mut target = ModeSettings::new_self_splitting(1, "Target".to_string());
+
+        copy_field(&source, &mut target, "adhesion_settings.break_force").unwrap();
+
+        assert_eq!(target.adhesion_settings.break_force, 42.0);
+        assert_eq!(target.split_mass, ModeSettings::new_self_splitting(1, String::new()).split_mass);
+        assert_eq!(target.name, "Target");
+    }
+}
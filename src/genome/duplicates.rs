@@ -0,0 +1,212 @@
+use bevy::prelude::*;
+
+use super::{GenomeData, ModeSettings};
+
+/// Stands in for a mode's references to itself, so two self-splitting modes compare equal
+const SELF_REFERENCE: i32 = -2;
+
+/// Copy of mode `idx` with everything that doesn't change how cells behave blanked out:
+/// names, the favorite star and the quaternion balls' lat/lon tracking, plus the color
+/// with `ignore_color`. References back to the mode itself become [`SELF_REFERENCE`].
+fn comparable_mode(mode: &ModeSettings, idx: usize, ignore_color: bool) -> ModeSettings {
+    let mut mode = mode.clone();
+    mode.name.clear();
+    mode.default_name.clear();
+    mode.favorite = false;
+    if ignore_color {
+        mode.color = Vec3::ZERO;
+        mode.color_pinned = false;
+        mode.color_is_custom = false;
+    }
+    let own_index = idx as i32;
+    for child in [&mut mode.child_a, &mut mode.child_b] {
+        if child.mode_number == own_index {
+            child.mode_number = SELF_REFERENCE;
+        }
+        child.x_axis_lat = 0.0;
+        child.x_axis_lon = 0.0;
+        child.y_axis_lat = 0.0;
+        child.y_axis_lon = 0.0;
+        child.z_axis_lat = 0.0;
+        child.z_axis_lon = 0.0;
+    }
+    for after_splits in [&mut mode.mode_a_after_splits, &mut mode.mode_b_after_splits] {
+        if *after_splits == own_index {
+            *after_splits = SELF_REFERENCE;
+        }
+    }
+    mode
+}
+
+impl GenomeData {
+    /// Groups of modes that are identical except for their names (and colors, with `ignore_color`).
+    /// Each group has at least two modes in index order, and groups are ordered by their first mode.
+    pub fn duplicate_mode_groups(&self, ignore_color: bool) -> Vec<Vec<usize>> {
+        let comparable: Vec<ModeSettings> = self.modes.iter()
+            .enumerate()
+            .map(|(i, mode)| comparable_mode(mode, i, ignore_color))
+            .collect();
+        let mut grouped = vec![false; comparable.len()];
+        let mut groups = Vec::new();
+        for i in 0..comparable.len() {
+            if grouped[i] {
+                continue;
+            }
+            let group: Vec<usize> = (i..comparable.len())
+                .filter(|&j| !grouped[j] && comparable[j] == comparable[i])
+                .collect();
+            if group.len() > 1 {
+                for &j in &group {
+                    grouped[j] = true;
+                }
+                groups.push(group);
+            }
+        }
+        groups
+    }
+
+    /// Merge each mode from index `first` on into the earliest mode it duplicates (ignoring
+    /// names), leaving the modes before `first` where they are. Returns how many modes were merged.
+    pub fn merge_duplicates_from(&mut self, first: usize) -> usize {
+        let mut merged = 0;
+        // Each merge renumbers the modes after it, so look for the next group afresh
+        while let Some(group) = self.duplicate_mode_groups(false).into_iter()
+            .find_map(|group| {
+                let (&survivor, rest) = group.split_first()?;
+                let later: Vec<usize> = rest.iter().copied().filter(|&i| i >= first).collect();
+                (!later.is_empty()).then(|| [vec![survivor], later].concat())
+            })
+        {
+            merged += group.len() - 1;
+            self.merge_modes(&group);
+        }
+        merged
+    }
+
+    /// Replace the modes in `group` with its first one, which every reference to the others
+    /// is pointed at. Returns each old mode index's new index.
+    pub fn merge_modes(&mut self, group: &[usize]) -> Vec<usize> {
+        let Some(&survivor) = group.iter().min() else {
+            return (0..self.modes.len()).collect();
+        };
+        let merged = |i: usize| i != survivor && group.contains(&i);
+
+        let mut new_index = Vec::with_capacity(self.modes.len());
+        let mut removed = 0;
+        for i in 0..self.modes.len() {
+            new_index.push(i - removed);
+            if merged(i) {
+                removed += 1;
+            }
+        }
+        for i in 0..new_index.len() {
+            if merged(i) {
+                new_index[i] = new_index[survivor];
+            }
+        }
+
+        let mut index = 0;
+        self.modes.retain(|_| {
+            index += 1;
+            !merged(index - 1)
+        });
+        self.remap_mode_references(|old| new_index.get(old).copied());
+        new_index
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Modes 1, 3 and 4 only differ by name; 2 has a different split interval
+    fn genome_with_duplicates() -> GenomeData {
+        let mut genome = GenomeData {
+            modes: (0..6).map(|i| ModeSettings::new_self_splitting(i, format!("M {}", i))).collect(),
+            ..Default::default()
+        };
+        for mode in &mut genome.modes[1..5] {
+            mode.split_interval = 8.0;
+        }
+        genome.modes[2].split_interval = 9.0;
+        genome.modes[0].child_a.mode_number = 3;
+        genome.modes[0].child_b.mode_number = 4;
+        genome.modes[2].mode_a_after_splits = 5;
+        genome.modes[5].child_a.mode_number = 2;
+        genome.initial_mode = 4;
+        genome
+    }
+
+    #[test]
+    fn modes_equal_but_for_name_are_grouped() {
+        let mut genome = genome_with_duplicates();
+        assert_eq!(genome.duplicate_mode_groups(false), vec![vec![1, 3, 4]]);
+
+        genome.modes[3].color = Vec3::X;
+        assert_eq!(genome.duplicate_mode_groups(false), vec![vec![1, 4]]);
+        assert_eq!(genome.duplicate_mode_groups(true), vec![vec![1, 3, 4]]);
+
+        // Pointing at another mode instead of itself makes a mode different
+        genome.modes[4].child_a.mode_number = 0;
+        assert_eq!(genome.duplicate_mode_groups(true), vec![vec![1, 3]]);
+    }
+
+    #[test]
+    fn merging_points_references_at_the_survivor() {
+        let mut genome = genome_with_duplicates();
+
+        let new_index = genome.merge_modes(&[1, 3, 4]);
+
+        assert_eq!(new_index, vec![0, 1, 2, 1, 1, 3]);
+        let names: Vec<&str> = genome.modes.iter().map(|mode| mode.name.as_str()).collect();
+        assert_eq!(names, ["M 0", "M 1", "M 2", "M 5"]);
+        assert_eq!((genome.modes[0].child_a.mode_number, genome.modes[0].child_b.mode_number), (1, 1));
+        assert_eq!(genome.modes[1].child_a.mode_number, 1);
+        assert_eq!(genome.modes[2].mode_a_after_splits, 3);
+        assert_eq!(genome.modes[3].child_a.mode_number, 2);
+        assert_eq!(genome.initial_mode, 1);
+        assert!(genome.duplicate_mode_groups(false).is_empty());
+    }
+
+    #[test]
+    fn appended_modes_keep_their_transitions() {
+        let mut genome = genome_with_duplicates();
+        // Imported: a branch (0) that splits into a leaf (1) and itself, and a leaf that splits into itself
+        let mut library = GenomeData {
+            modes: (0..2).map(|i| ModeSettings::new_self_splitting(i, format!("Lib {}", i))).collect(),
+            ..Default::default()
+        };
+        library.modes[0].child_b.mode_number = 1;
+        library.modes[0].mode_a_after_splits = 1;
+        library.modes[1].split_interval = 12.0;
+
+        let first = genome.append_modes(&library);
+
+        assert_eq!((first, genome.modes.len()), (6, 8));
+        assert_eq!((genome.modes[6].child_a.mode_number, genome.modes[6].child_b.mode_number), (6, 7));
+        assert_eq!((genome.modes[6].mode_a_after_splits, genome.modes[6].mode_b_after_splits), (7, -1));
+        assert_eq!((genome.modes[7].child_a.mode_number, genome.modes[7].child_b.mode_number), (7, 7));
+        // The existing modes and the initial mode are untouched
+        assert_eq!(genome.initial_mode, 4);
+        assert_eq!(genome.modes[0].child_a.mode_number, 3);
+    }
+
+    #[test]
+    fn appended_duplicates_merge_into_existing_modes() {
+        let mut genome = genome_with_duplicates();
+        let mut library = GenomeData { modes: genome.modes[4..6].to_vec(), ..Default::default() };
+        library.modes[0].child_a.mode_number = 0;
+        library.modes[0].child_b.mode_number = 0;
+        library.modes[1].child_a.mode_number = 1;
+        library.modes[1].split_interval = 20.0;
+
+        let first = genome.append_modes(&library);
+        assert_eq!(genome.merge_duplicates_from(first), 1);
+
+        // Library mode 0 duplicated mode 1 (the first of 1, 3 and 4), which the existing duplicates keep
+        assert_eq!(genome.modes.len(), 7);
+        assert_eq!(genome.duplicate_mode_groups(false), vec![vec![1, 3, 4]]);
+        assert_eq!(genome.modes[6].child_a.mode_number, 6);
+        assert_eq!(genome.modes[6].split_interval, 20.0);
+    }
+}
@@ -0,0 +1,127 @@
+use std::time::{Duration, Instant};
+
+use super::{GenomeData, ModeRemap};
+
+/// Most snapshots kept on each stack; older ones are dropped
+const MAX_HISTORY: usize = 50;
+
+/// Repeated scalar edits with the same label this close together undo as one step
+const SCALAR_COALESCE_WINDOW: Duration = Duration::from_secs(1);
+
+/// What kind of change a snapshot was taken before
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum EditKind {
+    /// Values changed in place; bursts of the same edit are merged into one step
+    Scalar,
+    /// Modes added, removed or reordered with their references remapped; always its own step
+    Structural,
+}
+
+struct Snapshot {
+    label: String,
+    kind: EditKind,
+    genome: GenomeData,
+    /// How the edit renumbered modes, so undo and redo can renumber references to them too
+    remap: Option<ModeRemap>,
+    /// When the edit last touched this step, or None once it can't be merged into any more
+    last_edit: Option<Instant>,
+}
+
+/// Whole-genome snapshots taken before bulk edits, so each edit can be undone as one step
+#[derive(Default)]
+pub struct GenomeHistory {
+    undo: Vec<Snapshot>,
+    redo: Vec<Snapshot>,
+}
+
+impl GenomeHistory {
+    /// Remember `genome` as it was before the edit called `label`
+    pub fn record(&mut self, label: impl Into<String>, kind: EditKind, genome: &GenomeData) {
+        let label = label.into();
+        self.redo.clear();
+
+        // The earlier snapshot already holds the state from before this burst of edits
+        if kind == EditKind::Scalar
+            && let Some(last) = self.undo.last_mut()
+            && last.kind == EditKind::Scalar
+            && last.label == label
+            && last.last_edit.is_some_and(|at| at.elapsed() < SCALAR_COALESCE_WINDOW)
+        {
+            last.last_edit = Some(Instant::now());
+            return;
+        }
+
+        self.undo.push(Snapshot {
+            label,
+            kind,
+            genome: genome.clone(),
+            remap: None,
+            last_edit: Some(Instant::now()),
+        });
+        if self.undo.len() > MAX_HISTORY {
+            self.undo.remove(0);
+        }
+    }
+
+    /// Remember how the last recorded edit renumbered modes
+    pub fn set_remap(&mut self, remap: ModeRemap) {
+        if let Some(last) = self.undo.last_mut() {
+            last.remap = Some(remap);
+        }
+    }
+
+    pub fn undo_label(&self) -> Option<&str> {
+        self.undo.last().map(|snapshot| snapshot.label.as_str())
+    }
+
+    pub fn redo_label(&self) -> Option<&str> {
+        self.redo.last().map(|snapshot| snapshot.label.as_str())
+    }
+
+    /// Swap `genome` back to the last snapshot, returning the undone edit's label and, if it
+    /// renumbered modes, how to renumber them back
+    pub fn undo(&mut self, genome: &mut GenomeData) -> Option<(String, Option<ModeRemap>)> {
+        let snapshot = self.undo.pop()?;
+        let label = snapshot.label.clone();
+        let remap = snapshot.remap.as_ref().map(|remap| invert(remap, genome.modes.len()));
+        self.redo.push(swap_snapshot(snapshot, genome));
+        Some((label, remap))
+    }
+
+    /// Reapply the last undone edit, returning its label and how it renumbered modes
+    pub fn redo(&mut self, genome: &mut GenomeData) -> Option<(String, Option<ModeRemap>)> {
+        let snapshot = self.redo.pop()?;
+        let label = snapshot.label.clone();
+        let remap = snapshot.remap.clone();
+        self.undo.push(swap_snapshot(snapshot, genome));
+        Some((label, remap))
+    }
+
+    pub fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+    }
+}
+
+/// Put the snapshot's genome in place, returning a snapshot of what it replaced
+fn swap_snapshot(snapshot: Snapshot, genome: &mut GenomeData) -> Snapshot {
+    Snapshot {
+        genome: std::mem::replace(genome, snapshot.genome),
+        // Never merge into a step that was undone or redone
+        last_edit: None,
+        ..snapshot
+    }
+}
+
+/// Remap from the `len` modes after an edit back to the modes before it. Modes the edit
+/// added map to None; references to modes it removed are already gone.
+fn invert(remap: &ModeRemap, len: usize) -> ModeRemap {
+    let mut inverse = vec![None; len];
+    for (old, new) in remap.iter().enumerate() {
+        if let Some(slot) = new.and_then(|new| inverse.get_mut(new)) {
+            // Merged modes share a new index; the first of them is the one that survived
+            slot.get_or_insert(old);
+        }
+    }
+    inverse
+}
@@ -1,13 +1,29 @@
 use bevy::prelude::*;
 use serde::{Serialize, Deserialize};
 
+pub mod archive;
+pub mod backups;
+pub mod diff;
+pub mod duplicates;
+pub mod history;
+pub mod palette;
+pub mod query;
+pub mod random;
+pub mod ranges;
+pub mod serde_math;
+pub mod templates;
+
+pub use archive::GenomeArchiveState;
+
 /// Plugin for genome management
 pub struct GenomePlugin;
 
 impl Plugin for GenomePlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<GenomeLibrary>()
-            .init_resource::<CurrentGenome>();
+            .init_resource::<CurrentGenome>()
+            .init_resource::<GenomeArchiveState>()
+            .add_systems(Update, (archive::poll_archive_tasks, auto_save_genome, backups::write_safety_backups));
     }
 }
 
@@ -18,10 +34,118 @@ pub struct GenomeLibrary {
 }
 
 impl GenomeLibrary {
-    #[allow(dead_code)]
     pub fn add_genome(&mut self, genome: GenomeData) {
+        if self.genomes.iter().any(|existing| same_name(&existing.name, &genome.name)) {
+            warn!("Library already has a genome named {:?}", genome.name);
+        }
         self.genomes.push(genome);
     }
+
+    /// Names used by more than one genome in the library, in first-seen order.
+    /// Names are compared ignoring case and surrounding whitespace, like the files they'd be saved as.
+    pub fn duplicate_names(&self) -> Vec<String> {
+        let mut duplicates: Vec<String> = Vec::new();
+        for (i, genome) in self.genomes.iter().enumerate() {
+            if let Some(first) = self.genomes[..i].iter().find(|other| same_name(&other.name, &genome.name))
+                && !duplicates.iter().any(|name| same_name(name, &genome.name))
+            {
+                duplicates.push(first.name.clone());
+            }
+        }
+        duplicates
+    }
+}
+
+fn same_name(a: &str, b: &str) -> bool {
+    a.trim().to_lowercase() == b.trim().to_lowercase()
+}
+
+/// Where the periodic genome autosave is written, inside the config folder
+const GENOME_AUTOSAVE_FILE: &str = "genome_autosave.json";
+
+/// Periodically write the current genome to the config folder if it changed since the last autosave
+fn auto_save_genome(
+    time: Res<Time>,
+    mut save_timer: Local<Timer>,
+    mut last_saved: Local<Option<GenomeData>>,
+    current_genome: Res<CurrentGenome>,
+    preferences: Res<crate::preferences::Preferences>,
+) {
+    let Some(interval) = preferences.genome_autosave_interval() else {
+        return;
+    };
+    if save_timer.duration() != interval || save_timer.mode() != TimerMode::Repeating {
+        *save_timer = Timer::new(interval, TimerMode::Repeating);
+    }
+
+    save_timer.tick(time.delta());
+    if !save_timer.just_finished() || last_saved.as_ref() == Some(&current_genome.genome) {
+        return;
+    }
+
+    match current_genome.write_autosave(preferences.genome_autosave_backups()) {
+        Ok(()) => *last_saved = Some(current_genome.genome.clone()),
+        Err(err) => warn!("Failed to autosave genome: {}", err),
+    }
+}
+
+/// `path` with `.n` before its extension, e.g. `genome_autosave.2.json`
+fn backup_path(path: &std::path::Path, n: usize) -> std::path::PathBuf {
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    match path.extension() {
+        Some(extension) => path.with_file_name(format!("{}.{}.{}", stem, n, extension.to_string_lossy())),
+        None => path.with_file_name(format!("{}.{}", stem, n)),
+    }
+}
+
+/// Shift `path`'s numbered backups up by one and copy `path` into backup 1, keeping at most
+/// `keep` of them. Backups past `keep`, e.g. after the count was lowered, are deleted.
+/// The copy keeps `path`'s modification time, which is when that version was written.
+fn rotate_backups(path: &std::path::Path, keep: usize) -> std::io::Result<()> {
+    let mut n = keep + 1;
+    while backup_path(path, n).exists() {
+        std::fs::remove_file(backup_path(path, n))?;
+        n += 1;
+    }
+    if keep == 0 || !path.exists() {
+        return Ok(());
+    }
+    for n in (1..keep).rev() {
+        let from = backup_path(path, n);
+        if from.exists() {
+            std::fs::rename(&from, backup_path(path, n + 1))?;
+        }
+    }
+    let modified = std::fs::metadata(path)?.modified()?;
+    let backup = backup_path(path, 1);
+    std::fs::copy(path, &backup)?;
+    std::fs::File::options().write(true).open(&backup)?.set_modified(modified)
+}
+
+/// Range of `ModeSettings::split_interval` allowed by the editor, in seconds
+pub const SPLIT_INTERVAL_MIN: f32 = 1.0;
+pub const SPLIT_INTERVAL_MAX: f32 = 60.0;
+
+/// Longest genome or mode name the editor accepts, in characters
+pub const MAX_NAME_CHARS: usize = 64;
+
+/// Name a new genome gets, and a genome whose name is cleared falls back to
+pub const UNTITLED_GENOME_NAME: &str = "Untitled Genome";
+
+/// `name` trimmed and cut to [`MAX_NAME_CHARS`], or `fallback` if nothing is left
+pub fn clean_name(name: &str, fallback: &str) -> String {
+    let trimmed = name.trim();
+    let name = if trimmed.is_empty() { fallback } else { trimmed };
+    name.chars().take(MAX_NAME_CHARS).collect::<String>().trim_end().to_string()
+}
+
+/// Display names for `ModeSettings::cell_type`, indexed by type id
+pub const CELL_TYPE_NAMES: [&str; 5] = ["Photocyte", "Phagocyte", "Flagellocyte", "Devorocyte", "Lipocyte"];
+
+/// Name "Auto-Name Modes" gives mode `index`, e.g. "Photocyte 0"
+pub fn auto_mode_name(cell_type: i32, index: usize) -> String {
+    let type_name = usize::try_from(cell_type).ok().and_then(|i| CELL_TYPE_NAMES.get(i)).copied().unwrap_or("Mode");
+    format!("{} {}", type_name, index)
 }
 
 /// Current genome being edited/used
@@ -29,13 +153,234 @@ impl GenomeLibrary {
 pub struct CurrentGenome {
     pub genome: GenomeData,
     pub selected_mode_index: i32,
+    /// File the genome was last loaded from or saved to
+    pub file_path: Option<std::path::PathBuf>,
+    /// Undo/redo for bulk edits to `genome`
+    pub history: history::GenomeHistory,
+    /// `genome` as it was last opened or saved; there are unsaved changes while they differ
+    saved_genome: GenomeData,
+    /// Snapshots taken by [`CurrentGenome::back_up`], waiting to be written to disk
+    pending_backups: Vec<GenomeData>,
+    /// Renumberings from deleting, moving or merging modes, for state outside the genome that
+    /// refers to modes by index
+    pending_remaps: Vec<ModeRemap>,
 }
 
+/// Each old mode index's new index after modes were renumbered, None for a removed mode
+pub type ModeRemap = Vec<Option<usize>>;
+
 impl Default for CurrentGenome {
     fn default() -> Self {
         Self {
             genome: GenomeData::default(),
             selected_mode_index: 0,
+            file_path: None,
+            history: history::GenomeHistory::default(),
+            saved_genome: GenomeData::default(),
+            pending_backups: Vec::new(),
+            pending_remaps: Vec::new(),
+        }
+    }
+}
+
+impl CurrentGenome {
+    /// Replace the edited genome, returning to the mode that was selected when it was saved
+    pub fn open(&mut self, genome: GenomeData) {
+        let last_mode = genome.last_selected_mode;
+        self.saved_genome = genome.clone();
+        self.genome = genome;
+        self.file_path = None;
+        self.history.clear();
+        self.selected_mode_index = last_mode;
+        self.clamp_selection();
+    }
+
+    /// Replace the edited genome with one from outside the editor (e.g. the clipboard) as a
+    /// single undoable step, so unsaved work replaced by accident can be brought back
+    pub fn replace_undoably(&mut self, genome: GenomeData, label: impl Into<String>) {
+        self.back_up();
+        self.record_structural_edit(label);
+        self.selected_mode_index = genome.last_selected_mode;
+        self.genome = genome;
+        self.file_path = None;
+        self.clamp_selection();
+    }
+
+    /// Remember that the genome was just written to `path`
+    pub fn mark_saved(&mut self, path: std::path::PathBuf) {
+        self.file_path = Some(path);
+        self.saved_genome = self.genome.clone();
+    }
+
+    /// Whether the genome has changed since it was last opened or saved
+    pub fn is_dirty(&self) -> bool {
+        self.genome != self.saved_genome
+    }
+
+    /// Snapshot the genome before an edit so it can be undone in one step
+    pub fn record_edit(&mut self, label: impl Into<String>) {
+        self.history.record(label, history::EditKind::Scalar, &self.genome);
+    }
+
+    /// Record an edit already made in place to mode `idx`, which was `before` until now.
+    /// Called every frame a slider is dragged; [`CurrentGenome::record_edit`] merges the burst.
+    pub fn record_mode_edit(&mut self, idx: usize, mut before: ModeSettings, label: impl Into<String>) {
+        let Some(mode) = self.genome.modes.get_mut(idx) else {
+            return;
+        };
+        std::mem::swap(mode, &mut before);
+        self.record_edit(label);
+        std::mem::swap(&mut self.genome.modes[idx], &mut before);
+    }
+
+    /// Snapshot the genome before modes are added, removed or reordered.
+    /// These are never merged with other edits, so one undo reverses the whole remap.
+    pub fn record_structural_edit(&mut self, label: impl Into<String>) {
+        self.history.record(label, history::EditKind::Structural, &self.genome);
+    }
+
+    /// Append a new self-splitting mode and select it
+    pub fn add_mode(&mut self) {
+        self.record_structural_edit("Add Mode");
+        self.selected_mode_index = self.genome.add_mode() as i32;
+    }
+
+    /// Delete mode `idx` as one undoable step. The last remaining mode can't be deleted.
+    pub fn delete_mode(&mut self, idx: usize) -> bool {
+        if self.genome.modes.len() <= 1 || idx >= self.genome.modes.len() {
+            return false;
+        }
+        let label = format!("Delete {}", self.genome.modes[idx].name);
+        self.back_up();
+        self.record_structural_edit(label);
+        let remap = self.genome.delete_mode(idx);
+        self.renumber_modes(remap);
+        self.clamp_selection();
+        true
+    }
+
+    /// Move mode `from` to position `to` as one undoable step, keeping it selected
+    pub fn move_mode(&mut self, from: usize, to: usize) -> bool {
+        let len = self.genome.modes.len();
+        if from >= len || to >= len || from == to {
+            return false;
+        }
+        let label = format!("Move {}", self.genome.modes[from].name);
+        self.record_structural_edit(label);
+        let new_index = self.genome.move_mode(from, to);
+        self.renumber_modes(new_index.into_iter().map(Some).collect());
+        self.selected_mode_index = to as i32;
+        true
+    }
+
+    /// Merge a group of duplicate modes into its first one as one undoable step,
+    /// keeping the selection on the same mode (or the one it was merged into)
+    pub fn merge_modes(&mut self, group: &[usize]) -> bool {
+        let len = self.genome.modes.len();
+        if group.len() < 2 || group.iter().any(|&idx| idx >= len) {
+            return false;
+        }
+        let survivor = group.iter().copied().min().unwrap_or(0);
+        let label = format!("Merge {} Modes into {}", group.len(), self.genome.modes[survivor].name);
+        self.back_up();
+        self.record_structural_edit(label);
+        let new_index = self.genome.merge_modes(group);
+        if let Some(&selected) = usize::try_from(self.selected_mode_index).ok().and_then(|i| new_index.get(i)) {
+            self.selected_mode_index = selected as i32;
+        }
+        self.renumber_modes(new_index.into_iter().map(Some).collect());
+        self.clamp_selection();
+        true
+    }
+
+    /// Append `other`'s modes as one undoable step. With `merge_duplicates`, appended modes that
+    /// only differ by name from an existing one are merged into it. Returns how many modes were
+    /// added and how many of those were merged away.
+    pub fn append_modes(&mut self, other: &GenomeData, merge_duplicates: bool) -> (usize, usize) {
+        self.record_structural_edit(format!("Append Modes from {}", other.name));
+        let first_appended = self.genome.append_modes(other);
+        let merged = if merge_duplicates { self.genome.merge_duplicates_from(first_appended) } else { 0 };
+        (other.modes.len(), merged)
+    }
+
+    /// Recolor every unpinned mode with `strategy` as one undoable step
+    pub fn recolor(&mut self, strategy: palette::RecolorStrategy) {
+        self.record_edit(strategy.label());
+        palette::apply_recolor(&mut self.genome.modes, strategy);
+    }
+
+    /// Spread the default palette over the current modes as one undoable step, keeping
+    /// hand-picked and pinned colors
+    pub fn respread_default_colors(&mut self) {
+        self.record_edit("Respread Default Colors");
+        palette::respread_default_colors(&mut self.genome.modes);
+    }
+
+    /// Queue a snapshot of the genome for the safety backups in the config folder. Called before
+    /// operations that replace the genome or remove modes, whose undo history doesn't survive a restart.
+    pub fn back_up(&mut self) {
+        self.pending_backups.push(self.genome_for_save());
+    }
+
+    /// Snapshots queued by [`CurrentGenome::back_up`] since the last call, oldest first
+    pub fn take_pending_backups(&mut self) -> Vec<GenomeData> {
+        std::mem::take(&mut self.pending_backups)
+    }
+
+    /// Queue a renumbering for state outside the genome, and keep it with the edit's undo step
+    /// so undo and redo renumber that state as well
+    fn renumber_modes(&mut self, remap: ModeRemap) {
+        self.history.set_remap(remap.clone());
+        self.pending_remaps.push(remap);
+    }
+
+    /// Mode renumberings since the last call, oldest first
+    pub fn take_mode_remaps(&mut self) -> Vec<ModeRemap> {
+        std::mem::take(&mut self.pending_remaps)
+    }
+
+    /// Revert the last recorded edit, returning its label
+    pub fn undo(&mut self) -> Option<String> {
+        let (label, remap) = self.history.undo(&mut self.genome)?;
+        self.pending_remaps.extend(remap);
+        self.clamp_selection();
+        Some(label)
+    }
+
+    /// Reapply the last undone edit, returning its label
+    pub fn redo(&mut self) -> Option<String> {
+        let (label, remap) = self.history.redo(&mut self.genome)?;
+        self.pending_remaps.extend(remap);
+        self.clamp_selection();
+        Some(label)
+    }
+
+    /// Keep the selection and the initial mode on an existing mode (index 0 for an empty genome),
+    /// so panels that guard against an out-of-range index don't get stuck showing nothing after
+    /// the genome shrinks
+    pub fn clamp_selection(&mut self) {
+        let max_index = self.genome.modes.len().saturating_sub(1) as i32;
+        self.selected_mode_index = self.selected_mode_index.clamp(0, max_index);
+        self.genome.initial_mode = self.genome.initial_mode.clamp(0, max_index);
+    }
+
+    /// Write the genome to the autosave file in the config folder, first moving the previous
+    /// autosave into a ring of `backups` numbered copies
+    pub fn write_autosave(&self, backups: usize) -> Result<(), Box<dyn std::error::Error>> {
+        let path = crate::dock::config_dir().join(GENOME_AUTOSAVE_FILE);
+        if let Err(err) = rotate_backups(&path, backups) {
+            warn!("Failed to rotate genome autosave backups: {}", err);
+        }
+        self.genome_for_save()
+            .save_to_file(&path)
+            .map_err(|err| format!("{}: {}", path.display(), err).into())
+    }
+
+    /// Copy of the genome to write to disk, remembering the currently selected mode
+    pub fn genome_for_save(&self) -> GenomeData {
+        GenomeData {
+            last_selected_mode: self.selected_mode_index,
+            ..self.genome.clone()
         }
     }
 }
@@ -74,10 +419,47 @@ impl Default for AdhesionSettings {
     }
 }
 
+/// Damping ratios outside this band get flagged in the editor
+pub const HEALTHY_DAMPING_RATIO: std::ops::RangeInclusive<f32> = 0.1..=2.0;
+
+/// How a stiffness/damping pair is expected to behave
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum SpringHealth {
+    Healthy,
+    /// Rings for a long time and can blow up at large time steps
+    UnderDamped,
+    /// Creeps back to rest slowly
+    OverDamped,
+}
+
+/// Damping ratio ζ = c / (2·√(k·m)) of a spring with `stiffness` k and `damping` c moving `mass` m.
+/// 1 is critically damped; a spring with no stiffness can't oscillate and counts as infinitely damped.
+pub fn damping_ratio(stiffness: f32, damping: f32, mass: f32) -> f32 {
+    let critical = 2.0 * (stiffness * mass).max(0.0).sqrt();
+    if critical > 0.0 {
+        damping / critical
+    } else {
+        f32::INFINITY
+    }
+}
+
+impl SpringHealth {
+    pub fn classify(ratio: f32) -> Self {
+        if ratio < *HEALTHY_DAMPING_RATIO.start() {
+            Self::UnderDamped
+        } else if ratio > *HEALTHY_DAMPING_RATIO.end() {
+            Self::OverDamped
+        } else {
+            Self::Healthy
+        }
+    }
+}
+
 /// Child settings for mode transitions
 #[derive(Clone, PartialEq, Serialize, Deserialize)]
 pub struct ChildSettings {
     pub mode_number: i32,
+    #[serde(with = "serde_math::quat")]
     pub orientation: Quat,
     pub keep_adhesion: bool,
     pub enable_angle_snapping: bool,
@@ -118,10 +500,20 @@ impl Default for ChildSettings {
 pub struct ModeSettings {
     pub name: String,
     pub default_name: String,
+    #[serde(with = "serde_math::vec3")]
     pub color: Vec3,
     pub opacity: f32,
     #[serde(default)]
     pub emissive: f32,
+    /// Pinned colors are left alone by the recolor actions
+    #[serde(default)]
+    pub color_pinned: bool,
+    /// Set when the user picks the color, so respreading the default palette keeps it
+    #[serde(default)]
+    pub color_is_custom: bool,
+    /// Starred in the Modes panel
+    #[serde(default)]
+    pub favorite: bool,
 
     // Cell type
     pub cell_type: i32,
@@ -139,6 +531,7 @@ pub struct ModeSettings {
     pub split_ratio: f32,
     pub nutrient_priority: f32,
     pub prioritize_when_low: bool,
+    #[serde(with = "serde_math::vec2")]
     pub parent_split_direction: Vec2,
     pub max_adhesions: i32,
     pub min_adhesions: i32,
@@ -150,6 +543,14 @@ pub struct ModeSettings {
     // Flagellocyte settings
     pub swim_force: f32,
 
+    // Devorocyte settings
+    #[serde(default = "default_devour_rate")]
+    pub devour_rate: f32,
+
+    // Lipocyte settings
+    #[serde(default = "default_storage_capacity")]
+    pub storage_capacity: f32,
+
     // Child settings
     pub child_a: ChildSettings,
     pub child_b: ChildSettings,
@@ -158,6 +559,54 @@ pub struct ModeSettings {
     pub adhesion_settings: AdhesionSettings,
 }
 
+fn default_devour_rate() -> f32 {
+    1.0
+}
+
+fn default_storage_capacity() -> f32 {
+    5.0
+}
+
+/// Common `parent_split_direction` values for roughing out a genome
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum SplitDirectionPreset {
+    /// Straight ahead: pitch and yaw both 0°
+    Zero,
+    /// Sideways, in the horizontal plane
+    Equatorial,
+    /// Straight up
+    Polar,
+    /// Uniformly distributed over the sphere
+    Random,
+}
+
+impl SplitDirectionPreset {
+    pub const ALL: [SplitDirectionPreset; 4] = [Self::Equatorial, Self::Polar, Self::Random, Self::Zero];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Zero => "Zero",
+            Self::Equatorial => "Equatorial",
+            Self::Polar => "Polar",
+            Self::Random => "Random",
+        }
+    }
+
+    /// (pitch, yaw) in degrees. Only `Random` uses `rng`.
+    pub fn direction(self, rng: &mut random::SeededRng) -> Vec2 {
+        match self {
+            Self::Zero => Vec2::new(0.0, 0.0),
+            Self::Equatorial => Vec2::new(0.0, 90.0),
+            Self::Polar => Vec2::new(90.0, 0.0),
+            // asin of a uniform height keeps directions from bunching up at the poles
+            Self::Random => Vec2::new(
+                rng.range_f32(-1.0, 1.0).asin().to_degrees(),
+                rng.range_f32(-180.0, 180.0),
+            ),
+        }
+    }
+}
+
 impl ModeSettings {
     /// Create a new mode that splits back to itself
     pub fn new_self_splitting(mode_index: i32, name: String) -> Self {
@@ -167,6 +616,9 @@ impl ModeSettings {
             color: Vec3::new(1.0, 1.0, 1.0),
             opacity: 1.0,
             emissive: 0.0,
+            color_pinned: false,
+            color_is_custom: false,
+            favorite: false,
             cell_type: 0,
             parent_make_adhesion: false,
             split_mass: 1.5,
@@ -186,6 +638,8 @@ impl ModeSettings {
             mode_a_after_splits: -1,
             mode_b_after_splits: -1,
             swim_force: 0.5,
+            devour_rate: default_devour_rate(),
+            storage_capacity: default_storage_capacity(),
             child_a: ChildSettings {
                 mode_number: mode_index,
                 ..Default::default()
@@ -199,6 +653,45 @@ impl ModeSettings {
     }
 }
 
+impl ModeSettings {
+    /// Save a single mode to a JSON file
+    pub fn save_to_file(&self, path: &std::path::Path) -> Result<(), Box<dyn std::error::Error>> {
+        let json = serde_json::to_string_pretty(self)?;
+        std::fs::write(path, json)?;
+        Ok(())
+    }
+
+    /// Load a single mode from a JSON file, clamping its parameters to the editor's ranges
+    pub fn load_from_file(path: &std::path::Path) -> Result<Self, Box<dyn std::error::Error>> {
+        let json = std::fs::read_to_string(path)?;
+        let mut mode: Self = serde_json::from_str(&json)?;
+        if mode.clamp_to_editor_ranges() {
+            warn!("Clamped out-of-range parameters into the editor's ranges in mode {}", mode.name);
+        }
+        Ok(mode)
+    }
+
+    /// Bring mode references into range for a genome with `mode_count` modes.
+    /// Child modes are clamped; out-of-range "after splits" modes fall back to -1 (unset).
+    /// Returns true if anything had to be changed.
+    pub fn clamp_mode_references(&mut self, mode_count: usize) -> bool {
+        let last = mode_count.saturating_sub(1) as i32;
+        let mut changed = false;
+        for child in [&mut self.child_a, &mut self.child_b] {
+            let clamped = child.mode_number.clamp(0, last);
+            changed |= clamped != child.mode_number;
+            child.mode_number = clamped;
+        }
+        for after_splits in [&mut self.mode_a_after_splits, &mut self.mode_b_after_splits] {
+            if *after_splits < -1 || *after_splits > last {
+                *after_splits = -1;
+                changed = true;
+            }
+        }
+        changed
+    }
+}
+
 impl Default for ModeSettings {
     fn default() -> Self {
         Self {
@@ -207,6 +700,9 @@ impl Default for ModeSettings {
             color: Vec3::new(1.0, 1.0, 1.0),
             opacity: 1.0,
             emissive: 0.0,
+            color_pinned: false,
+            color_is_custom: false,
+            favorite: false,
             cell_type: 0,
             parent_make_adhesion: false,
             split_mass: 1.5,
@@ -226,6 +722,8 @@ impl Default for ModeSettings {
             mode_a_after_splits: -1,
             mode_b_after_splits: -1,
             swim_force: 0.5,
+            devour_rate: default_devour_rate(),
+            storage_capacity: default_storage_capacity(),
             child_a: ChildSettings::default(),
             child_b: ChildSettings::default(),
             adhesion_settings: AdhesionSettings::default(),
@@ -233,33 +731,60 @@ impl Default for ModeSettings {
     }
 }
 
+/// Number of modes in a new genome; also sets the spacing of default mode colors
+pub const DEFAULT_MODE_COUNT: usize = 120;
+
 /// A complete genome definition
-#[derive(Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct GenomeData {
     pub name: String,
     pub initial_mode: i32,
+    #[serde(with = "serde_math::quat")]
     pub initial_orientation: Quat,
     pub modes: Vec<ModeSettings>,
+    /// Seed used by Randomize, so a random genome can be reproduced and shared
+    #[serde(default)]
+    pub seed: Option<u64>,
+    // Editor state saved with the genome (not part of the genome's identity)
+    #[serde(default)]
+    pub last_selected_mode: i32,
+    /// Genome Graph node positions from the last auto-arrange, one per mode in 0..=1
+    #[serde(default)]
+    pub graph_positions: Vec<[f32; 2]>,
+}
+
+// Manual impl so editor state like `last_selected_mode` doesn't make two genomes differ.
+// Graph positions are arranged by the user and saved, so rearranging counts as a change.
+impl PartialEq for GenomeData {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name
+            && self.initial_mode == other.initial_mode
+            && self.initial_orientation == other.initial_orientation
+            && self.modes == other.modes
+            && self.seed == other.seed
+            && self.graph_positions == other.graph_positions
+    }
 }
 
 impl Default for GenomeData {
     fn default() -> Self {
         let mut genome = Self {
-            name: "Untitled Genome".to_string(),
+            name: UNTITLED_GENOME_NAME.to_string(),
             initial_mode: 0,
             initial_orientation: Quat::IDENTITY,
             modes: Vec::new(),
+            seed: None,
+            last_selected_mode: 0,
+            graph_positions: Vec::new(),
         };
         
         // Create all 120 modes
-        for i in 0..120 {
+        for i in 0..DEFAULT_MODE_COUNT {
             let mode_name = format!("M {}", i);
             let mut mode = ModeSettings::new_self_splitting(i as i32, mode_name);
             
             // Generate a color based on the mode number using HSV
-            let hue = (i as f32 / 120.0) * 360.0;
-            let (r, g, b) = hue_to_rgb(hue);
-            mode.color = Vec3::new(r as f32 / 255.0, g as f32 / 255.0, b as f32 / 255.0);
+            mode.color = palette::index_color(i, DEFAULT_MODE_COUNT);
             
             genome.modes.push(mode);
         }
@@ -268,45 +793,734 @@ impl Default for GenomeData {
     }
 }
 
-// Helper function to convert HSV hue to RGB
-fn hue_to_rgb(hue: f32) -> (u8, u8, u8) {
-    let h = hue / 60.0;
-    let c = 1.0;
-    let x = 1.0 - (h % 2.0 - 1.0).abs();
-    
-    let (r, g, b) = if h < 1.0 {
-        (c, x, 0.0)
-    } else if h < 2.0 {
-        (x, c, 0.0)
-    } else if h < 3.0 {
-        (0.0, c, x)
-    } else if h < 4.0 {
-        (0.0, x, c)
-    } else if h < 5.0 {
-        (x, 0.0, c)
-    } else {
-        (c, 0.0, x)
-    };
-    
-    // Scale to 100-255 range for better visibility
-    let scale = |v: f32| ((v * 155.0) + 100.0) as u8;
-    (scale(r), scale(g), scale(b))
-}
-
 impl GenomeData {
     /// Save genome to a JSON file
-    #[allow(dead_code)]
     pub fn save_to_file(&self, path: &std::path::Path) -> Result<(), Box<dyn std::error::Error>> {
         let json = serde_json::to_string_pretty(self)?;
         std::fs::write(path, json)?;
         Ok(())
     }
 
+    /// Indices of the other modes whose child A or child B becomes mode `idx`.
+    /// A mode splitting back into itself isn't counted as a reference.
+    pub fn referencing_modes(&self, idx: usize) -> Vec<usize> {
+        self.modes.iter()
+            .enumerate()
+            .filter(|(i, mode)| {
+                *i != idx
+                    && (mode.child_a.mode_number as usize == idx || mode.child_b.mode_number as usize == idx)
+            })
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    /// Modes a cell starting in the initial mode can end up in, through either child or an "after splits" mode.
+    /// Sorted by index; empty if the initial mode doesn't exist.
+    pub fn reachable_modes(&self) -> Vec<usize> {
+        let valid = |idx: i32| usize::try_from(idx).ok().filter(|&i| i < self.modes.len());
+        let mut seen = vec![false; self.modes.len()];
+        let mut stack: Vec<usize> = valid(self.initial_mode).into_iter().collect();
+        while let Some(idx) = stack.pop() {
+            if std::mem::replace(&mut seen[idx], true) {
+                continue;
+            }
+            stack.extend(self.transitions(idx).filter_map(valid));
+        }
+        (0..self.modes.len()).filter(|&i| seen[i]).collect()
+    }
+
+    /// Fewest transitions from the initial mode to each mode (0 for the initial mode itself),
+    /// or `None` for modes that aren't reachable
+    pub fn mode_depths(&self) -> Vec<Option<usize>> {
+        let valid = |idx: i32| usize::try_from(idx).ok().filter(|&i| i < self.modes.len());
+        let mut depths = vec![None; self.modes.len()];
+        let mut queue: std::collections::VecDeque<usize> = valid(self.initial_mode).into_iter().collect();
+        if let Some(&initial) = queue.front() {
+            depths[initial] = Some(0);
+        }
+        while let Some(idx) = queue.pop_front() {
+            let next = depths[idx].map(|depth| depth + 1);
+            for child in self.transitions(idx).filter_map(valid) {
+                if depths[child].is_none() {
+                    depths[child] = next;
+                    queue.push_back(child);
+                }
+            }
+        }
+        depths
+    }
+
+    /// Every mode index mode `idx` can turn into, including unset (-1) and self references
+    fn transitions(&self, idx: usize) -> impl Iterator<Item = i32> {
+        let mode = &self.modes[idx];
+        [mode.child_a.mode_number, mode.child_b.mode_number, mode.mode_a_after_splits, mode.mode_b_after_splits].into_iter()
+    }
+
+    /// Single number for comparing how involved genomes are. Only reachable modes count:
+    ///
+    /// `score = reachable + transitions + 10 × variation`
+    ///
+    /// - `reachable`: number of modes reachable from the initial mode
+    /// - `transitions`: distinct mode-to-other-mode transitions among them (a mode that only
+    ///   splits into itself adds nothing), so branching genomes score higher than chains
+    /// - `variation`: mean coefficient of variation (standard deviation / mean) of split interval,
+    ///   split mass and nutrient priority; 0 when every reachable mode uses the same values
+    ///
+    /// The default genome scores 1: one reachable self-splitting mode.
+    pub fn complexity_score(&self) -> f32 {
+        let reachable = self.reachable_modes();
+        if reachable.is_empty() {
+            return 0.0;
+        }
+
+        let transitions: std::collections::HashSet<(usize, i32)> = reachable.iter()
+            .flat_map(|&i| self.transitions(i).map(move |target| (i, target)))
+            .filter(|&(i, target)| target >= 0 && target as usize != i)
+            .collect();
+
+        let parameters: [fn(&ModeSettings) -> f32; 3] = [
+            |mode| mode.split_interval,
+            |mode| mode.split_mass,
+            |mode| mode.nutrient_priority,
+        ];
+        let count = reachable.len() as f32;
+        let variation = parameters.iter()
+            .map(|parameter| {
+                let values: Vec<f32> = reachable.iter().map(|&i| parameter(&self.modes[i])).collect();
+                let mean = values.iter().sum::<f32>() / count;
+                let variance = values.iter().map(|v| (v - mean).powi(2)).sum::<f32>() / count;
+                variance.sqrt() / mean.abs().max(f32::EPSILON)
+            })
+            .sum::<f32>() / parameters.len() as f32;
+
+        count + transitions.len() as f32 + 10.0 * variation
+    }
+
+    /// Modes whose name was changed from the one they were created or auto-named with
+    pub fn custom_named_modes(&self) -> usize {
+        self.modes.iter().filter(|mode| mode.name != mode.default_name).count()
+    }
+
+    /// Name every mode after its cell type and index. The new name also becomes the mode's
+    /// default name, so it is what a cleared name falls back to.
+    pub fn auto_name_modes(&mut self) {
+        for (i, mode) in self.modes.iter_mut().enumerate() {
+            mode.name = auto_mode_name(mode.cell_type, i);
+            mode.default_name = mode.name.clone();
+        }
+    }
+
+    /// Multiply every mode's split interval by `factor`, keeping results within the editor's 1-60s range.
+    /// With `scale_minimums`, randomized minimums are scaled too. Minimums are always kept at or below the interval.
+    pub fn scale_split_intervals(&mut self, factor: f32, scale_minimums: bool) {
+        for mode in &mut self.modes {
+            mode.split_interval = (mode.split_interval * factor).clamp(SPLIT_INTERVAL_MIN, SPLIT_INTERVAL_MAX);
+            if let Some(min) = mode.split_interval_min.as_mut() {
+                let scaled = if scale_minimums { *min * factor } else { *min };
+                *min = scaled.clamp(SPLIT_INTERVAL_MIN, mode.split_interval);
+            }
+        }
+    }
+
+    /// Append a self-splitting mode with the next default name and color, returning its index
+    pub fn add_mode(&mut self) -> usize {
+        let idx = self.modes.len();
+        let mut mode = ModeSettings::new_self_splitting(idx as i32, format!("M {}", idx));
+        mode.color = palette::index_color(idx, DEFAULT_MODE_COUNT);
+        self.modes.push(mode);
+        idx
+    }
+
+    /// Append copies of `other`'s modes, renumbering their references so they still point at
+    /// the same modes among the appended ones. Returns the index of the first appended mode.
+    pub fn append_modes(&mut self, other: &GenomeData) -> usize {
+        let offset = self.modes.len();
+        let mut appended = other.clone();
+        appended.remap_mode_references(|old| Some(old + offset));
+        self.modes.append(&mut appended.modes);
+        offset
+    }
+
+    /// Remove mode `idx` and renumber references to the modes after it.
+    /// Children that became the deleted mode split back into their own mode instead,
+    /// "after splits" modes pointing at it are unset, and an initial mode pointing at it resets to 0.
+    /// Returns each old mode index's new index.
+    pub fn delete_mode(&mut self, idx: usize) -> ModeRemap {
+        let new_index: ModeRemap = (0..self.modes.len())
+            .map(|old| match old.cmp(&idx) {
+                std::cmp::Ordering::Less => Some(old),
+                std::cmp::Ordering::Equal => None,
+                std::cmp::Ordering::Greater => Some(old - 1),
+            })
+            .collect();
+        self.modes.remove(idx);
+        self.remap_mode_references(|old| new_index.get(old).copied().flatten());
+        new_index
+    }
+
+    /// Move mode `from` to position `to`, shifting the modes in between and renumbering every reference.
+    /// Returns each old mode index's new index.
+    pub fn move_mode(&mut self, from: usize, to: usize) -> Vec<usize> {
+        let new_index: Vec<usize> = (0..self.modes.len())
+            .map(|old| {
+                if old == from {
+                    to
+                } else if from < to && (from + 1..=to).contains(&old) {
+                    old - 1
+                } else if to < from && (to..from).contains(&old) {
+                    old + 1
+                } else {
+                    old
+                }
+            })
+            .collect();
+        // Graph nodes move with their modes
+        if self.graph_positions.len() == self.modes.len() {
+            let position = self.graph_positions.remove(from);
+            self.graph_positions.insert(to, position);
+        }
+        let mode = self.modes.remove(from);
+        self.modes.insert(to, mode);
+        self.remap_mode_references(|old| new_index.get(old).copied());
+        new_index
+    }
+
+    /// Rewrite every mode reference through `remap`, which maps an old mode index to its new one
+    /// (None if that mode no longer exists). `self.modes` must already be in the new order.
+    fn remap_mode_references(&mut self, remap: impl Fn(usize) -> Option<usize>) {
+        let lookup = |old: i32| usize::try_from(old).ok().and_then(&remap);
+
+        for (i, mode) in self.modes.iter_mut().enumerate() {
+            for child in [&mut mode.child_a, &mut mode.child_b] {
+                child.mode_number = lookup(child.mode_number).unwrap_or(i) as i32;
+            }
+            for after_splits in [&mut mode.mode_a_after_splits, &mut mode.mode_b_after_splits] {
+                if *after_splits >= 0 {
+                    *after_splits = lookup(*after_splits).map_or(-1, |new| new as i32);
+                }
+            }
+        }
+        self.initial_mode = lookup(self.initial_mode).unwrap_or(0) as i32;
+    }
+
     /// Load genome from a JSON file
-    #[allow(dead_code)]
     pub fn load_from_file(path: &std::path::Path) -> Result<Self, Box<dyn std::error::Error>> {
         let json = std::fs::read_to_string(path)?;
-        let genome = serde_json::from_str(&json)?;
+        Self::from_json(&json)
+    }
+
+    /// Parse a genome from JSON text, rejecting one without modes and bringing mode
+    /// references and parameters into range
+    pub fn from_json(json: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        serde_json::from_str::<Self>(json)?.validated()
+    }
+
+    /// Parse a genome from RON text, validated the same way as [`GenomeData::from_json`]
+    pub fn from_ron(ron: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        ron::from_str::<Self>(ron)?.validated()
+    }
+
+    fn validated(self) -> Result<Self, Box<dyn std::error::Error>> {
+        let mut genome = self;
+        if genome.modes.is_empty() {
+            return Err("the genome has no modes".into());
+        }
+        genome.clamp_mode_references();
+        let clamped: Vec<&str> = genome.modes.iter_mut()
+            .filter_map(|mode| mode.clamp_to_editor_ranges().then_some(mode.name.as_str()))
+            .collect();
+        if !clamped.is_empty() {
+            warn!("Clamped out-of-range parameters into the editor's ranges in modes: {}", clamped.join(", "));
+        }
         Ok(genome)
     }
+
+    /// Bring every mode reference, the initial mode included, into range.
+    /// Returns true if anything had to be changed.
+    pub fn clamp_mode_references(&mut self) -> bool {
+        let mode_count = self.modes.len();
+        let mut changed = false;
+        for mode in &mut self.modes {
+            changed |= mode.clamp_mode_references(mode_count);
+        }
+        let initial_mode = self.initial_mode.clamp(0, mode_count.saturating_sub(1) as i32);
+        changed |= initial_mode != self.initial_mode;
+        self.initial_mode = initial_mode;
+        changed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn referencing_modes_finds_either_child_and_skips_self() {
+        let mut genome = GenomeData {
+            modes: (0..4).map(|i| ModeSettings::new_self_splitting(i, format!("M {}", i))).collect(),
+            ..Default::default()
+        };
+        genome.modes[1].child_a.mode_number = 0;
+        genome.modes[3].child_b.mode_number = 0;
+        genome.modes[2].child_a.mode_number = 3;
+
+        assert_eq!(genome.referencing_modes(0), vec![1, 3]);
+        assert_eq!(genome.referencing_modes(3), vec![2]);
+        // Every mode splits into itself, which doesn't count
+        assert!(genome.referencing_modes(1).is_empty());
+    }
+
+    #[test]
+    fn single_mode_round_trips_through_file() {
+        let mut mode = ModeSettings::new_self_splitting(7, "Tuned".to_string());
+        mode.cell_type = 3;
+        mode.child_b.orientation = Quat::from_rotation_y(0.5);
+        mode.adhesion_settings.break_force = 42.0;
+
+        let path = std::env::temp_dir().join(format!("mode_round_trip_{}.json", std::process::id()));
+        mode.save_to_file(&path).unwrap();
+        let loaded = ModeSettings::load_from_file(&path);
+        let _ = std::fs::remove_file(&path);
+        assert!(loaded.unwrap() == mode);
+    }
+
+    #[test]
+    fn autosave_backups_rotate_and_prune() {
+        let dir = std::env::temp_dir().join(format!("autosave_rotation_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("genome_autosave.json");
+        let read = |n: usize| std::fs::read_to_string(backup_path(&path, n)).ok();
+
+        for save in 0..5 {
+            rotate_backups(&path, 3).unwrap();
+            std::fs::write(&path, save.to_string()).unwrap();
+        }
+        assert_eq!((read(1), read(2), read(3), read(4)), (Some("3".into()), Some("2".into()), Some("1".into()), None));
+
+        // A backup is as old as the save it came from
+        let written = std::time::SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(1_000_000_000);
+        std::fs::File::options().write(true).open(&path).unwrap().set_modified(written).unwrap();
+        rotate_backups(&path, 3).unwrap();
+        std::fs::write(&path, "5").unwrap();
+        assert_eq!(std::fs::metadata(backup_path(&path, 1)).unwrap().modified().unwrap(), written);
+
+        // Lowering the count drops the oldest backups on the next save
+        rotate_backups(&path, 1).unwrap();
+        let remaining = (read(1), read(2), read(3));
+        let _ = std::fs::remove_dir_all(&dir);
+        assert_eq!(remaining, (Some("5".into()), None, None));
+    }
+
+    #[test]
+    fn scale_split_intervals_clamps_and_scales_minimums() {
+        let mut genome = GenomeData {
+            modes: vec![ModeSettings::default(), ModeSettings::default(), ModeSettings::default()],
+            ..Default::default()
+        };
+        genome.modes[0].split_interval = 10.0;
+        genome.modes[0].split_interval_min = Some(4.0);
+        genome.modes[1].split_interval = 40.0;
+        genome.modes[2].split_interval = 1.5;
+
+        genome.scale_split_intervals(2.0, true);
+        assert_eq!(genome.modes[0].split_interval, 20.0);
+        assert_eq!(genome.modes[0].split_interval_min, Some(8.0));
+        assert_eq!(genome.modes[1].split_interval, SPLIT_INTERVAL_MAX);
+        assert_eq!(genome.modes[2].split_interval, 3.0);
+
+        genome.scale_split_intervals(0.1, false);
+        assert_eq!(genome.modes[0].split_interval, 2.0);
+        // Not scaled, but pulled down so it doesn't exceed the interval
+        assert_eq!(genome.modes[0].split_interval_min, Some(2.0));
+        assert_eq!(genome.modes[2].split_interval, SPLIT_INTERVAL_MIN);
+    }
+
+    #[test]
+    fn duplicate_names_ignore_case_and_whitespace() {
+        let named = |name: &str| GenomeData { name: name.to_string(), modes: Vec::new(), ..Default::default() };
+        let library = GenomeLibrary {
+            genomes: vec![named("Alpha"), named("Beta"), named("alpha "), named("ALPHA"), named("Gamma")],
+        };
+        assert_eq!(library.duplicate_names(), vec!["Alpha".to_string()]);
+        assert!(GenomeLibrary { genomes: vec![named("Alpha"), named("Beta")] }.duplicate_names().is_empty());
+    }
+
+    #[test]
+    fn damping_ratio_flags_springs_outside_healthy_band() {
+        // c = 2√(km) is critically damped
+        assert!((damping_ratio(100.0, 20.0, 1.0) - 1.0).abs() < 1e-6);
+        assert_eq!(SpringHealth::classify(damping_ratio(500.0, 0.5, 1.0)), SpringHealth::UnderDamped);
+        assert_eq!(SpringHealth::classify(damping_ratio(0.1, 10.0, 1.0)), SpringHealth::OverDamped);
+        assert_eq!(SpringHealth::classify(damping_ratio(0.0, 1.0, 1.0)), SpringHealth::OverDamped);
+
+        // The default adhesion settings shouldn't warn for any mode mass the editor allows
+        let defaults = AdhesionSettings::default();
+        for mass in [1.0, 3.0] {
+            for (stiffness, damping) in [
+                (defaults.linear_spring_stiffness, defaults.linear_spring_damping),
+                (defaults.orientation_spring_stiffness, defaults.orientation_spring_damping),
+                (defaults.twist_constraint_stiffness, defaults.twist_constraint_damping),
+            ] {
+                assert_eq!(SpringHealth::classify(damping_ratio(stiffness, damping, mass)), SpringHealth::Healthy);
+            }
+        }
+    }
+
+    fn linked_genome() -> GenomeData {
+        let mut genome = GenomeData {
+            modes: (0..5).map(|i| ModeSettings::new_self_splitting(i, format!("M {}", i))).collect(),
+            initial_mode: 3,
+            ..Default::default()
+        };
+        genome.modes[0].child_a.mode_number = 2;
+        genome.modes[0].child_b.mode_number = 4;
+        genome.modes[1].child_b.mode_number = 2;
+        genome.modes[3].mode_a_after_splits = 2;
+        genome.modes[4].mode_b_after_splits = 1;
+        genome
+    }
+
+    #[test]
+    fn delete_mode_remaps_references() {
+        let mut genome = linked_genome();
+        assert_eq!(genome.delete_mode(2), [Some(0), Some(1), None, Some(2), Some(3)]);
+
+        assert_eq!(genome.modes.len(), 4);
+        // Mode 0 lost its child A target and splits back into itself; mode 4 is now 3
+        assert_eq!((genome.modes[0].child_a.mode_number, genome.modes[0].child_b.mode_number), (0, 3));
+        assert_eq!(genome.modes[1].child_b.mode_number, 1);
+        assert_eq!(genome.modes[2].mode_a_after_splits, -1);
+        assert_eq!(genome.modes[3].mode_b_after_splits, 1);
+        assert_eq!(genome.initial_mode, 2);
+    }
+
+    #[test]
+    fn move_mode_follows_the_moved_mode() {
+        let mut genome = linked_genome();
+        assert_eq!(genome.move_mode(4, 1), [0, 2, 3, 4, 1]);
+
+        let names: Vec<&str> = genome.modes.iter().map(|mode| mode.name.as_str()).collect();
+        assert_eq!(names, ["M 0", "M 4", "M 1", "M 2", "M 3"]);
+        assert_eq!((genome.modes[0].child_a.mode_number, genome.modes[0].child_b.mode_number), (3, 1));
+        assert_eq!(genome.modes[1].mode_b_after_splits, 2);
+        assert_eq!(genome.modes[4].mode_a_after_splits, 3);
+        assert_eq!(genome.initial_mode, 4);
+        // Self-splitting modes still split into themselves
+        assert!(genome.modes.iter().enumerate().skip(2).all(|(i, mode)| mode.child_a.mode_number == i as i32));
+    }
+
+    #[test]
+    fn renumbering_modes_queues_the_remap() {
+        let mut current = CurrentGenome::default();
+        current.open(linked_genome());
+        current.move_mode(0, 2);
+        current.delete_mode(1);
+        assert_eq!(current.take_mode_remaps(), [
+            vec![Some(2), Some(0), Some(1), Some(3), Some(4)],
+            vec![Some(0), None, Some(1), Some(2), Some(3)],
+        ]);
+        assert!(current.take_mode_remaps().is_empty());
+    }
+
+    #[test]
+    fn undo_and_redo_queue_the_remaps_back() {
+        let mut current = CurrentGenome::default();
+        current.open(linked_genome());
+        current.move_mode(0, 2);
+        current.delete_mode(1);
+        current.take_mode_remaps();
+
+        // Undoing the delete brings back a mode nothing refers to any more
+        current.undo();
+        current.undo();
+        assert_eq!(current.take_mode_remaps(), [
+            vec![Some(0), Some(2), Some(3), Some(4)],
+            vec![Some(1), Some(2), Some(0), Some(3), Some(4)],
+        ]);
+
+        current.redo();
+        assert_eq!(current.take_mode_remaps(), [vec![Some(2), Some(0), Some(1), Some(3), Some(4)]]);
+    }
+
+    #[test]
+    fn delete_then_undo_restores_genome_exactly() {
+        let mut current = CurrentGenome::default();
+        current.open(linked_genome());
+        current.genome.modes[2].name = "Doomed".to_string();
+        let before = serde_json::to_vec(&current.genome).unwrap();
+
+        // A scalar edit just before must not be merged into the delete
+        current.record_edit("Rename");
+        current.genome.modes[1].split_mass = 2.5;
+        let after_scalar = serde_json::to_vec(&current.genome).unwrap();
+
+        assert!(current.delete_mode(2));
+        assert_eq!(current.undo().as_deref(), Some("Delete Doomed"));
+        assert_eq!(serde_json::to_vec(&current.genome).unwrap(), after_scalar);
+
+        assert_eq!(current.undo().as_deref(), Some("Rename"));
+        assert_eq!(serde_json::to_vec(&current.genome).unwrap(), before);
+    }
+
+    #[test]
+    fn loading_a_genome_restores_its_selected_mode_clamped() {
+        let mut current = CurrentGenome::default();
+        for (saved, expected) in [(2, 2), (99, 4), (-3, 0)] {
+            let json = serde_json::to_string(&GenomeData { last_selected_mode: saved, ..linked_genome() }).unwrap();
+            current.open(GenomeData::from_json(&json).unwrap());
+            assert_eq!(current.selected_mode_index, expected, "saved selection {}", saved);
+        }
+    }
+
+    #[test]
+    fn selection_stays_on_an_existing_mode() {
+        // Saved with a selection past the end of its modes
+        let mut current = CurrentGenome::default();
+        current.open(GenomeData { last_selected_mode: 100, ..linked_genome() });
+        assert_eq!(current.selected_mode_index, 4);
+
+        current.delete_mode(4);
+        assert_eq!(current.selected_mode_index, 3);
+
+        // Undoing back to a larger genome keeps the selection; opening a smaller one pulls it in
+        current.undo();
+        assert_eq!(current.selected_mode_index, 3);
+        current.open(GenomeData {
+            modes: vec![ModeSettings::new_self_splitting(0, "Only".to_string())],
+            last_selected_mode: 7,
+            ..Default::default()
+        });
+        assert_eq!(current.selected_mode_index, 0);
+
+        // The initial mode is held to an existing mode the same way
+        current.genome.initial_mode = 5;
+        current.clamp_selection();
+        assert_eq!(current.genome.initial_mode, 0);
+        current.genome.initial_mode = -2;
+        current.clamp_selection();
+        assert_eq!(current.genome.initial_mode, 0);
+    }
+
+    #[test]
+    fn edits_are_dirty_until_saved_or_undone() {
+        let mut current = CurrentGenome::default();
+        current.open(linked_genome());
+        assert!(!current.is_dirty());
+
+        current.delete_mode(0);
+        assert!(current.is_dirty());
+        current.undo();
+        assert!(!current.is_dirty());
+
+        current.genome.name = "Renamed".to_string();
+        assert!(current.is_dirty());
+        current.mark_saved(std::path::PathBuf::from("renamed.json"));
+        assert!(!current.is_dirty());
+    }
+
+    #[test]
+    fn graph_positions_count_as_changes_and_move_with_their_modes() {
+        let mut current = CurrentGenome::default();
+        let mut genome = linked_genome();
+        genome.graph_positions = (0..genome.modes.len()).map(|i| [i as f32 / 10.0, 0.5]).collect();
+        current.open(genome);
+
+        current.genome.graph_positions[0] = [0.9, 0.9];
+        assert!(current.is_dirty());
+        current.genome.graph_positions[0] = [0.0, 0.5];
+        assert!(!current.is_dirty());
+
+        current.move_mode(0, 2);
+        assert_eq!(current.genome.graph_positions[2], [0.0, 0.5]);
+        assert_eq!(current.genome.graph_positions[0], [0.1, 0.5]);
+    }
+
+    #[test]
+    fn scalar_edits_coalesce_but_structural_edits_do_not() {
+        let mut current = CurrentGenome::default();
+        current.open(linked_genome());
+        current.record_edit("Split Mass");
+        current.record_edit("Split Mass");
+        current.move_mode(0, 1);
+        current.move_mode(1, 0);
+
+        assert_eq!(current.undo().as_deref(), Some("Move M 0"));
+        assert_eq!(current.undo().as_deref(), Some("Move M 0"));
+        assert_eq!(current.undo().as_deref(), Some("Split Mass"));
+        assert_eq!(current.undo(), None);
+    }
+
+    #[test]
+    fn in_place_mode_edits_undo_as_one_step() {
+        let mut current = CurrentGenome::default();
+        current.open(linked_genome());
+        let original = current.genome.modes[1].split_mass;
+        for mass in [1.5, 1.6, 1.7] {
+            let before = current.genome.modes[1].clone();
+            current.genome.modes[1].split_mass = mass;
+            current.record_mode_edit(1, before, "Split Mass");
+        }
+        assert_eq!(current.genome.modes[1].split_mass, 1.7);
+
+        assert_eq!(current.undo().as_deref(), Some("Split Mass"));
+        assert_eq!(current.genome.modes[1].split_mass, original);
+        assert_eq!(current.undo(), None);
+        current.redo();
+        assert_eq!(current.genome.modes[1].split_mass, 1.7);
+    }
+
+    #[test]
+    fn recolor_undoes_onto_the_same_modes_after_a_move() {
+        let mut current = CurrentGenome::default();
+        current.open(linked_genome());
+        let colors_by_name = |current: &CurrentGenome| {
+            let mut colors: Vec<(String, Vec3)> = current.genome.modes.iter().map(|mode| (mode.name.clone(), mode.color)).collect();
+            colors.sort_by(|a, b| a.0.cmp(&b.0));
+            colors
+        };
+        let before = colors_by_name(&current);
+
+        current.recolor(palette::RecolorStrategy::ByCellType);
+        assert_ne!(colors_by_name(&current), before);
+        current.move_mode(0, 2);
+        assert_eq!(current.undo().as_deref(), Some("Move M 0"));
+        assert_eq!(current.undo().as_deref(), Some("Color by Type"));
+        assert_eq!(colors_by_name(&current), before);
+    }
+
+    #[test]
+    fn reachable_modes_follow_children_and_after_splits() {
+        // 3 -> 2 (after splits); 2 only splits into itself. 0, 1 and 4 are never reached.
+        assert_eq!(linked_genome().reachable_modes(), vec![2, 3]);
+        assert_eq!(GenomeData::default().complexity_score(), 1.0);
+    }
+
+    #[test]
+    fn mode_depths_count_transitions_from_the_initial_mode() {
+        let mut genome = linked_genome();
+        genome.modes[2].child_b.mode_number = 4;
+
+        // 3 -> 2 -> 4 -> 1; 0 is never reached
+        assert_eq!(genome.mode_depths(), vec![None, Some(3), Some(1), Some(0), Some(2)]);
+        genome.initial_mode = 99;
+        assert!(genome.mode_depths().iter().all(Option::is_none));
+    }
+
+    #[test]
+    fn reachable_branching_mode_raises_complexity() {
+        let mut genome = linked_genome();
+        let before = genome.complexity_score();
+
+        // 2 now branches into itself and 4, which turns into 1 after splitting
+        genome.modes[2].child_b.mode_number = 4;
+        assert_eq!(genome.reachable_modes(), vec![1, 2, 3, 4]);
+        assert!(genome.complexity_score() > before);
+
+        // Unreachable modes don't count, however different they are
+        let branched = genome.complexity_score();
+        genome.modes[0].split_interval = 50.0;
+        assert_eq!(genome.complexity_score(), branched);
+    }
+
+    #[test]
+    fn split_direction_presets() {
+        let mut rng = random::SeededRng::new(7);
+        assert_eq!(SplitDirectionPreset::Zero.direction(&mut rng), Vec2::ZERO);
+        assert_eq!(SplitDirectionPreset::Equatorial.direction(&mut rng), Vec2::new(0.0, 90.0));
+        assert_eq!(SplitDirectionPreset::Polar.direction(&mut rng), Vec2::new(90.0, 0.0));
+
+        for _ in 0..100 {
+            let direction = SplitDirectionPreset::Random.direction(&mut rng);
+            assert!((-90.0..=90.0).contains(&direction.x));
+            assert!((-180.0..=180.0).contains(&direction.y));
+        }
+        // Same seed, same direction
+        assert_eq!(
+            SplitDirectionPreset::Random.direction(&mut random::SeededRng::new(3)),
+            SplitDirectionPreset::Random.direction(&mut random::SeededRng::new(3)),
+        );
+    }
+
+    #[test]
+    fn auto_names_follow_cell_type_and_index() {
+        let mut genome = GenomeData {
+            modes: (0..3).map(|i| ModeSettings::new_self_splitting(i, format!("M {}", i))).collect(),
+            ..Default::default()
+        };
+        genome.modes[1].cell_type = 2;
+        genome.modes[2].name = "Root".to_string();
+        assert_eq!(genome.custom_named_modes(), 1);
+
+        genome.auto_name_modes();
+
+        let names: Vec<&str> = genome.modes.iter().map(|mode| mode.name.as_str()).collect();
+        assert_eq!(names, ["Photocyte 0", "Flagellocyte 1", "Photocyte 2"]);
+        assert_eq!(genome.custom_named_modes(), 0);
+        assert_eq!(auto_mode_name(99, 4), "Mode 4");
+    }
+
+    #[test]
+    fn clean_name_trims_caps_and_falls_back() {
+        assert_eq!(clean_name("  Stem  ", "M 0"), "Stem");
+        assert_eq!(clean_name("   ", "M 0"), "M 0");
+        let long = "x".repeat(MAX_NAME_CHARS + 10);
+        assert_eq!(clean_name(&long, "M 0").chars().count(), MAX_NAME_CHARS);
+        // Counted in characters, so multi-byte names aren't split mid-character
+        assert_eq!(clean_name(&"é".repeat(100), "M 0"), "é".repeat(MAX_NAME_CHARS));
+    }
+
+    #[test]
+    fn clamp_mode_references_fits_smaller_genome() {
+        let mut mode = ModeSettings::new_self_splitting(7, "Tuned".to_string());
+        mode.mode_a_after_splits = 6;
+        assert!(mode.clamp_mode_references(4));
+        assert_eq!((mode.child_a.mode_number, mode.child_b.mode_number), (3, 3));
+        assert_eq!(mode.mode_a_after_splits, -1);
+        assert!(!mode.clamp_mode_references(4));
+    }
+
+    #[test]
+    fn genome_json_is_validated_and_clamped() {
+        let mut genome = GenomeData {
+            modes: (0..3).map(|i| ModeSettings::new_self_splitting(i, format!("M {}", i))).collect(),
+            ..Default::default()
+        };
+        genome.modes[1].child_b.mode_number = 9;
+        genome.initial_mode = 5;
+        let json = serde_json::to_string(&genome).unwrap();
+
+        let parsed = GenomeData::from_json(&json).unwrap();
+        assert_eq!(parsed.modes[1].child_b.mode_number, 2);
+        assert_eq!(parsed.initial_mode, 2);
+
+        assert!(GenomeData::from_json("not json").is_err());
+        assert!(GenomeData::from_json(r#"{"name": "Chat snippet"}"#).is_err());
+        let ron = ron::to_string(&genome).unwrap();
+        let parsed = GenomeData::from_ron(&ron).unwrap();
+        assert_eq!(parsed.modes[1].child_b.mode_number, 2);
+        assert_eq!(parsed.initial_mode, 2);
+
+        genome.modes.clear();
+        assert!(GenomeData::from_json(&serde_json::to_string(&genome).unwrap()).is_err());
+        assert!(GenomeData::from_ron(&ron::to_string(&genome).unwrap()).is_err());
+    }
+
+    #[test]
+    fn loading_clamps_parameters_to_editor_ranges() {
+        let mut genome = GenomeData {
+            modes: vec![ModeSettings::new_self_splitting(0, "Hand edited".to_string())],
+            ..Default::default()
+        };
+        genome.modes[0].adhesion_settings.rest_length = 40.0;
+        genome.modes[0].split_mass = 0.1;
+        let path = std::env::temp_dir().join(format!("out_of_range_genome_{}.json", std::process::id()));
+        genome.save_to_file(&path).unwrap();
+
+        let loaded = GenomeData::load_from_file(&path);
+        std::fs::remove_file(&path).unwrap();
+
+        let mode = &loaded.unwrap().modes[0];
+        assert_eq!(mode.adhesion_settings.rest_length, *ranges::REST_LENGTH.end());
+        assert_eq!(mode.split_mass, *ranges::SPLIT_MASS.start());
+    }
 }
@@ -0,0 +1,182 @@
+use bevy::prelude::*;
+
+use super::ModeSettings;
+
+/// Ways to regenerate the colors of a genome's modes
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum RecolorStrategy {
+    /// Hue sweep across the mode list (the default genome palette)
+    ByIndex,
+    /// One hue per cell type, with brightness varying between modes of the same type
+    ByCellType,
+}
+
+impl RecolorStrategy {
+    pub const ALL: [RecolorStrategy; 2] = [RecolorStrategy::ByIndex, RecolorStrategy::ByCellType];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            RecolorStrategy::ByIndex => "Color by Index",
+            RecolorStrategy::ByCellType => "Color by Type",
+        }
+    }
+}
+
+/// Recolor every unpinned mode using `strategy`
+pub fn apply_recolor(modes: &mut [ModeSettings], strategy: RecolorStrategy) {
+    let colors = match strategy {
+        RecolorStrategy::ByIndex => (0..modes.len()).map(|i| index_color(i, modes.len())).collect(),
+        RecolorStrategy::ByCellType => cell_type_colors(modes),
+    };
+
+    for (mode, color) in modes.iter_mut().zip(colors) {
+        if !mode.color_pinned {
+            mode.color = color;
+        }
+    }
+}
+
+/// Spread the default palette evenly over the current mode count, recoloring only modes
+/// whose color was generated by it; hand-picked and pinned colors are kept.
+pub fn respread_default_colors(modes: &mut [ModeSettings]) {
+    let count = modes.len();
+    for (i, mode) in modes.iter_mut().enumerate() {
+        if !mode.color_pinned && !mode.color_is_custom && is_palette_color(mode.color) {
+            mode.color = index_color(i, count);
+        }
+    }
+}
+
+/// Whether `color` is one [`index_color`] produces for some index and count. Genomes saved
+/// before colors were flagged as custom rely on this to tell generated colors apart.
+pub fn is_palette_color(color: Vec3) -> bool {
+    // Tolerates the rounding of colors that went through 8-bit channels
+    const TOLERANCE: f32 = 1.5 / 255.0;
+    let (max, min) = (color.max_element(), color.min_element());
+    let delta = max - min;
+    if delta <= 0.0 {
+        return false;
+    }
+    let hue = if max == color.x {
+        60.0 * ((color.y - color.z) / delta).rem_euclid(6.0)
+    } else if max == color.y {
+        60.0 * ((color.z - color.x) / delta + 2.0)
+    } else {
+        60.0 * ((color.x - color.y) / delta + 4.0)
+    };
+    let (r, g, b) = hue_to_rgb(hue);
+    let generated = Vec3::new(r as f32 / 255.0, g as f32 / 255.0, b as f32 / 255.0);
+    (generated - color).abs().max_element() <= TOLERANCE
+}
+
+/// Default palette color for mode `index` out of `count`
+pub fn index_color(index: usize, count: usize) -> Vec3 {
+    let hue = (index as f32 / count.max(1) as f32) * 360.0;
+    let (r, g, b) = hue_to_rgb(hue);
+    Vec3::new(r as f32 / 255.0, g as f32 / 255.0, b as f32 / 255.0)
+}
+
+fn cell_type_colors(modes: &[ModeSettings]) -> Vec<Vec3> {
+    let type_count = super::CELL_TYPE_NAMES.len() as f32;
+    let mut per_type_total = std::collections::HashMap::new();
+    for mode in modes {
+        *per_type_total.entry(mode.cell_type).or_insert(0usize) += 1;
+    }
+
+    let mut per_type_seen = std::collections::HashMap::new();
+    modes.iter().map(|mode| {
+        let rank = per_type_seen.entry(mode.cell_type).or_insert(0usize);
+        let total = per_type_total[&mode.cell_type];
+
+        let hue = (mode.cell_type.max(0) as f32 / type_count) * 360.0;
+        // Brightest first, darkening towards the last mode of the type
+        let t = if total > 1 { *rank as f32 / (total - 1) as f32 } else { 0.0 };
+        *rank += 1;
+        hsv_to_rgb(hue, 0.65, 1.0 - t * 0.55)
+    }).collect()
+}
+
+fn hsv_to_rgb(hue: f32, saturation: f32, value: f32) -> Vec3 {
+    let c = value * saturation;
+    let h = (hue % 360.0) / 60.0;
+    let x = c * (1.0 - (h % 2.0 - 1.0).abs());
+    let (r, g, b) = if h < 1.0 {
+        (c, x, 0.0)
+    } else if h < 2.0 {
+        (x, c, 0.0)
+    } else if h < 3.0 {
+        (0.0, c, x)
+    } else if h < 4.0 {
+        (0.0, x, c)
+    } else if h < 5.0 {
+        (x, 0.0, c)
+    } else {
+        (c, 0.0, x)
+    };
+    let m = value - c;
+    Vec3::new(r + m, g + m, b + m)
+}
+
+// Helper function to convert HSV hue to RGB
+fn hue_to_rgb(hue: f32) -> (u8, u8, u8) {
+    let h = hue / 60.0;
+    let c = 1.0;
+    let x = 1.0 - (h % 2.0 - 1.0).abs();
+    
+    let (r, g, b) = if h < 1.0 {
+        (c, x, 0.0)
+    } else if h < 2.0 {
+        (x, c, 0.0)
+    } else if h < 3.0 {
+        (0.0, c, x)
+    } else if h < 4.0 {
+        (0.0, x, c)
+    } else if h < 5.0 {
+        (x, 0.0, c)
+    } else {
+        (c, 0.0, x)
+    };
+    
+    // Scale to 100-255 range for better visibility
+    let scale = |v: f32| ((v * 155.0) + 100.0) as u8;
+    (scale(r), scale(g), scale(b))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::genome::DEFAULT_MODE_COUNT;
+
+    #[test]
+    fn respread_keeps_custom_and_pinned_colors() {
+        let mut modes: Vec<ModeSettings> = (0..4)
+            .map(|i| {
+                let mut mode = ModeSettings::new_self_splitting(i, format!("M {}", i));
+                mode.color = index_color(i as usize, DEFAULT_MODE_COUNT);
+                mode
+            })
+            .collect();
+        modes[1].color = Vec3::new(0.2, 0.3, 0.4);
+        modes[2].color_is_custom = true;
+        modes[3].color_pinned = true;
+        let before: Vec<Vec3> = modes.iter().map(|mode| mode.color).collect();
+
+        respread_default_colors(&mut modes);
+
+        assert_eq!(modes[0].color, index_color(0, 4));
+        assert_eq!(modes[1].color, before[1]);
+        assert_eq!(modes[2].color, before[2]);
+        assert_eq!(modes[3].color, before[3]);
+    }
+
+    #[test]
+    fn palette_colors_are_recognized() {
+        for count in [3, 7, 120] {
+            for i in 0..count {
+                assert!(is_palette_color(index_color(i, count)), "{} of {}", i, count);
+            }
+        }
+        assert!(!is_palette_color(Vec3::new(0.2, 0.3, 0.4)));
+        assert!(!is_palette_color(Vec3::splat(0.5)));
+    }
+}
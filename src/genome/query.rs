@@ -0,0 +1,112 @@
+use super::{GenomeData, ModeSettings};
+
+/// Filter over the modes of library genomes. Every set criterion must hold for a mode to match;
+/// unset criteria are ignored, so the default query matches everything.
+#[derive(Clone, Default)]
+pub struct ModeQuery {
+    pub cell_type: Option<i32>,
+    pub min_break_force: Option<f32>,
+    pub max_break_force: Option<f32>,
+    pub makes_adhesion: Option<bool>,
+    /// Case-insensitive substring of the mode name (empty = any)
+    pub name_contains: String,
+    /// Only starred modes
+    pub favorites_only: bool,
+}
+
+/// A library genome with at least one matching mode
+pub struct GenomeMatch {
+    pub genome_index: usize,
+    pub mode_indices: Vec<usize>,
+}
+
+impl ModeQuery {
+    pub fn matches_mode(&self, mode: &ModeSettings) -> bool {
+        if self.cell_type.is_some_and(|cell_type| mode.cell_type != cell_type) {
+            return false;
+        }
+        let break_force = mode.adhesion_settings.break_force;
+        if self.min_break_force.is_some_and(|min| break_force < min) {
+            return false;
+        }
+        if self.max_break_force.is_some_and(|max| break_force > max) {
+            return false;
+        }
+        if self.makes_adhesion.is_some_and(|makes| mode.parent_make_adhesion != makes) {
+            return false;
+        }
+        if self.favorites_only && !mode.favorite {
+            return false;
+        }
+        let needle = self.name_contains.trim();
+        needle.is_empty() || mode.name.to_lowercase().contains(&needle.to_lowercase())
+    }
+
+    /// Indices of the genome's modes that satisfy the query
+    pub fn matching_modes(&self, genome: &GenomeData) -> Vec<usize> {
+        genome.modes.iter()
+            .enumerate()
+            .filter(|(_, mode)| self.matches_mode(mode))
+            .map(|(i, _)| i)
+            .collect()
+    }
+}
+
+/// Every genome containing at least one mode that satisfies `query`, in library order
+pub fn search_genomes(genomes: &[GenomeData], query: &ModeQuery) -> Vec<GenomeMatch> {
+    genomes.iter()
+        .enumerate()
+        .filter_map(|(genome_index, genome)| {
+            let mode_indices = query.matching_modes(genome);
+            (!mode_indices.is_empty()).then_some(GenomeMatch { genome_index, mode_indices })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn genome_with(modes: Vec<ModeSettings>) -> GenomeData {
+        GenomeData { modes, ..Default::default() }
+    }
+
+    #[test]
+    fn search_requires_all_criteria_on_a_single_mode() {
+        let mut flagellocyte = ModeSettings { cell_type: 2, ..Default::default() };
+        flagellocyte.adhesion_settings.break_force = 5.0;
+        let mut strong = ModeSettings::default();
+        strong.adhesion_settings.break_force = 50.0;
+
+        let genomes = vec![
+            genome_with(vec![flagellocyte.clone(), strong.clone()]),
+            genome_with(vec![strong]),
+        ];
+
+        let query = ModeQuery { cell_type: Some(2), ..Default::default() };
+        let matches = search_genomes(&genomes, &query);
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].genome_index, 0);
+        assert_eq!(matches[0].mode_indices, vec![0]);
+
+        // Flagellocyte and high break force exist in genome 0, but not on the same mode
+        let query = ModeQuery { cell_type: Some(2), min_break_force: Some(20.0), ..Default::default() };
+        assert!(search_genomes(&genomes, &query).is_empty());
+
+        let query = ModeQuery { min_break_force: Some(20.0), ..Default::default() };
+        let indices: Vec<_> = search_genomes(&genomes, &query).iter().map(|m| m.genome_index).collect();
+        assert_eq!(indices, vec![0, 1]);
+    }
+
+    #[test]
+    fn favorites_filter_combines_with_name() {
+        let mode = |name: &str, favorite: bool| ModeSettings { name: name.to_string(), favorite, ..Default::default() };
+        let genome = genome_with(vec![mode("Stem", true), mode("Stem B", false), mode("Leaf", true)]);
+
+        let query = ModeQuery { favorites_only: true, ..Default::default() };
+        assert_eq!(query.matching_modes(&genome), vec![0, 2]);
+
+        let query = ModeQuery { favorites_only: true, name_contains: "stem".to_string(), ..Default::default() };
+        assert_eq!(query.matching_modes(&genome), vec![0]);
+    }
+}
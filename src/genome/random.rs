@@ -0,0 +1,111 @@
+use bevy::prelude::*;
+use std::f32::consts::TAU;
+
+use super::{GenomeData, CELL_TYPE_NAMES};
+
+/// Small deterministic PRNG (SplitMix64). Kept in-tree rather than using `rand`
+/// so a saved seed keeps producing the same genome across dependency upgrades.
+pub struct SeededRng(u64);
+
+impl SeededRng {
+    pub fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform in [0, 1)
+    pub fn next_f32(&mut self) -> f32 {
+        (self.next_u64() >> 40) as f32 / (1u64 << 24) as f32
+    }
+
+    pub fn range_f32(&mut self, min: f32, max: f32) -> f32 {
+        min + (max - min) * self.next_f32()
+    }
+
+    /// Uniform in [0, len)
+    pub fn index(&mut self, len: usize) -> usize {
+        (self.next_u64() % len.max(1) as u64) as usize
+    }
+
+    pub fn chance(&mut self, probability: f32) -> bool {
+        self.next_f32() < probability
+    }
+
+    /// Uniformly distributed rotation (Shoemake's method)
+    pub fn rotation(&mut self) -> Quat {
+        let (u1, u2, u3) = (self.next_f32(), TAU * self.next_f32(), TAU * self.next_f32());
+        let (a, b) = ((1.0 - u1).sqrt(), u1.sqrt());
+        Quat::from_xyzw(a * u2.sin(), a * u2.cos(), b * u3.sin(), b * u3.cos()).normalize()
+    }
+}
+
+/// A seed for when the genome doesn't have one yet. Kept to 32 bits so it is easy
+/// to read out and survives editing in a `DragValue` (which goes through f64).
+pub fn fresh_seed() -> u64 {
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_nanos() as u64)
+        .unwrap_or_default();
+    SeededRng::new(nanos).next_u64() >> 32
+}
+
+/// Overwrite the genome's behaviour with random values derived only from `seed` and the mode count.
+/// Mode names are kept, and pinned colors stay as they are.
+pub fn randomize_genome(genome: &mut GenomeData, seed: u64) {
+    let mut rng = SeededRng::new(seed);
+    let mode_count = genome.modes.len();
+
+    genome.initial_mode = rng.index(mode_count) as i32;
+    genome.initial_orientation = Quat::IDENTITY;
+
+    for mode in &mut genome.modes {
+        // Always draw the color so pinning doesn't shift the rest of the sequence
+        let color = Vec3::new(rng.next_f32(), rng.next_f32(), rng.next_f32());
+        if !mode.color_pinned {
+            mode.color = color;
+        }
+
+        mode.cell_type = rng.index(CELL_TYPE_NAMES.len()) as i32;
+        mode.parent_make_adhesion = rng.chance(0.5);
+        mode.split_mass = rng.range_f32(1.0, 3.0);
+        mode.split_interval = rng.range_f32(2.0, 15.0);
+        mode.nutrient_gain_rate = rng.range_f32(0.05, 0.5);
+        mode.split_ratio = rng.range_f32(0.3, 0.7);
+        mode.parent_split_direction = Vec2::new(rng.range_f32(-180.0, 180.0), rng.range_f32(-180.0, 180.0));
+        mode.swim_force = rng.range_f32(0.0, 1.0);
+
+        for child in [&mut mode.child_a, &mut mode.child_b] {
+            child.mode_number = rng.index(mode_count) as i32;
+            child.orientation = rng.rotation();
+            child.keep_adhesion = rng.chance(0.5);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn same_seed_reproduces_genome() {
+        let mut first = GenomeData::default();
+        randomize_genome(&mut first, 42);
+
+        // Round-trip through JSON like a save/load would
+        let json = serde_json::to_string(&first).unwrap();
+        let mut second: GenomeData = serde_json::from_str(&json).unwrap();
+        randomize_genome(&mut second, 42);
+        assert!(first == second);
+
+        let mut other = GenomeData::default();
+        randomize_genome(&mut other, 43);
+        assert!(first != other);
+    }
+}
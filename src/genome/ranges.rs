@@ -0,0 +1,108 @@
+//! Ranges the editor's sliders allow for each mode parameter. Loaded genomes are clamped
+//! into them so every value can be shown and edited.
+
+use std::ops::RangeInclusive;
+
+use super::{AdhesionSettings, ModeSettings, SPLIT_INTERVAL_MAX, SPLIT_INTERVAL_MIN};
+
+pub const SPLIT_MASS: RangeInclusive<f32> = 1.0..=3.0;
+pub const SPLIT_INTERVAL: RangeInclusive<f32> = SPLIT_INTERVAL_MIN..=SPLIT_INTERVAL_MAX;
+/// -1 means no limit
+pub const MAX_SPLITS: RangeInclusive<i32> = -1..=20;
+pub const NUTRIENT_PRIORITY: RangeInclusive<f32> = 0.1..=10.0;
+pub const CONNECTIONS: RangeInclusive<i32> = 0..=20;
+/// Pitch and yaw of the parent split direction, in degrees
+pub const SPLIT_ANGLE: RangeInclusive<f32> = -180.0..=180.0;
+pub const NUTRIENT_GAIN_RATE: RangeInclusive<f32> = 0.0..=5.0;
+pub const SWIM_FORCE: RangeInclusive<f32> = 0.0..=5.0;
+pub const DEVOUR_RATE: RangeInclusive<f32> = 0.0..=10.0;
+pub const STORAGE_CAPACITY: RangeInclusive<f32> = 0.0..=20.0;
+
+pub const BREAK_FORCE: RangeInclusive<f32> = 0.1..=100.0;
+pub const REST_LENGTH: RangeInclusive<f32> = 0.5..=5.0;
+pub const LINEAR_SPRING_STIFFNESS: RangeInclusive<f32> = 0.1..=500.0;
+pub const ORIENTATION_SPRING_STIFFNESS: RangeInclusive<f32> = 0.1..=100.0;
+pub const SPRING_DAMPING: RangeInclusive<f32> = 0.0..=10.0;
+/// Degrees
+pub const MAX_ANGULAR_DEVIATION: RangeInclusive<f32> = 0.0..=180.0;
+pub const TWIST_CONSTRAINT_STIFFNESS: RangeInclusive<f32> = 0.0..=2.0;
+
+/// Clamp `value` into `range`, noting in `changed` whether it moved. NaN becomes the range's start.
+fn clamp_into<T: PartialOrd + Copy>(value: &mut T, range: RangeInclusive<T>, changed: &mut bool) {
+    // Written so NaN, which fails every comparison, falls through to the start
+    let clamped = if *value > *range.end() {
+        *range.end()
+    } else if *value >= *range.start() {
+        return;
+    } else {
+        *range.start()
+    };
+    *value = clamped;
+    *changed = true;
+}
+
+impl AdhesionSettings {
+    /// Bring every slider-edited value into its editor range. Returns true if anything changed.
+    pub fn clamp_to_editor_ranges(&mut self) -> bool {
+        let mut changed = false;
+        clamp_into(&mut self.break_force, BREAK_FORCE, &mut changed);
+        clamp_into(&mut self.rest_length, REST_LENGTH, &mut changed);
+        clamp_into(&mut self.linear_spring_stiffness, LINEAR_SPRING_STIFFNESS, &mut changed);
+        clamp_into(&mut self.linear_spring_damping, SPRING_DAMPING, &mut changed);
+        clamp_into(&mut self.orientation_spring_stiffness, ORIENTATION_SPRING_STIFFNESS, &mut changed);
+        clamp_into(&mut self.orientation_spring_damping, SPRING_DAMPING, &mut changed);
+        clamp_into(&mut self.max_angular_deviation, MAX_ANGULAR_DEVIATION, &mut changed);
+        clamp_into(&mut self.twist_constraint_stiffness, TWIST_CONSTRAINT_STIFFNESS, &mut changed);
+        clamp_into(&mut self.twist_constraint_damping, SPRING_DAMPING, &mut changed);
+        changed
+    }
+}
+
+impl ModeSettings {
+    /// Bring every slider-edited value, adhesion settings included, into its editor range.
+    /// Randomized minimums are kept between the range's start and their maximum.
+    /// Returns true if anything changed.
+    pub fn clamp_to_editor_ranges(&mut self) -> bool {
+        let mut changed = self.adhesion_settings.clamp_to_editor_ranges();
+        clamp_into(&mut self.split_mass, SPLIT_MASS, &mut changed);
+        clamp_into(&mut self.split_interval, SPLIT_INTERVAL, &mut changed);
+        if let Some(min) = &mut self.split_mass_min {
+            clamp_into(min, *SPLIT_MASS.start()..=self.split_mass, &mut changed);
+        }
+        if let Some(min) = &mut self.split_interval_min {
+            clamp_into(min, *SPLIT_INTERVAL.start()..=self.split_interval, &mut changed);
+        }
+        clamp_into(&mut self.max_splits, MAX_SPLITS, &mut changed);
+        clamp_into(&mut self.nutrient_priority, NUTRIENT_PRIORITY, &mut changed);
+        clamp_into(&mut self.max_adhesions, CONNECTIONS, &mut changed);
+        clamp_into(&mut self.min_adhesions, CONNECTIONS, &mut changed);
+        clamp_into(&mut self.parent_split_direction.x, SPLIT_ANGLE, &mut changed);
+        clamp_into(&mut self.parent_split_direction.y, SPLIT_ANGLE, &mut changed);
+        clamp_into(&mut self.nutrient_gain_rate, NUTRIENT_GAIN_RATE, &mut changed);
+        clamp_into(&mut self.swim_force, SWIM_FORCE, &mut changed);
+        clamp_into(&mut self.devour_rate, DEVOUR_RATE, &mut changed);
+        clamp_into(&mut self.storage_capacity, STORAGE_CAPACITY, &mut changed);
+        changed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn out_of_range_values_are_clamped_and_nan_reset() {
+        let mut mode = ModeSettings::new_self_splitting(0, "Hand edited".to_string());
+        mode.adhesion_settings.rest_length = 12.0;
+        mode.adhesion_settings.break_force = f32::NAN;
+        mode.min_adhesions = -3;
+        mode.split_interval_min = Some(0.2);
+
+        assert!(mode.clamp_to_editor_ranges());
+        assert_eq!(mode.adhesion_settings.rest_length, *REST_LENGTH.end());
+        assert_eq!(mode.adhesion_settings.break_force, *BREAK_FORCE.start());
+        assert_eq!(mode.min_adhesions, 0);
+        assert_eq!(mode.split_interval_min, Some(SPLIT_INTERVAL_MIN));
+        assert!(!mode.clamp_to_editor_ranges());
+    }
+}
@@ -0,0 +1,137 @@
+//! Explicit on-disk formats for glam types in genome files.
+//!
+//! Fields are written as `{x, y, z(, w)}` objects instead of relying on glam's own serde
+//! representation, which is an array and not guaranteed to stay that way. Reading accepts
+//! both the object form and the older array form, so existing files keep loading.
+
+use bevy::prelude::*;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+pub mod quat {
+    use super::*;
+
+    #[derive(Serialize, Deserialize)]
+    struct Xyzw {
+        x: f32,
+        y: f32,
+        z: f32,
+        w: f32,
+    }
+
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Repr {
+        Object(Xyzw),
+        Array([f32; 4]),
+    }
+
+    pub fn serialize<S: Serializer>(value: &Quat, serializer: S) -> Result<S::Ok, S::Error> {
+        Xyzw { x: value.x, y: value.y, z: value.z, w: value.w }.serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Quat, D::Error> {
+        Ok(match Repr::deserialize(deserializer)? {
+            Repr::Object(Xyzw { x, y, z, w }) => Quat::from_xyzw(x, y, z, w),
+            Repr::Array(array) => Quat::from_array(array),
+        })
+    }
+}
+
+pub mod vec3 {
+    use super::*;
+
+    #[derive(Serialize, Deserialize)]
+    struct Xyz {
+        x: f32,
+        y: f32,
+        z: f32,
+    }
+
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Repr {
+        Object(Xyz),
+        Array([f32; 3]),
+    }
+
+    pub fn serialize<S: Serializer>(value: &Vec3, serializer: S) -> Result<S::Ok, S::Error> {
+        Xyz { x: value.x, y: value.y, z: value.z }.serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec3, D::Error> {
+        Ok(match Repr::deserialize(deserializer)? {
+            Repr::Object(Xyz { x, y, z }) => Vec3::new(x, y, z),
+            Repr::Array(array) => Vec3::from_array(array),
+        })
+    }
+}
+
+pub mod vec2 {
+    use super::*;
+
+    #[derive(Serialize, Deserialize)]
+    struct Xy {
+        x: f32,
+        y: f32,
+    }
+
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Repr {
+        Object(Xy),
+        Array([f32; 2]),
+    }
+
+    pub fn serialize<S: Serializer>(value: &Vec2, serializer: S) -> Result<S::Ok, S::Error> {
+        Xy { x: value.x, y: value.y }.serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec2, D::Error> {
+        Ok(match Repr::deserialize(deserializer)? {
+            Repr::Object(Xy { x, y }) => Vec2::new(x, y),
+            Repr::Array(array) => Vec2::from_array(array),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    struct Sample {
+        #[serde(with = "quat")]
+        orientation: Quat,
+        #[serde(with = "vec3")]
+        color: Vec3,
+        #[serde(with = "vec2")]
+        direction: Vec2,
+    }
+
+    fn sample() -> Sample {
+        Sample {
+            orientation: Quat::from_xyzw(0.0, 0.6, 0.0, 0.8),
+            color: Vec3::new(0.25, 0.5, 1.0),
+            direction: Vec2::new(-45.0, 90.0),
+        }
+    }
+
+    #[test]
+    fn writes_and_reads_object_form() {
+        let json = serde_json::to_string(&sample()).unwrap();
+        assert_eq!(
+            json,
+            r#"{"orientation":{"x":0.0,"y":0.6,"z":0.0,"w":0.8},"color":{"x":0.25,"y":0.5,"z":1.0},"direction":{"x":-45.0,"y":90.0}}"#
+        );
+        assert_eq!(serde_json::from_str::<Sample>(&json).unwrap(), sample());
+
+        let ron = ron::to_string(&sample()).unwrap();
+        assert_eq!(ron::from_str::<Sample>(&ron).unwrap(), sample());
+    }
+
+    #[test]
+    fn reads_legacy_array_form() {
+        let json = r#"{"orientation":[0.0,0.6,0.0,0.8],"color":[0.25,0.5,1.0],"direction":[-45.0,90.0]}"#;
+        assert_eq!(serde_json::from_str::<Sample>(json).unwrap(), sample());
+    }
+}
@@ -0,0 +1,121 @@
+use bevy::prelude::*;
+
+use super::{palette, GenomeData, ModeSettings};
+
+/// Built-in starter genome, listed in the Genome > Templates menu
+pub struct GenomeTemplate {
+    pub name: &'static str,
+    pub description: &'static str,
+    build: fn() -> GenomeData,
+}
+
+impl GenomeTemplate {
+    pub fn build(&self) -> GenomeData {
+        (self.build)()
+    }
+}
+
+pub const TEMPLATES: [GenomeTemplate; 3] = [
+    GenomeTemplate {
+        name: "Simple Colony",
+        description: "One photocyte mode that keeps splitting, with every cell stuck to its siblings",
+        build: simple_colony,
+    },
+    GenomeTemplate {
+        name: "Swimmer",
+        description: "A photocyte body that buds off a flagellocyte tail and then stops growing",
+        build: swimmer,
+    },
+    GenomeTemplate {
+        name: "Branching Tree",
+        description: "A stem that grows branches at an angle, each ending in a few photocyte leaves",
+        build: branching_tree,
+    },
+];
+
+/// Genome named `name` made of `modes`, starting in the first and colored by the default palette
+fn template_genome(name: &str, mut modes: Vec<ModeSettings>) -> GenomeData {
+    let count = modes.len();
+    for (i, mode) in modes.iter_mut().enumerate() {
+        mode.color = palette::index_color(i, count);
+    }
+    GenomeData {
+        name: name.to_string(),
+        initial_mode: 0,
+        initial_orientation: Quat::IDENTITY,
+        modes,
+        seed: None,
+        last_selected_mode: 0,
+        graph_positions: Vec::new(),
+    }
+}
+
+/// Mode `index` named `name` that splits into itself and keeps both children attached
+fn adhesive_mode(index: i32, name: &str, cell_type: i32) -> ModeSettings {
+    let mut mode = ModeSettings::new_self_splitting(index, name.to_string());
+    mode.cell_type = cell_type;
+    mode.parent_make_adhesion = true;
+    mode.child_a.keep_adhesion = true;
+    mode.child_b.keep_adhesion = true;
+    mode
+}
+
+fn simple_colony() -> GenomeData {
+    template_genome("Simple Colony", vec![adhesive_mode(0, "Colony", 0)])
+}
+
+fn swimmer() -> GenomeData {
+    let mut body = adhesive_mode(0, "Body", 0);
+    body.max_splits = 3;
+    body.mode_a_after_splits = 1;
+    body.mode_b_after_splits = 2;
+
+    let mut rest = adhesive_mode(1, "Resting Body", 0);
+    rest.max_splits = 0;
+
+    let mut tail = adhesive_mode(2, "Tail", 2);
+    tail.max_splits = 0;
+    tail.swim_force = 1.5;
+
+    template_genome("Swimmer", vec![body, rest, tail])
+}
+
+fn branching_tree() -> GenomeData {
+    let mut stem = adhesive_mode(0, "Stem", 0);
+    stem.child_b.mode_number = 1;
+    stem.child_b.orientation = Quat::from_rotation_z(45f32.to_radians());
+    stem.split_interval = 8.0;
+
+    let mut branch = adhesive_mode(1, "Branch", 0);
+    branch.max_splits = 2;
+    branch.mode_a_after_splits = 2;
+    branch.mode_b_after_splits = 2;
+    branch.parent_split_direction = Vec2::new(30.0, 0.0);
+
+    let mut leaf = adhesive_mode(2, "Leaf", 0);
+    leaf.max_splits = 0;
+    leaf.nutrient_gain_rate = 0.5;
+
+    template_genome("Branching Tree", vec![stem, branch, leaf])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn templates_are_valid_and_fully_reachable() {
+        for template in &TEMPLATES {
+            let mut genome = template.build();
+            assert_eq!(genome.name, template.name);
+            assert!(!genome.clamp_mode_references(), "{} has out-of-range references", template.name);
+            for mode in &mut genome.modes {
+                assert!(!mode.clamp_to_editor_ranges(), "{}: {} is out of range", template.name, mode.name);
+            }
+            assert_eq!(genome.reachable_modes(), (0..genome.modes.len()).collect::<Vec<_>>(), "{}", template.name);
+
+            let json = serde_json::to_string(&genome).unwrap();
+            assert!(GenomeData::from_json(&json).unwrap() == genome, "{} doesn't survive a save", template.name);
+        }
+    }
+}
@@ -0,0 +1,602 @@
+use bevy::math::Vec2;
+use bevy_egui::egui;
+use serde::{Deserialize, Serialize};
+use std::f32::consts::TAU;
+
+use crate::genome::{CurrentGenome, GenomeData, ModeSettings};
+
+const NODE_RADIUS: f32 = 7.0;
+const LEGEND_HEIGHT: f32 = 12.0;
+/// Seconds nodes take to move to a new arrangement
+const ARRANGE_SECONDS: f64 = 0.5;
+/// Iterations of the force-directed layout; it cools linearly to a stop over these
+const FORCE_ITERATIONS: usize = 300;
+/// Gap kept between the outermost nodes and the edge of the 0..=1 layout square
+const LAYOUT_MARGIN: f32 = 0.05;
+/// Zoom limits of the graph view
+const MIN_ZOOM: f32 = 0.25;
+const MAX_ZOOM: f32 = 8.0;
+/// Zoom factor per point of mouse wheel scroll
+const WHEEL_ZOOM_SPEED: f32 = 0.002;
+
+/// How the Genome Graph arranges its nodes
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Serialize, Deserialize)]
+pub enum GraphLayout {
+    #[default]
+    Circular,
+    /// Linked modes pull together, all modes push apart
+    ForceDirected,
+    /// One row per transition from the initial mode, unreachable modes at the bottom
+    Hierarchical,
+    Grid,
+}
+
+impl GraphLayout {
+    pub const ALL: [GraphLayout; 4] = [Self::Circular, Self::ForceDirected, Self::Hierarchical, Self::Grid];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Circular => "Circular",
+            Self::ForceDirected => "Force-Directed",
+            Self::Hierarchical => "Hierarchical",
+            Self::Grid => "Grid",
+        }
+    }
+}
+
+/// Numeric mode parameter the graph's heatmap can color nodes by
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum HeatmapParameter {
+    SplitInterval,
+    SplitMass,
+    NutrientPriority,
+    MaxSplits,
+    MaxAdhesions,
+    BreakForce,
+    RestLength,
+    LinearSpringStiffness,
+    OrientationSpringStiffness,
+}
+
+impl HeatmapParameter {
+    pub const ALL: [HeatmapParameter; 9] = [
+        Self::SplitInterval,
+        Self::SplitMass,
+        Self::NutrientPriority,
+        Self::MaxSplits,
+        Self::MaxAdhesions,
+        Self::BreakForce,
+        Self::RestLength,
+        Self::LinearSpringStiffness,
+        Self::OrientationSpringStiffness,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::SplitInterval => "Split Interval",
+            Self::SplitMass => "Split Mass",
+            Self::NutrientPriority => "Nutrient Priority",
+            Self::MaxSplits => "Max Splits",
+            Self::MaxAdhesions => "Max Connections",
+            Self::BreakForce => "Adhesion Break Force",
+            Self::RestLength => "Adhesion Rest Length",
+            Self::LinearSpringStiffness => "Linear Spring Stiffness",
+            Self::OrientationSpringStiffness => "Orientation Spring Stiffness",
+        }
+    }
+
+    pub fn value(self, mode: &ModeSettings) -> f32 {
+        match self {
+            Self::SplitInterval => mode.split_interval,
+            Self::SplitMass => mode.split_mass,
+            Self::NutrientPriority => mode.nutrient_priority,
+            Self::MaxSplits => mode.max_splits as f32,
+            Self::MaxAdhesions => mode.max_adhesions as f32,
+            Self::BreakForce => mode.adhesion_settings.break_force,
+            Self::RestLength => mode.adhesion_settings.rest_length,
+            Self::LinearSpringStiffness => mode.adhesion_settings.linear_spring_stiffness,
+            Self::OrientationSpringStiffness => mode.adhesion_settings.orientation_spring_stiffness,
+        }
+    }
+}
+
+/// Genome Graph window state
+#[derive(Default)]
+pub struct GenomeGraphState {
+    pub open: bool,
+    /// Color nodes by this parameter instead of by mode color
+    pub heatmap: Option<HeatmapParameter>,
+    /// Where the nodes were when the last arrangement started, and when it started
+    transition: Option<(Vec<[f32; 2]>, f64)>,
+    view: GraphView,
+}
+
+/// Zoom and pan of the graph. Offsets are measured from the center of the drawing area;
+/// "graph" offsets are where a point would be drawn at zoom 1 with no pan.
+#[derive(Clone, Copy, PartialEq, Debug)]
+struct GraphView {
+    zoom: f32,
+    pan: egui::Vec2,
+}
+
+impl Default for GraphView {
+    fn default() -> Self {
+        Self { zoom: 1.0, pan: egui::Vec2::ZERO }
+    }
+}
+
+impl GraphView {
+    fn to_screen(self, graph_offset: egui::Vec2) -> egui::Vec2 {
+        graph_offset * self.zoom + self.pan
+    }
+
+    fn to_graph(self, screen_offset: egui::Vec2) -> egui::Vec2 {
+        (screen_offset - self.pan) / self.zoom
+    }
+
+    /// Scale the zoom by `factor`, keeping the point under `cursor` where it is on screen
+    fn zoom_at(&mut self, cursor: egui::Vec2, factor: f32, half_size: egui::Vec2) {
+        let anchor = self.to_graph(cursor);
+        self.zoom = (self.zoom * factor).clamp(MIN_ZOOM, MAX_ZOOM);
+        self.pan = cursor - anchor * self.zoom;
+        self.clamp_pan(half_size);
+    }
+
+    fn pan_by(&mut self, delta: egui::Vec2, half_size: egui::Vec2) {
+        self.pan += delta;
+        self.clamp_pan(half_size);
+    }
+
+    /// Keep the center of the view over the graph's area so it can't be lost off-screen
+    fn clamp_pan(&mut self, half_size: egui::Vec2) {
+        let limit = half_size * self.zoom;
+        self.pan = self.pan.clamp(-limit, limit);
+    }
+}
+
+/// Child A/B links between different modes, as (parent, child)
+fn child_edges(genome: &GenomeData) -> Vec<(usize, usize)> {
+    genome.modes.iter()
+        .enumerate()
+        .flat_map(|(i, mode)| [mode.child_a.mode_number, mode.child_b.mode_number].map(move |child| (i, child)))
+        .filter_map(|(i, child)| usize::try_from(child).ok().filter(|&c| c < genome.modes.len() && c != i).map(|c| (i, c)))
+        .collect()
+}
+
+/// Node positions for `layout`, one per mode, inside the 0..=1 square
+pub fn arrange(genome: &GenomeData, layout: GraphLayout) -> Vec<[f32; 2]> {
+    let count = genome.modes.len();
+    let positions = match layout {
+        GraphLayout::Circular => circular_layout(count),
+        GraphLayout::ForceDirected => force_directed_layout(count, &child_edges(genome)),
+        GraphLayout::Hierarchical => hierarchical_layout(&genome.mode_depths()),
+        GraphLayout::Grid => grid_layout(count),
+    };
+    positions.into_iter().map(|position| position.to_array()).collect()
+}
+
+/// Evenly around a ring, starting at the top
+fn circular_layout(count: usize) -> Vec<Vec2> {
+    let radius = 0.5 - LAYOUT_MARGIN;
+    (0..count)
+        .map(|i| {
+            let angle = i as f32 / count as f32 * TAU - TAU / 4.0;
+            Vec2::splat(0.5) + radius * Vec2::new(angle.cos(), angle.sin())
+        })
+        .collect()
+}
+
+/// Row by row in the smallest square grid that fits
+fn grid_layout(count: usize) -> Vec<Vec2> {
+    let columns = (count as f32).sqrt().ceil().max(1.0) as usize;
+    let rows = count.div_ceil(columns).max(1);
+    (0..count)
+        .map(|i| Vec2::new(
+            ((i % columns) as f32 + 0.5) / columns as f32,
+            ((i / columns) as f32 + 0.5) / rows as f32,
+        ))
+        .collect()
+}
+
+/// A row per depth from the initial mode, in index order within a row. Unreachable modes
+/// get a row of their own below the rest.
+fn hierarchical_layout(depths: &[Option<usize>]) -> Vec<Vec2> {
+    let unreachable_row = depths.iter().flatten().max().map_or(0, |deepest| deepest + 1);
+    let row_of = |depth: Option<usize>| depth.unwrap_or(unreachable_row);
+    let rows = depths.iter().map(|&depth| row_of(depth)).max().map_or(1, |last| last + 1);
+
+    let mut row_sizes = vec![0usize; rows];
+    for &depth in depths {
+        row_sizes[row_of(depth)] += 1;
+    }
+    let mut placed = vec![0usize; rows];
+    depths.iter()
+        .map(|&depth| {
+            let row = row_of(depth);
+            let column = placed[row];
+            placed[row] += 1;
+            Vec2::new(
+                (column as f32 + 0.5) / row_sizes[row] as f32,
+                (row as f32 + 0.5) / rows as f32,
+            )
+        })
+        .collect()
+}
+
+/// Fruchterman-Reingold from the circular layout: every pair of nodes repels, linked nodes
+/// attract, and the step size cools to zero. Deterministic, so the same genome always gets
+/// the same picture.
+fn force_directed_layout(count: usize, edges: &[(usize, usize)]) -> Vec<Vec2> {
+    let mut positions = circular_layout(count);
+    if count < 2 {
+        return positions;
+    }
+    // Ideal distance between nodes for a unit area
+    let k = (1.0 / count as f32).sqrt();
+    let mut displacement = vec![Vec2::ZERO; count];
+    for iteration in 0..FORCE_ITERATIONS {
+        displacement.fill(Vec2::ZERO);
+        for i in 0..count {
+            for j in (i + 1)..count {
+                let delta = positions[i] - positions[j];
+                // Nudge coincident nodes apart in a fixed direction
+                let distance = delta.length().max(1e-4);
+                let direction = if delta.length() > 1e-4 { delta / distance } else { Vec2::X };
+                let push = direction * (k * k / distance);
+                displacement[i] += push;
+                displacement[j] -= push;
+            }
+        }
+        for &(a, b) in edges {
+            let delta = positions[a] - positions[b];
+            let pull = delta * (delta.length() / k);
+            displacement[a] -= pull;
+            displacement[b] += pull;
+        }
+
+        let temperature = 0.1 * (1.0 - iteration as f32 / FORCE_ITERATIONS as f32);
+        for (position, moved) in positions.iter_mut().zip(&displacement) {
+            let length = moved.length();
+            if length > 0.0 {
+                *position += *moved / length * length.min(temperature);
+            }
+        }
+    }
+    fit_to_unit_square(&mut positions);
+    positions
+}
+
+/// Scale and shift positions so their bounding box fills the layout square inside the margin
+fn fit_to_unit_square(positions: &mut [Vec2]) {
+    let min = positions.iter().copied().reduce(Vec2::min).unwrap_or(Vec2::ZERO);
+    let max = positions.iter().copied().reduce(Vec2::max).unwrap_or(Vec2::ONE);
+    let size = (max - min).max(Vec2::splat(1e-6));
+    let span = 1.0 - 2.0 * LAYOUT_MARGIN;
+    for position in positions.iter_mut() {
+        // A degenerate axis (all nodes in a line) goes down the middle
+        let t = Vec2::select((max - min).cmpgt(Vec2::splat(1e-6)), (*position - min) / size, Vec2::splat(0.5));
+        *position = Vec2::splat(LAYOUT_MARGIN) + t * span;
+    }
+}
+
+/// `t` in 0..=1 eased in and out
+fn smoothstep(t: f32) -> f32 {
+    let t = t.clamp(0.0, 1.0);
+    t * t * (3.0 - 2.0 * t)
+}
+
+/// Smallest and largest value of `parameter` across the genome's modes
+pub fn value_range(genome: &GenomeData, parameter: HeatmapParameter) -> Option<(f32, f32)> {
+    genome.modes.iter()
+        .map(|mode| parameter.value(mode))
+        .fold(None, |range, value| match range {
+            None => Some((value, value)),
+            Some((min, max)) => Some((min.min(value), max.max(value))),
+        })
+}
+
+/// Where `value` sits in `min..=max`, from 0 to 1. A genome where every mode has the same value sits in the middle.
+pub fn normalize(value: f32, (min, max): (f32, f32)) -> f32 {
+    if max > min {
+        ((value - min) / (max - min)).clamp(0.0, 1.0)
+    } else {
+        0.5
+    }
+}
+
+/// Heatmap color for `t` in 0..=1: blue through cyan, green and yellow to red
+pub fn heat_color(t: f32) -> egui::Color32 {
+    const STOPS: [[f32; 3]; 5] = [
+        [0.0, 0.0, 1.0],
+        [0.0, 1.0, 1.0],
+        [0.0, 1.0, 0.0],
+        [1.0, 1.0, 0.0],
+        [1.0, 0.0, 0.0],
+    ];
+    let scaled = t.clamp(0.0, 1.0) * (STOPS.len() - 1) as f32;
+    let index = (scaled.floor() as usize).min(STOPS.len() - 2);
+    let local = scaled - index as f32;
+    let [r, g, b] = std::array::from_fn(|i| STOPS[index][i] + (STOPS[index + 1][i] - STOPS[index][i]) * local);
+    egui::Color32::from_rgb((r * 255.0).round() as u8, (g * 255.0).round() as u8, (b * 255.0).round() as u8)
+}
+
+fn mode_color(mode: &ModeSettings) -> egui::Color32 {
+    egui::Color32::from_rgb(
+        (mode.color.x * 255.0) as u8,
+        (mode.color.y * 255.0) as u8,
+        (mode.color.z * 255.0) as u8,
+    )
+}
+
+/// Move the nodes to a fresh `layout`, animating from wherever they are drawn now
+fn start_arrangement(state: &mut GenomeGraphState, genome: &mut GenomeData, layout: GraphLayout, now: f64) {
+    let from = displayed_positions(state, &genome.graph_positions, now);
+    genome.graph_positions = arrange(genome, layout);
+    state.transition = Some((from, now));
+}
+
+/// Node positions to draw: partway to `targets` during a transition, otherwise the targets
+fn displayed_positions(state: &GenomeGraphState, targets: &[[f32; 2]], now: f64) -> Vec<[f32; 2]> {
+    let Some((from, started)) = state.transition.as_ref().filter(|(from, _)| from.len() == targets.len()) else {
+        return targets.to_vec();
+    };
+    let t = smoothstep(((now - started) / ARRANGE_SECONDS) as f32);
+    from.iter()
+        .zip(targets)
+        .map(|(from, to)| Vec2::from_array(*from).lerp(Vec2::from_array(*to), t).to_array())
+        .collect()
+}
+
+/// Modes with arrows to their children, arranged by `layout`. Clicking a node selects that mode.
+pub fn show_genome_graph(
+    ctx: &egui::Context,
+    state: &mut GenomeGraphState,
+    current_genome: &mut CurrentGenome,
+    layout: &mut GraphLayout,
+) {
+    let now = ctx.input(|i| i.time);
+    // Genomes saved before the graph was arranged, or with modes added or removed since
+    if current_genome.genome.graph_positions.len() != current_genome.genome.modes.len() {
+        current_genome.genome.graph_positions = arrange(&current_genome.genome, *layout);
+        state.transition = None;
+    }
+    if state.transition.as_ref().is_some_and(|(_, started)| now - started >= ARRANGE_SECONDS) {
+        state.transition = None;
+    }
+
+    let mut open = state.open;
+    egui::Window::new("Genome Graph")
+        .open(&mut open)
+        .default_size([500.0, 560.0])
+        .resizable(true)
+        .show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                ui.label("Layout:");
+                let previous = *layout;
+                egui::ComboBox::from_id_salt("genome_graph_layout")
+                    .selected_text(layout.label())
+                    .show_ui(ui, |ui| {
+                        for option in GraphLayout::ALL {
+                            ui.selectable_value(layout, option, option.label());
+                        }
+                    });
+                let rearrange = ui.button("Re-arrange")
+                    .on_hover_text("Lay the graph out again, e.g. after changing which modes link");
+                if *layout != previous || rearrange.clicked() {
+                    start_arrangement(state, &mut current_genome.genome, *layout, now);
+                }
+                let reset_view = ui.add_enabled(state.view != GraphView::default(), egui::Button::new("Reset View"))
+                    .on_hover_text("Scroll to zoom, drag empty space to pan");
+                if reset_view.clicked() {
+                    state.view = GraphView::default();
+                }
+            });
+
+            ui.horizontal(|ui| {
+                ui.label("Color by:");
+                egui::ComboBox::from_id_salt("genome_graph_heatmap")
+                    .selected_text(state.heatmap.map_or("Mode Color", HeatmapParameter::label))
+                    .show_ui(ui, |ui| {
+                        ui.selectable_value(&mut state.heatmap, None, "Mode Color");
+                        for parameter in HeatmapParameter::ALL {
+                            ui.selectable_value(&mut state.heatmap, Some(parameter), parameter.label());
+                        }
+                    });
+            });
+
+            let range = state.heatmap.and_then(|parameter| Some((parameter, value_range(&current_genome.genome, parameter)?)));
+            if let Some((_, range)) = range {
+                draw_legend(ui, range);
+            }
+            ui.separator();
+
+            let positions = displayed_positions(state, &current_genome.genome.graph_positions, now);
+            draw_graph(ui, current_genome, &positions, range, &mut state.view);
+        });
+    state.open = open;
+    if state.transition.is_some() {
+        ctx.request_repaint();
+    }
+}
+
+/// Gradient bar labelled with the genome's min and max values
+fn draw_legend(ui: &mut egui::Ui, (min, max): (f32, f32)) {
+    ui.horizontal(|ui| {
+        ui.label(format!("{:.2}", min));
+        let width = (ui.available_width() - 60.0).max(40.0);
+        let (rect, _) = ui.allocate_exact_size(egui::vec2(width, LEGEND_HEIGHT), egui::Sense::hover());
+        let steps = 32;
+        let step_width = rect.width() / steps as f32;
+        for i in 0..steps {
+            let x = rect.left() + i as f32 * step_width;
+            let step_rect = egui::Rect::from_min_size(egui::pos2(x, rect.top()), egui::vec2(step_width + 0.5, rect.height()));
+            ui.painter().rect_filled(step_rect, 0.0, heat_color(i as f32 / (steps - 1) as f32));
+        }
+        ui.label(format!("{:.2}", max));
+    });
+}
+
+/// Draw the graph with nodes at `layout_positions` (0..=1, one per mode) scaled to the space left
+/// and transformed by `view`. Scrolling zooms around the cursor and dragging empty space pans.
+fn draw_graph(
+    ui: &mut egui::Ui,
+    current_genome: &mut CurrentGenome,
+    layout_positions: &[[f32; 2]],
+    heatmap: Option<(HeatmapParameter, (f32, f32))>,
+    view: &mut GraphView,
+) {
+    let (rect, background) = ui.allocate_exact_size(ui.available_size(), egui::Sense::click_and_drag());
+    let genome = &current_genome.genome;
+    if genome.modes.is_empty() || layout_positions.len() != genome.modes.len() {
+        return;
+    }
+
+    let area = rect.shrink(NODE_RADIUS * 2.0);
+    let half_size = area.size() / 2.0;
+    // Nodes sense drags too, so only drags that start on empty space reach the background
+    if background.dragged() {
+        view.pan_by(background.drag_delta(), half_size);
+    }
+    if let Some(cursor) = background.hover_pos() {
+        let (scroll, pinch) = ui.input(|i| (i.smooth_scroll_delta.y, i.zoom_delta()));
+        let factor = (scroll * WHEEL_ZOOM_SPEED).exp() * pinch;
+        if factor != 1.0 {
+            view.zoom_at(cursor - area.center(), factor, half_size);
+        }
+    }
+
+    let positions: Vec<egui::Pos2> = layout_positions.iter()
+        .map(|&[x, y]| area.center() + view.to_screen(egui::vec2((x - 0.5) * area.width(), (y - 0.5) * area.height())))
+        .collect();
+
+    let painter = ui.painter_at(rect);
+    let edge_stroke = egui::Stroke::new(1.0, ui.visuals().weak_text_color());
+    for (i, mode) in genome.modes.iter().enumerate() {
+        for child in [mode.child_a.mode_number, mode.child_b.mode_number] {
+            let Some(&target) = usize::try_from(child).ok().and_then(|c| positions.get(c)) else {
+                continue;
+            };
+            if child as usize == i {
+                continue;
+            }
+            let from = positions[i];
+            let direction = (target - from).normalized();
+            painter.arrow(from + direction * NODE_RADIUS, target - from - direction * NODE_RADIUS * 2.0, edge_stroke);
+        }
+    }
+
+    let selected = current_genome.selected_mode_index as usize;
+    let mut clicked = None;
+    for (i, (mode, &position)) in genome.modes.iter().zip(&positions).enumerate() {
+        let fill = match heatmap {
+            Some((parameter, range)) => heat_color(normalize(parameter.value(mode), range)),
+            None => mode_color(mode),
+        };
+        painter.circle_filled(position, NODE_RADIUS, fill);
+        if i == selected {
+            painter.circle_stroke(position, NODE_RADIUS + 2.0, egui::Stroke::new(2.0, ui.visuals().strong_text_color()));
+        }
+
+        let node_rect = egui::Rect::from_center_size(position, egui::Vec2::splat(NODE_RADIUS * 2.0));
+        if !rect.intersects(node_rect) {
+            continue;
+        }
+        let response = ui.interact(node_rect, ui.id().with(("genome_graph_node", i)), egui::Sense::click_and_drag());
+        let response = match heatmap {
+            Some((parameter, _)) => response.on_hover_text(format!("{}\n{}: {:.2}", mode.name, parameter.label(), parameter.value(mode))),
+            None => response.on_hover_text(&mode.name),
+        };
+        if response.clicked() {
+            clicked = Some(i);
+        }
+    }
+
+    if let Some(i) = clicked {
+        current_genome.selected_mode_index = i as i32;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalize_uses_genome_range() {
+        let mut genome = GenomeData {
+            modes: (0..3).map(|i| ModeSettings::new_self_splitting(i, format!("M {}", i))).collect(),
+            ..Default::default()
+        };
+        genome.modes[0].split_interval = 2.0;
+        genome.modes[1].split_interval = 10.0;
+        genome.modes[2].split_interval = 6.0;
+
+        let range = value_range(&genome, HeatmapParameter::SplitInterval).unwrap();
+        assert_eq!(range, (2.0, 10.0));
+        assert_eq!(normalize(6.0, range), 0.5);
+        assert_eq!(normalize(10.0, range), 1.0);
+        // All modes equal: nothing stands out
+        assert_eq!(normalize(3.0, (3.0, 3.0)), 0.5);
+    }
+
+    #[test]
+    fn hierarchical_layout_puts_modes_at_their_depth() {
+        let positions = hierarchical_layout(&[Some(0), Some(1), Some(1), None, Some(2)]);
+
+        let row = |i: usize| positions[i].y;
+        assert!(row(0) < row(1));
+        assert_eq!(row(1), row(2));
+        // Unreachable modes go below the deepest reachable one
+        assert!(row(2) < row(4) && row(4) < row(3));
+        assert_eq!(positions[0].x, 0.5);
+        assert_eq!((positions[1].x, positions[2].x), (0.25, 0.75));
+    }
+
+    #[test]
+    fn force_directed_layout_pulls_linked_modes_together() {
+        // Two triangles with no link between them
+        let edges = [(0, 1), (1, 2), (2, 0), (3, 4), (4, 5), (5, 3)];
+        let positions = force_directed_layout(6, &edges);
+
+        let distance = |a: usize, b: usize| positions[a].distance(positions[b]);
+        assert!(distance(0, 1) < distance(0, 3));
+        assert!(distance(3, 4) < distance(1, 4));
+        assert!(positions.iter().all(|p| p.cmpge(Vec2::ZERO).all() && p.cmple(Vec2::ONE).all()));
+        assert_eq!(positions, force_directed_layout(6, &edges));
+    }
+
+    #[test]
+    fn grid_and_circular_layouts_fill_the_square() {
+        let grid = grid_layout(5);
+        assert_eq!(grid[0], Vec2::new(1.0 / 6.0, 0.25));
+        assert_eq!(grid[4], Vec2::new(0.5, 0.75));
+
+        let ring = circular_layout(4);
+        assert!((ring[0] - Vec2::new(0.5, LAYOUT_MARGIN)).length() < 1e-6);
+        assert!(ring.iter().all(|p| (p.distance(Vec2::splat(0.5)) - (0.5 - LAYOUT_MARGIN)).abs() < 1e-6));
+    }
+
+    #[test]
+    fn zoom_keeps_the_point_under_the_cursor() {
+        let half_size = egui::vec2(200.0, 100.0);
+        let mut view = GraphView::default();
+        let cursor = egui::vec2(50.0, -20.0);
+        let anchor = view.to_graph(cursor);
+
+        view.zoom_at(cursor, 2.0, half_size);
+        assert_eq!(view.zoom, 2.0);
+        assert!((view.to_screen(anchor) - cursor).length() < 1e-4);
+
+        view.zoom_at(cursor, 1000.0, half_size);
+        assert_eq!(view.zoom, MAX_ZOOM);
+        // Panning stops once the view's center reaches the edge of the graph
+        view.pan_by(egui::vec2(1e6, -1e6), half_size);
+        assert_eq!(view.pan, egui::vec2(half_size.x, -half_size.y) * MAX_ZOOM);
+    }
+
+    #[test]
+    fn heat_color_runs_blue_to_red() {
+        assert_eq!(heat_color(0.0), egui::Color32::from_rgb(0, 0, 255));
+        assert_eq!(heat_color(0.5), egui::Color32::from_rgb(0, 255, 0));
+        assert_eq!(heat_color(1.0), egui::Color32::from_rgb(255, 0, 0));
+    }
+}
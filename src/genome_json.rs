@@ -0,0 +1,100 @@
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiClipboard};
+use std::time::{Duration, Instant};
+
+use crate::genome::{CurrentGenome, GenomeData};
+use crate::toast::Toasts;
+use crate::ui::{replace_genome, GenomeReplacement, WidgetDemoState};
+
+/// Re-serializing a large genome every frame is wasteful; this is fresh enough to follow edits
+const REFRESH_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Raw JSON window state
+#[derive(Default)]
+pub struct GenomeJsonState {
+    pub open: bool,
+    /// Set by "Paste Genome from Clipboard"; handled by [`paste_genome_from_clipboard`]
+    pub paste_requested: bool,
+    text: String,
+    refreshed_at: Option<Instant>,
+}
+
+impl GenomeJsonState {
+    /// Re-serialize the genome as it would be saved if the cached text is older than [`REFRESH_INTERVAL`]
+    fn refresh(&mut self, current_genome: &CurrentGenome) {
+        if self.refreshed_at.is_some_and(|at| at.elapsed() < REFRESH_INTERVAL) {
+            return;
+        }
+        self.text = serde_json::to_string_pretty(&current_genome.genome_for_save()).unwrap_or_else(|err| format!("Failed to serialize genome: {}", err));
+        self.refreshed_at = Some(Instant::now());
+    }
+}
+
+/// Read-only view of the genome as it would be written to a file
+pub fn show_genome_json(ctx: &egui::Context, state: &mut GenomeJsonState, current_genome: &CurrentGenome) {
+    state.refresh(current_genome);
+
+    let mut open = state.open;
+    egui::Window::new("Genome JSON")
+        .open(&mut open)
+        .default_size([480.0, 600.0])
+        .resizable(true)
+        .show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                if ui.button("Copy to Clipboard").clicked() {
+                    ctx.copy_text(state.text.clone());
+                }
+                if ui.button("Paste from Clipboard").on_hover_text("Replace the genome with JSON from the clipboard").clicked() {
+                    state.paste_requested = true;
+                }
+                ui.label(format!("{} lines", state.text.lines().count()));
+            });
+            ui.separator();
+
+            egui::ScrollArea::both()
+                .auto_shrink([false, false])
+                .show(ui, |ui| {
+                    ui.add(
+                        egui::TextEdit::multiline(&mut state.text.as_str())
+                            .code_editor()
+                            .desired_width(f32::INFINITY),
+                    );
+                });
+        });
+    state.open = open;
+
+    if !state.open {
+        // Start from fresh text when reopened
+        state.refreshed_at = None;
+    }
+}
+
+/// Replace the current genome with genome JSON from the clipboard, as one undoable step,
+/// asking first if it has unsaved changes.
+/// Anything that isn't a valid genome is reported and leaves the current one alone.
+pub fn paste_genome_from_clipboard(
+    mut widget_demo_state: ResMut<WidgetDemoState>,
+    clipboard: Option<ResMut<EguiClipboard>>,
+    mut current_genome: ResMut<CurrentGenome>,
+    mut toasts: ResMut<Toasts>,
+) {
+    if !std::mem::take(&mut widget_demo_state.genome_json.paste_requested) {
+        return;
+    }
+    let Some(text) = clipboard.and_then(|mut clipboard| clipboard.get_text()) else {
+        toasts.error("Could not read the clipboard");
+        return;
+    };
+    match GenomeData::from_json(&text) {
+        Ok(genome) => {
+            let replacement = GenomeReplacement::Paste(Box::new(genome));
+            replace_genome(&mut current_genome, &mut widget_demo_state, &mut toasts, replacement);
+            // Show the pasted genome rather than text from before it
+            widget_demo_state.genome_json.refreshed_at = None;
+        }
+        Err(err) => {
+            warn!("Clipboard is not a genome: {}", err);
+            toasts.error(format!("The clipboard doesn't hold genome JSON: {}", err));
+        }
+    }
+}
@@ -0,0 +1,154 @@
+use bevy_egui::egui;
+
+use crate::camera::TOGGLE_VIEW_SHORTCUT_TEXT;
+use crate::dock::{DockResource, Panel, DUPLICATE_SHORTCUT_TEXT, REOPEN_SHORTCUT_TEXT, SOLO_SHORTCUT_TEXT};
+
+/// Help window state
+#[derive(Default)]
+pub struct HelpState {
+    pub open: bool,
+    pub search: String,
+}
+
+/// One section of the help window, with the panel where its settings are edited
+pub struct HelpTopic {
+    pub title: &'static str,
+    pub body: &'static str,
+    pub panel: Option<Panel>,
+}
+
+pub const TOPICS: &[HelpTopic] = &[
+    HelpTopic {
+        title: "Genomes and Modes",
+        body: "A genome describes how an organism grows from a single cell. It is a list of modes: \
+            every cell is in exactly one mode, which decides its cell type, when it splits and what \
+            its children become. The first cell starts in the genome's initial mode.",
+        panel: Some(Panel::Modes),
+    },
+    HelpTopic {
+        title: "Cell Types",
+        body: "Each mode gives its cells a type. Photocytes gain nutrients from light, Phagocytes \
+            eat food, Flagellocytes swim, Devorocytes consume other cells and Lipocytes store \
+            nutrients. Only the settings of the chosen type are shown.",
+        panel: Some(Panel::NameTypeEditor),
+    },
+    HelpTopic {
+        title: "Splitting",
+        body: "A cell splits once it has grown to its split mass and its split interval has passed. \
+            The parent split direction sets the plane it divides along, and max splits limits how \
+            often a mode splits before its children switch to the \"after splits\" modes.",
+        panel: Some(Panel::ParentSettings),
+    },
+    HelpTopic {
+        title: "Children A and B",
+        body: "Splitting turns a cell into two children, A and B. Each child has the mode it \
+            continues in, which may be the parent's own, and an orientation relative to the parent, \
+            set with the quaternion balls. Keep Adhesion lets a child inherit the parent's bonds.",
+        panel: Some(Panel::QuaternionBall),
+    },
+    HelpTopic {
+        title: "Adhesion",
+        body: "With Make Adhesion, the two children stay bonded by a spring. Rest length and \
+            stiffness shape the bond, damping calms it, and a breakable bond snaps when pulled \
+            harder than its break force.",
+        panel: Some(Panel::AdhesionSettings),
+    },
+    HelpTopic {
+        title: "Colored Mode Lists",
+        body: "Wherever a mode is picked from a list, each entry is drawn in that mode's own color, \
+            with black or white text for contrast, so modes can be told apart at a glance. A star \
+            marks a favorite mode. Colors are set in the Modes panel, where a pinned color is kept \
+            by the recolor actions.",
+        panel: Some(Panel::Modes),
+    },
+];
+
+/// Keys and the actions they trigger. The dock hotkeys share their labels with the Windows menu.
+pub const SHORTCUTS: &[(&str, &str)] = &[
+    ("Ctrl+Z", "Undo the last genome edit"),
+    ("Ctrl+Shift+Z", "Redo"),
+    (SOLO_SHORTCUT_TEXT, "Show only the focused panel and the viewport"),
+    (DUPLICATE_SHORTCUT_TEXT, "Open a second copy of the focused panel"),
+    (REOPEN_SHORTCUT_TEXT, "Reopen the last closed panel"),
+    ("F2", "Rename the focused mode in the Modes list"),
+    ("Ctrl+wheel", "Scale the Modes list text while over it"),
+    ("Alt+wheel", "Step the selected mode while over the Modes list"),
+    (TOGGLE_VIEW_SHORTCUT_TEXT, "Fly the viewport camera between the selected sphere and the whole scene"),
+    ("Arrow keys", "Nudge the focused value; Shift for ×10, Ctrl for ×0.1"),
+    ("[ / ]", "Step the focused child's mode while over Child Settings"),
+    ("X / Y / Z, L", "Lock a quaternion ball's rotation axis while over it"),
+];
+
+/// Whether every word of `query` appears in one of `texts`, ignoring case
+pub fn matches_search(query: &str, texts: &[&str]) -> bool {
+    let texts: Vec<String> = texts.iter().map(|text| text.to_lowercase()).collect();
+    query.to_lowercase()
+        .split_whitespace()
+        .all(|word| texts.iter().any(|text| text.contains(word)))
+}
+
+/// Searchable explanations of the genome model and the keyboard shortcuts, with links to the panels
+pub fn show_help(ctx: &egui::Context, state: &mut HelpState, dock_resource: &mut DockResource) {
+    let mut open = state.open;
+    egui::Window::new("Help")
+        .open(&mut open)
+        .default_size([420.0, 520.0])
+        .resizable(true)
+        .show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                ui.label("Search:");
+                ui.text_edit_singleline(&mut state.search);
+                if !state.search.is_empty() && ui.small_button("✖").on_hover_text("Clear the search").clicked() {
+                    state.search.clear();
+                }
+            });
+            ui.separator();
+
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                let mut shown = 0;
+                for topic in TOPICS.iter().filter(|topic| matches_search(&state.search, &[topic.title, topic.body])) {
+                    shown += 1;
+                    ui.strong(topic.title);
+                    ui.label(topic.body);
+                    if let Some(panel) = &topic.panel
+                        && ui.link(format!("Go to {}", panel)).clicked()
+                    {
+                        dock_resource.focus_panel(panel);
+                    }
+                    ui.add_space(8.0);
+                }
+
+                let shortcuts: Vec<_> = SHORTCUTS.iter()
+                    .filter(|(keys, action)| matches_search(&state.search, &["keyboard shortcuts", keys, action]))
+                    .collect();
+                if !shortcuts.is_empty() {
+                    shown += 1;
+                    ui.strong("Keyboard Shortcuts");
+                    egui::Grid::new("help_shortcuts").striped(true).show(ui, |ui| {
+                        for (keys, action) in shortcuts {
+                            ui.monospace(*keys);
+                            ui.label(*action);
+                            ui.end_row();
+                        }
+                    });
+                }
+
+                if shown == 0 {
+                    ui.weak("Nothing matches the search.");
+                }
+            });
+        });
+    state.open = open;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn search_needs_every_word_in_some_text() {
+        assert!(matches_search("", &["Adhesion"]));
+        assert!(matches_search("adhesion BREAK", &["Adhesion", "snaps past its break force"]));
+        assert!(!matches_search("adhesion swim", &["Adhesion", "snaps past its break force"]));
+    }
+}
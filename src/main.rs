@@ -1,25 +1,45 @@
 mod scene;
+mod camera;
 mod drag;
 mod widgets;
 mod dock;
 mod ui;
 mod genome;
+mod toast;
+mod asset_browser;
+mod preferences;
+mod file_dialog;
+mod window_state;
+mod genome_graph;
+mod help;
+mod genome_json;
+mod mode_compare;
+mod mode_card;
+mod workspace;
+mod performance;
 
 use bevy::prelude::*;
 use bevy::window::WindowResolution;
 use bevy_egui::EguiPlugin;
 
 use scene::ScenePlugin;
+use camera::CameraPlugin;
 use drag::DragPlugin;
 use genome::GenomePlugin;
-use dock::{setup_dock, auto_save_dock_state, save_on_exit};
+use asset_browser::AssetBrowserPlugin;
+use preferences::PreferencesPlugin;
+use window_state::WindowStatePlugin;
+use workspace::WorkspacePlugin;
+use performance::PerformancePlugin;
+use dock::{setup_dock, auto_save_dock_state, enforce_placeholders, save_dock_state, DockResource};
+use window_state::LastWindowGeometry;
 use ui::ui_system;
 
 fn main() {
     App::new()
         .add_plugins(DefaultPlugins.set(WindowPlugin {
             primary_window: Some(Window {
-                title: "BioSpheres-Q Egui".to_string(),
+                title: window_state::APP_TITLE.to_string(),
                 resolution: WindowResolution::new(1920, 1080),
                 present_mode: bevy::window::PresentMode::AutoNoVsync,
                 resize_constraints: bevy::window::WindowResizeConstraints {
@@ -32,19 +52,85 @@ fn main() {
             ..default()
         }))
         .add_plugins(EguiPlugin::default())
+        .add_plugins(PreferencesPlugin)
         .add_plugins(ScenePlugin)
+        .add_plugins(CameraPlugin)
         .add_plugins(DragPlugin)
         .add_plugins(GenomePlugin)
+        .add_plugins(AssetBrowserPlugin)
+        .add_plugins(WindowStatePlugin)
+        .add_plugins(WorkspacePlugin)
+        .add_plugins(PerformancePlugin)
         .init_resource::<ui::GlobalUiState>()
         .init_resource::<ui::WidgetDemoState>()
-        .add_systems(Startup, (setup_dock, maximize_window))
+        .init_resource::<toast::Toasts>()
+        .init_resource::<file_dialog::FileDialogs>()
+        .add_systems(Startup, (setup_dock, open_genome_from_args))
         .add_systems(bevy_egui::EguiPrimaryContextPass, ui_system)
-        .add_systems(Update, (auto_save_dock_state, save_on_exit))
+        .add_systems(Update, genome_json::paste_genome_from_clipboard)
+        .add_systems(Update, (enforce_placeholders, auto_save_dock_state).chain())
+        .add_systems(Last, save_on_exit)
         .run();
 }
 
-fn maximize_window(mut windows: Query<&mut Window>) {
-    for mut window in windows.iter_mut() {
-        window.set_maximized(true);
+/// `bevy_egui_app path/to/genome.json` opens that genome instead of the default one
+fn open_genome_from_args(
+    mut current_genome: ResMut<genome::CurrentGenome>,
+    mut toasts: ResMut<toast::Toasts>,
+) {
+    let Some(path) = std::env::args_os()
+        .skip(1)
+        .map(std::path::PathBuf::from)
+        .find(|arg| !arg.to_string_lossy().starts_with('-'))
+    else {
+        return;
+    };
+
+    match genome::GenomeData::load_from_file(&path) {
+        Ok(genome) => {
+            current_genome.open(genome);
+            current_genome.file_path = Some(path.clone());
+            info!("Opened genome from command line: {:?}", path);
+        }
+        Err(err) => {
+            warn!("Failed to open genome {:?} from command line, using default: {}", path, err);
+            toasts.error(format!("Could not open {}: {}", path.display(), err));
+        }
+    }
+}
+
+/// Write everything that persists, once, when the app is asked to exit.
+/// Runs in `Last` so the files are written in the frame the exit was requested,
+/// before the runner sees the exit message and tears the app down.
+fn save_on_exit(
+    mut exit_events: MessageReader<AppExit>,
+    mut saved: Local<bool>,
+    dock_resource: Res<DockResource>,
+    current_genome: Res<genome::CurrentGenome>,
+    preferences: Res<preferences::Preferences>,
+    window_geometry: Res<LastWindowGeometry>,
+) {
+    if exit_events.is_empty() {
+        return;
+    }
+    // Several systems (or a second close request) can send AppExit; only save for the first
+    exit_events.clear();
+    if std::mem::replace(&mut *saved, true) {
+        return;
     }
+
+    match save_dock_state(dock_resource.layout_to_save()) {
+        Ok(()) => info!("Saved dock state on exit"),
+        Err(err) => warn!("Failed to save dock state on exit: {}", err),
+    }
+
+    if preferences.genome_autosave_interval().is_some() {
+        match current_genome.write_autosave(preferences.genome_autosave_backups()) {
+            Ok(()) => info!("Autosaved genome on exit"),
+            Err(err) => warn!("Failed to autosave genome on exit: {}", err),
+        }
+    }
+
+    preferences.save();
+    window_geometry.save(&preferences);
 }
@@ -0,0 +1,205 @@
+//! "Export Mode Card": a PNG showing a mode's color, name, type and main parameters, for
+//! sharing a mode's look without the whole genome. Text is drawn from egui's font atlas, so
+//! the card uses the same font as the editor.
+
+use bevy::tasks::{futures::check_ready, IoTaskPool, Task};
+use bevy_egui::egui::{self, Color32, ColorImage, FontId, Pos2, Rect};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use crate::genome::{GenomeData, CELL_TYPE_NAMES};
+use crate::widgets;
+
+/// Card width in points; the height follows the number of lines
+const CARD_WIDTH: f32 = 320.0;
+const PADDING: f32 = 12.0;
+const HEADER_HEIGHT: f32 = 48.0;
+const LINE_SPACING: f32 = 4.0;
+const NAME_SIZE: f32 = 20.0;
+const TEXT_SIZE: f32 = 14.0;
+const BACKGROUND: Color32 = Color32::from_rgb(32, 32, 36);
+const LABEL_COLOR: Color32 = Color32::from_rgb(160, 160, 170);
+const VALUE_COLOR: Color32 = Color32::from_rgb(230, 230, 235);
+
+/// Label/value rows shown under the header
+pub fn card_lines(genome: &GenomeData, idx: usize, decimals: usize) -> Vec<(&'static str, String)> {
+    let mode = &genome.modes[idx];
+    let mode_name = |number: i32| {
+        genome.modes.get(number.max(0) as usize).map_or_else(|| "-".to_string(), |mode| mode.name.clone())
+    };
+    vec![
+        ("Type", CELL_TYPE_NAMES.get(mode.cell_type as usize).copied().unwrap_or("Unknown").to_string()),
+        ("Split interval", format!("{:.*} s", decimals, mode.split_interval)),
+        ("Split mass", format!("{:.*}", decimals, mode.split_mass)),
+        ("Split ratio", format!("{:.*}", decimals, mode.split_ratio)),
+        ("Nutrient priority", format!("{:.*}", decimals, mode.nutrient_priority)),
+        ("Split direction", format!(
+            "{:.*}°, {:.*}°",
+            decimals, mode.parent_split_direction.x, decimals, mode.parent_split_direction.y,
+        )),
+        ("Child A", mode_name(mode.child_a.mode_number)),
+        ("Child B", mode_name(mode.child_b.mode_number)),
+        ("Make adhesion", if mode.parent_make_adhesion { "Yes" } else { "No" }.to_string()),
+    ]
+}
+
+/// Draw the card for mode `idx` at the context's pixel density
+pub fn render_mode_card(ctx: &egui::Context, genome: &GenomeData, idx: usize, decimals: usize) -> ColorImage {
+    let mode = &genome.modes[idx];
+    let color = Color32::from_rgb(
+        (mode.color.x * 255.0) as u8,
+        (mode.color.y * 255.0) as u8,
+        (mode.color.z * 255.0) as u8,
+    );
+    let lines = card_lines(genome, idx, decimals);
+
+    let layout = |text: String, size: f32, color: Color32| {
+        ctx.fonts_mut(|fonts| fonts.layout_no_wrap(text, FontId::proportional(size), color))
+    };
+    let name = layout(mode.name.clone(), NAME_SIZE, widgets::contrast_text_color(color));
+    let rows: Vec<_> = lines.into_iter()
+        .map(|(label, value)| (layout(label.to_string(), TEXT_SIZE, LABEL_COLOR), layout(value, TEXT_SIZE, VALUE_COLOR)))
+        .collect();
+    let row_height = TEXT_SIZE + LINE_SPACING + 2.0;
+    let height = HEADER_HEIGHT + PADDING * 2.0 + row_height * rows.len() as f32;
+
+    let pixels_per_point = ctx.pixels_per_point();
+    let size = [(CARD_WIDTH * pixels_per_point).round() as usize, (height * pixels_per_point).round() as usize];
+    let mut card = Canvas { image: ColorImage::filled(size, BACKGROUND), pixels_per_point };
+    // Laid-out glyphs are in the atlas from here on
+    let atlas = ctx.fonts(|fonts| fonts.image());
+
+    card.fill(Rect::from_min_size(Pos2::ZERO, egui::vec2(CARD_WIDTH, HEADER_HEIGHT)), color);
+    card.text(&atlas, &name, Pos2::new(PADDING, (HEADER_HEIGHT - name.size().y) / 2.0));
+
+    let value_x = CARD_WIDTH / 2.0;
+    for (i, (label, value)) in rows.iter().enumerate() {
+        let y = HEADER_HEIGHT + PADDING + row_height * i as f32;
+        card.text(&atlas, label, Pos2::new(PADDING, y));
+        card.text(&atlas, value, Pos2::new(value_x, y));
+    }
+    card.image
+}
+
+/// Image being drawn in points
+struct Canvas {
+    image: ColorImage,
+    pixels_per_point: f32,
+}
+
+impl Canvas {
+    fn fill(&mut self, rect: Rect, color: Color32) {
+        let [width, height] = self.image.size;
+        let to_pixels = |value: f32, max: usize| ((value * self.pixels_per_point).round().max(0.0) as usize).min(max);
+        for y in to_pixels(rect.min.y, height)..to_pixels(rect.max.y, height) {
+            for x in to_pixels(rect.min.x, width)..to_pixels(rect.max.x, width) {
+                self.image[(x, y)] = color;
+            }
+        }
+    }
+
+    /// Blend a laid-out galley in at `origin`, taking glyph coverage from the font atlas
+    fn text(&mut self, atlas: &ColorImage, galley: &Arc<egui::Galley>, origin: Pos2) {
+        let [width, height] = self.image.size;
+        // Cards lay out each string in a single color
+        let color = galley.job.sections.first().map_or(Color32::WHITE, |section| section.format.color);
+        for placed in &galley.rows {
+            for glyph in &placed.row.glyphs {
+                let uv = glyph.uv_rect;
+                if uv.is_nothing() {
+                    continue;
+                }
+                let left_top = (origin + placed.pos.to_vec2() + glyph.pos.to_vec2() + uv.offset) * self.pixels_per_point;
+                let (left, top) = (left_top.x.round() as i64, left_top.y.round() as i64);
+
+                for v in uv.min[1]..uv.max[1] {
+                    for u in uv.min[0]..uv.max[0] {
+                        let x = left + (u - uv.min[0]) as i64;
+                        let y = top + (v - uv.min[1]) as i64;
+                        if x < 0 || y < 0 || x as usize >= width || y as usize >= height {
+                            continue;
+                        }
+                        let coverage = atlas[(u as usize, v as usize)].a() as f32 / 255.0;
+                        let pixel = &mut self.image[(x as usize, y as usize)];
+                        *pixel = blend(*pixel, color, coverage);
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn blend(under: Color32, over: Color32, amount: f32) -> Color32 {
+    let mix = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * amount).round() as u8;
+    Color32::from_rgb(mix(under.r(), over.r()), mix(under.g(), over.g()), mix(under.b(), over.b()))
+}
+
+/// Encode an opaque card as an RGB PNG
+pub fn write_png(path: &Path, image: &ColorImage) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let file = std::io::BufWriter::new(std::fs::File::create(path)?);
+    let mut encoder = png::Encoder::new(file, image.size[0] as u32, image.size[1] as u32);
+    encoder.set_color(png::ColorType::Rgb);
+    encoder.set_depth(png::BitDepth::Eight);
+    let data: Vec<u8> = image.pixels.iter().flat_map(|pixel| [pixel.r(), pixel.g(), pixel.b()]).collect();
+    let mut writer = encoder.write_header()?;
+    writer.write_image_data(&data)?;
+    writer.finish()?;
+    Ok(())
+}
+
+/// Result of writing a card: where it went, or the path and why it failed
+pub type CardResult = Result<PathBuf, (PathBuf, String)>;
+
+/// Cards being written on the IO task pool
+#[derive(Default)]
+pub struct ModeCardExport {
+    tasks: Vec<Task<CardResult>>,
+}
+
+impl ModeCardExport {
+    pub fn start(&mut self, path: PathBuf, card: ColorImage) {
+        self.tasks.push(IoTaskPool::get().spawn(async move {
+            match write_png(&path, &card) {
+                Ok(()) => Ok(path),
+                Err(err) => Err((path, err.to_string())),
+            }
+        }));
+    }
+
+    /// Results of the writes that have finished since the last poll
+    pub fn poll(&mut self) -> Vec<CardResult> {
+        let mut finished = Vec::new();
+        self.tasks.retain_mut(|task| match check_ready(task) {
+            Some(result) => {
+                finished.push(result);
+                false
+            }
+            None => true,
+        });
+        finished
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::genome::ModeSettings;
+
+    #[test]
+    fn card_lines_name_child_modes() {
+        let mut genome = GenomeData {
+            modes: vec![
+                ModeSettings::new_self_splitting(0, "Stem".to_string()),
+                ModeSettings::new_self_splitting(1, "Leaf".to_string()),
+            ],
+            ..Default::default()
+        };
+        genome.modes[0].child_b.mode_number = 1;
+
+        let lines = card_lines(&genome, 0, 2);
+
+        assert!(lines.contains(&("Child A", "Stem".to_string())));
+        assert!(lines.contains(&("Child B", "Leaf".to_string())));
+        assert!(lines.iter().any(|(label, _)| *label == "Split interval"));
+    }
+}
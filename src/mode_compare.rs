@@ -0,0 +1,129 @@
+use bevy::prelude::*;
+use bevy_egui::egui;
+use serde_json::Value;
+
+use crate::genome::diff::{copy_field, diff_fields};
+use crate::genome::CurrentGenome;
+use crate::widgets;
+
+/// Compare Modes window state. The selected mode is compared against `other`.
+#[derive(Default)]
+pub struct ModeCompareState {
+    pub open: bool,
+    pub other: usize,
+    pub only_differences: bool,
+}
+
+/// Which way a field is copied between the compared modes
+enum CopyDirection {
+    ToOther,
+    ToSelected,
+}
+
+/// Parameters of the selected mode and another mode side by side, with per-field copy buttons
+pub fn show_mode_compare(ctx: &egui::Context, state: &mut ModeCompareState, current_genome: &mut CurrentGenome) {
+    let mode_count = current_genome.genome.modes.len();
+    if mode_count == 0 {
+        return;
+    }
+    let selected = (current_genome.selected_mode_index as usize).min(mode_count - 1);
+    state.other = state.other.min(mode_count - 1);
+
+    let mut open = state.open;
+    let mut copy = None;
+    egui::Window::new("Compare Modes")
+        .open(&mut open)
+        .default_size([520.0, 600.0])
+        .resizable(true)
+        .show(ctx, |ui| {
+            let modes = &current_genome.genome.modes;
+            ui.horizontal(|ui| {
+                ui.label(format!("{}  vs", modes[selected].name));
+                egui::ComboBox::from_id_salt("mode_compare_other")
+                    .selected_text(&modes[state.other].name)
+                    .show_ui(ui, |ui| {
+                        for (i, mode) in modes.iter().enumerate() {
+                            ui.selectable_value(&mut state.other, i, &mode.name);
+                        }
+                    });
+                ui.checkbox(&mut state.only_differences, "Only differences");
+            });
+            ui.separator();
+
+            let diffs = match diff_fields(&modes[selected], &modes[state.other]) {
+                Ok(diffs) => diffs,
+                Err(err) => {
+                    ui.colored_label(ui.visuals().error_fg_color, format!("Could not compare modes: {}", err));
+                    return;
+                }
+            };
+            let decimals = widgets::display_decimals(ui);
+            let highlight = ui.visuals().warn_fg_color;
+
+            egui::ScrollArea::vertical().auto_shrink([false, false]).show(ui, |ui| {
+                egui::Grid::new("mode_compare_fields")
+                    .num_columns(4)
+                    .striped(true)
+                    .show(ui, |ui| {
+                        ui.strong("Field");
+                        ui.strong(&modes[selected].name);
+                        ui.label("");
+                        ui.strong(&modes[state.other].name);
+                        ui.end_row();
+
+                        for diff in diffs.iter().filter(|diff| !state.only_differences || diff.differs()) {
+                            let value_text = |value: &Value| {
+                                let text = egui::RichText::new(format_value(value, decimals));
+                                if diff.differs() { text.color(highlight) } else { text }
+                            };
+                            ui.label(&diff.path);
+                            ui.label(value_text(&diff.left));
+                            ui.add_enabled_ui(diff.differs() && selected != state.other, |ui| {
+                                ui.horizontal(|ui| {
+                                    if ui.small_button("→").on_hover_text("Copy to the right").clicked() {
+                                        copy = Some((diff.path.clone(), CopyDirection::ToOther));
+                                    }
+                                    if ui.small_button("←").on_hover_text("Copy to the left").clicked() {
+                                        copy = Some((diff.path.clone(), CopyDirection::ToSelected));
+                                    }
+                                });
+                            });
+                            ui.label(value_text(&diff.right));
+                            ui.end_row();
+                        }
+                    });
+            });
+        });
+    state.open = open;
+
+    if let Some((path, direction)) = copy {
+        let (from, to) = match direction {
+            CopyDirection::ToOther => (selected, state.other),
+            CopyDirection::ToSelected => (state.other, selected),
+        };
+        current_genome.record_edit(format!("Copy {}", path));
+        let source = current_genome.genome.modes[from].clone();
+        match copy_field(&source, &mut current_genome.genome.modes[to], &path) {
+            Ok(()) => info!("Copied {} from {} to {}", path, source.name, current_genome.genome.modes[to].name),
+            Err(err) => warn!("Could not copy {}: {}", path, err),
+        }
+    }
+}
+
+/// Short text for a compared value: floats at the display precision, vectors as tuples
+fn format_value(value: &Value, decimals: usize) -> String {
+    match value {
+        Value::Null => "—".to_string(),
+        Value::Number(number) if number.is_f64() => format!("{:.*}", decimals, number.as_f64().unwrap_or_default()),
+        Value::String(text) => text.clone(),
+        // Vectors and quaternions; keys come back sorted, so put them in x, y, z, w order
+        Value::Object(fields) => {
+            let parts: Vec<String> = ["x", "y", "z", "w"].iter()
+                .filter_map(|key| fields.get(*key))
+                .map(|field| format_value(field, decimals))
+                .collect();
+            format!("({})", parts.join(", "))
+        }
+        other => other.to_string(),
+    }
+}
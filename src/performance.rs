@@ -0,0 +1,314 @@
+use bevy::prelude::*;
+use bevy::window::{Monitor, PrimaryMonitor};
+use std::collections::VecDeque;
+
+use crate::preferences::Preferences;
+use crate::scene::DraggableSphere;
+
+/// Default number of spheres the stress test spawns
+const DEFAULT_STRESS_COUNT: usize = 100;
+/// Most spheres the stress test will spawn at once
+pub const MAX_STRESS_COUNT: usize = 10_000;
+/// Distance between neighbouring stress spheres, enough for the largest radius
+const STRESS_SPACING: f32 = 1.2;
+const STRESS_MIN_RADIUS: f32 = 0.2;
+const STRESS_MAX_RADIUS: f32 = 0.5;
+/// Distinct sphere sizes, each sharing one mesh
+const STRESS_RADIUS_STEPS: usize = 10;
+/// Frames kept for the frame-time statistics
+const FRAME_HISTORY: usize = 2000;
+/// Assumed refresh rate when the monitor doesn't report one
+const FALLBACK_REFRESH_HZ: f32 = 60.0;
+/// Fraction of the budget a frame may go over and still count as within it, so vsync jitter
+/// around the refresh interval doesn't read as an overrun
+const BUDGET_TOLERANCE: f32 = 0.1;
+/// Frames in a row over budget before it counts as an overrun
+const SUSTAINED_OVERRUN_FRAMES: u32 = 3;
+
+pub struct PerformancePlugin;
+
+impl Plugin for PerformancePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PerformanceMonitor>()
+            .add_systems(Update, (record_frame_time, apply_stress_test));
+    }
+}
+
+/// Spheres spawned by the stress test, so they can be cleared without touching the scene's own
+#[derive(Component)]
+pub struct StressSphere;
+
+pub enum StressTestAction {
+    Spawn(usize),
+    Clear,
+}
+
+/// Performance Monitor panel state
+#[derive(Resource)]
+pub struct PerformanceMonitor {
+    /// Number of spheres the next stress test spawns
+    pub stress_count: usize,
+    /// Set from the panel, carried out on the next update
+    pub stress_request: Option<StressTestAction>,
+    /// Stress spheres currently in the scene
+    pub stress_spawned: usize,
+    /// Recent frame times in milliseconds, oldest first
+    pub frame_times: VecDeque<f32>,
+    /// Time the primary monitor takes to refresh, in milliseconds
+    pub frame_budget_ms: f32,
+    /// App time in seconds as of the last recorded frame
+    pub elapsed_secs: f64,
+    /// App time in seconds of the last overrun: a few frames in a row over budget, or one stutter
+    pub last_over_budget: Option<f64>,
+    /// Frames in a row over budget so far
+    frames_over_budget: u32,
+}
+
+impl PerformanceMonitor {
+    pub fn frame_time_stats(&self) -> Option<FrameTimeStats> {
+        let mut sorted: Vec<f32> = self.frame_times.iter().copied().collect();
+        sorted.sort_by(f32::total_cmp);
+        Some(FrameTimeStats {
+            average_ms: sorted.iter().sum::<f32>() / sorted.len().max(1) as f32,
+            low_1_ms: percentile(&sorted, 99.0)?,
+            low_01_ms: percentile(&sorted, 99.9)?,
+        })
+    }
+
+    /// Note an overrun if the frame just recorded ends a sustained run over budget or stutters
+    fn track_overruns(&mut self, frame_ms: f32) {
+        let status = BudgetStatus::of(frame_ms, self.frame_budget_ms);
+        self.frames_over_budget = match status {
+            BudgetStatus::Within => 0,
+            BudgetStatus::Over | BudgetStatus::Stutter => self.frames_over_budget + 1,
+        };
+        if status == BudgetStatus::Stutter || self.frames_over_budget >= SUSTAINED_OVERRUN_FRAMES {
+            self.last_over_budget = Some(self.elapsed_secs);
+        }
+    }
+}
+
+/// Frame times over the rolling history, in milliseconds. The "lows" are the slow tail:
+/// 1% low is the time 99% of frames beat.
+pub struct FrameTimeStats {
+    pub average_ms: f32,
+    pub low_1_ms: f32,
+    pub low_01_ms: f32,
+}
+
+/// How a frame time compares with the refresh budget
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BudgetStatus {
+    /// Up to [`BUDGET_TOLERANCE`] over the budget
+    Within,
+    Over,
+    /// More than twice the budget, i.e. visibly dropped frames
+    Stutter,
+}
+
+impl BudgetStatus {
+    pub fn of(frame_ms: f32, budget_ms: f32) -> Self {
+        if frame_ms <= budget_ms * (1.0 + BUDGET_TOLERANCE) {
+            Self::Within
+        } else if frame_ms <= budget_ms * 2.0 {
+            Self::Over
+        } else {
+            Self::Stutter
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Within => "Within budget",
+            Self::Over => "Over budget",
+            Self::Stutter => "Stuttering",
+        }
+    }
+}
+
+/// Nearest-rank percentile `p` (0-100) of already sorted samples
+pub fn percentile(sorted: &[f32], p: f32) -> Option<f32> {
+    if sorted.is_empty() {
+        return None;
+    }
+    let rank = (p / 100.0 * sorted.len() as f32).ceil() as usize;
+    Some(sorted[rank.clamp(1, sorted.len()) - 1])
+}
+
+/// "just now", "12s ago", "3m ago", "2h ago"
+pub fn relative_time(seconds_ago: f64) -> String {
+    if seconds_ago < 1.0 {
+        "just now".to_string()
+    } else if seconds_ago < 60.0 {
+        format!("{:.0}s ago", seconds_ago.floor())
+    } else if seconds_ago < 3600.0 {
+        format!("{:.0}m ago", (seconds_ago / 60.0).floor())
+    } else {
+        format!("{:.0}h ago", (seconds_ago / 3600.0).floor())
+    }
+}
+
+impl Default for PerformanceMonitor {
+    fn default() -> Self {
+        Self {
+            stress_count: DEFAULT_STRESS_COUNT,
+            stress_request: None,
+            stress_spawned: 0,
+            frame_times: VecDeque::with_capacity(FRAME_HISTORY),
+            frame_budget_ms: 1000.0 / FALLBACK_REFRESH_HZ,
+            elapsed_secs: 0.0,
+            last_over_budget: None,
+            frames_over_budget: 0,
+        }
+    }
+}
+
+/// Centers of `count` spheres laid out in a square grid on the XZ plane around the origin
+pub fn stress_grid_positions(count: usize) -> Vec<Vec2> {
+    let side = (count as f32).sqrt().ceil() as usize;
+    let offset = (side.saturating_sub(1)) as f32 * STRESS_SPACING / 2.0;
+    (0..count)
+        .map(|i| Vec2::new((i % side) as f32 * STRESS_SPACING - offset, (i / side) as f32 * STRESS_SPACING - offset))
+        .collect()
+}
+
+/// Add the last frame to the rolling history and refresh the budget from the primary monitor
+fn record_frame_time(
+    time: Res<Time>,
+    monitors: Query<&Monitor, With<PrimaryMonitor>>,
+    mut monitor: ResMut<PerformanceMonitor>,
+) {
+    let frame_ms = time.delta_secs() * 1000.0;
+    // The first frame has no delta
+    if frame_ms <= 0.0 {
+        return;
+    }
+
+    let refresh_hz = monitors.single().ok()
+        .and_then(|primary| primary.refresh_rate_millihertz)
+        .filter(|&millihertz| millihertz > 0)
+        .map_or(FALLBACK_REFRESH_HZ, |millihertz| millihertz as f32 / 1000.0);
+    monitor.frame_budget_ms = 1000.0 / refresh_hz;
+
+    if monitor.frame_times.len() == FRAME_HISTORY {
+        monitor.frame_times.pop_front();
+    }
+    monitor.frame_times.push_back(frame_ms);
+    monitor.elapsed_secs = time.elapsed_secs_f64();
+    monitor.track_overruns(frame_ms);
+}
+
+/// Spawn or clear stress spheres. Sizes and colors vary across the grid so picking has
+/// overlapping, differently sized targets to choose between.
+fn apply_stress_test(
+    mut commands: Commands,
+    mut monitor: ResMut<PerformanceMonitor>,
+    stress_spheres: Query<Entity, With<StressSphere>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    preferences: Res<Preferences>,
+) {
+    let Some(action) = monitor.stress_request.take() else {
+        return;
+    };
+
+    for entity in stress_spheres.iter() {
+        commands.entity(entity).despawn();
+    }
+    monitor.stress_spawned = 0;
+
+    let StressTestAction::Spawn(count) = action else {
+        info!("Cleared stress test spheres");
+        return;
+    };
+    let count = count.min(MAX_STRESS_COUNT);
+    let sizes: Vec<(f32, Handle<Mesh>)> = (0..STRESS_RADIUS_STEPS)
+        .map(|step| {
+            let radius = STRESS_MIN_RADIUS + (STRESS_MAX_RADIUS - STRESS_MIN_RADIUS) * step as f32 / (STRESS_RADIUS_STEPS - 1) as f32;
+            (radius, meshes.add(Sphere::new(radius)))
+        })
+        .collect();
+    for (i, position) in stress_grid_positions(count).into_iter().enumerate() {
+        let t = i as f32 / count.max(1) as f32;
+        // Stride through the sizes so neighbours differ
+        let (radius, mesh) = sizes[(i * 7) % STRESS_RADIUS_STEPS].clone();
+        commands.spawn((
+            Mesh3d(mesh),
+            MeshMaterial3d(materials.add(StandardMaterial {
+                base_color: Color::hsl(t * 360.0, 0.7, 0.55),
+                ..default()
+            })),
+            Transform::from_xyz(position.x, preferences.ground_height + radius, position.y),
+            DraggableSphere { radius },
+            StressSphere,
+            Name::new(format!("Stress Sphere {}", i + 1)),
+        ));
+    }
+    monitor.stress_spawned = count;
+    info!("Spawned {} stress test spheres", count);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn percentiles_use_nearest_rank() {
+        let samples: Vec<f32> = (1..=1000).map(|i| i as f32).collect();
+        assert_eq!(percentile(&samples, 50.0), Some(500.0));
+        assert_eq!(percentile(&samples, 99.0), Some(990.0));
+        assert_eq!(percentile(&samples, 99.9), Some(999.0));
+        assert_eq!(percentile(&samples, 100.0), Some(1000.0));
+        assert_eq!(percentile(&samples, 0.0), Some(1.0));
+        // Too few samples for a 0.1% tail falls back to the slowest frame
+        assert_eq!(percentile(&samples[..10], 99.9), Some(10.0));
+        assert_eq!(percentile(&[], 99.0), None);
+    }
+
+    #[test]
+    fn budget_status_and_relative_time() {
+        assert_eq!(BudgetStatus::of(16.0, 16.7), BudgetStatus::Within);
+        // Jitter around the refresh interval
+        assert_eq!(BudgetStatus::of(17.5, 16.7), BudgetStatus::Within);
+        assert_eq!(BudgetStatus::of(20.0, 16.7), BudgetStatus::Over);
+        assert_eq!(BudgetStatus::of(40.0, 16.7), BudgetStatus::Stutter);
+        assert_eq!(relative_time(0.4), "just now");
+        assert_eq!(relative_time(12.7), "12s ago");
+        assert_eq!(relative_time(150.0), "2m ago");
+        assert_eq!(relative_time(7300.0), "2h ago");
+    }
+
+    #[test]
+    fn only_sustained_overruns_and_stutters_are_noted() {
+        let mut monitor = PerformanceMonitor { frame_budget_ms: 10.0, ..Default::default() };
+        let frame = |monitor: &mut PerformanceMonitor, frame_ms: f32| {
+            monitor.elapsed_secs += 1.0;
+            monitor.track_overruns(frame_ms);
+        };
+
+        // Single slow frames between good ones
+        for frame_ms in [10.5, 13.0, 9.0, 13.0, 13.0, 9.0] {
+            frame(&mut monitor, frame_ms);
+        }
+        assert_eq!(monitor.last_over_budget, None);
+
+        for _ in 0..SUSTAINED_OVERRUN_FRAMES {
+            frame(&mut monitor, 13.0);
+        }
+        assert_eq!(monitor.last_over_budget, Some(monitor.elapsed_secs));
+
+        frame(&mut monitor, 9.0);
+        frame(&mut monitor, 25.0);
+        assert_eq!(monitor.last_over_budget, Some(monitor.elapsed_secs));
+    }
+
+    #[test]
+    fn stress_grid_is_square_and_centered() {
+        let positions = stress_grid_positions(9);
+        assert_eq!(positions.len(), 9);
+        assert_eq!(positions[0], Vec2::new(-STRESS_SPACING, -STRESS_SPACING));
+        assert_eq!(positions[4], Vec2::ZERO);
+        assert_eq!(positions[8], Vec2::new(STRESS_SPACING, STRESS_SPACING));
+        assert!(stress_grid_positions(0).is_empty());
+    }
+}
@@ -0,0 +1,325 @@
+use bevy::prelude::*;
+use bevy_egui::EguiContext;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::fs;
+use std::ops::RangeInclusive;
+use std::time::Duration;
+
+use crate::dock::config_dir;
+use crate::genome_graph::GraphLayout;
+
+const PREFERENCES_FILE: &str = "preferences.ron";
+
+/// Shortest autosave interval allowed, to avoid hammering the disk
+pub const MIN_AUTOSAVE_SECS: f32 = 1.0;
+/// Longest autosave interval the Settings panel offers
+pub const MAX_AUTOSAVE_SECS: f32 = 600.0;
+
+/// Most previous genome autosaves that can be kept next to the latest one
+pub const MAX_AUTOSAVE_BACKUPS: usize = 20;
+
+/// Most decimal places the value fields can be set to show
+pub const MAX_DISPLAY_DECIMALS: usize = 6;
+
+/// Widths the dock splitters can be drawn at and grabbed by, in pixels
+pub const SEPARATOR_WIDTH_RANGE: RangeInclusive<f32> = 1.0..=8.0;
+pub const SEPARATOR_GRAB_WIDTH_RANGE: RangeInclusive<f32> = 0.0..=16.0;
+
+/// Spacings the drag snapping grid can be set to
+pub const DRAG_GRID_SPACING_RANGE: RangeInclusive<f32> = 0.05..=10.0;
+
+pub struct PreferencesPlugin;
+
+impl Plugin for PreferencesPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(Preferences::load())
+            .add_systems(Update, save_preferences_on_change);
+    }
+}
+
+/// User settings that persist across sessions
+#[derive(Resource, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Preferences {
+    /// Seconds between dock layout autosaves (0 = disabled)
+    pub dock_autosave_secs: f32,
+    /// Seconds between genome autosaves (0 = disabled)
+    pub genome_autosave_secs: f32,
+    /// Previous genome autosaves kept as numbered backups; older ones are deleted
+    pub genome_autosave_backups: usize,
+    /// Snapshots kept of the genome from before it was replaced or had modes removed (0 = disabled)
+    pub genome_safety_backups: usize,
+    /// Overlay in the viewport showing the selected mode and sim state
+    pub show_viewport_hud: bool,
+    pub viewport_hud_corner: HudCorner,
+    /// Drawn width of the dock splitters
+    pub separator_width: f32,
+    /// Extra grabbable width on each side of a dock splitter
+    pub separator_grab_width: f32,
+    /// Splitter color on hover (None = follow the theme)
+    pub separator_hover_color: Option<[u8; 3]>,
+    /// Reopen the window where it was last closed instead of maximized
+    pub remember_window_geometry: bool,
+    /// Decimal places shown by slider value fields and circular sliders (display only)
+    pub display_decimals: usize,
+    /// Keys of the collapsible panel sections the user has closed (sections start open)
+    pub collapsed_sections: BTreeSet<String>,
+    /// Height of the ground plane in the viewport
+    pub ground_height: f32,
+    /// Stop dragged spheres from sinking into the ground
+    pub clamp_drag_to_ground: bool,
+    /// Drop dragged spheres onto the nearest point of a grid with `drag_grid_spacing` between lines
+    pub drag_grid_snap: bool,
+    pub drag_grid_spacing: f32,
+    /// While dragging with grid snapping on, mark where the sphere will land
+    pub show_drag_snap_marker: bool,
+    /// Lat/lon step of the quaternion balls' grid snapping, one of `widgets::GRID_SNAP_DEGREES`
+    pub qball_snap_degrees: f32,
+    /// Ease a dropped sphere towards the selected mode's adhesion rest length from its nearest neighbour
+    pub settle_on_release: bool,
+    /// Draw springs between spheres dropped next to each other, styled by the selected mode's adhesion
+    pub show_adhesions: bool,
+    /// Auto-arrangement the Genome Graph uses
+    pub genome_graph_layout: GraphLayout,
+    /// Show rarely needed controls such as the twist constraint in the mode panels
+    pub show_advanced_parameters: bool,
+    /// Show and edit each quaternion ball's orientation as Euler angles below it
+    pub show_euler_angles: bool,
+    /// Mode panels edit a scratch copy that only reaches the genome on Apply
+    pub staged_editing: bool,
+}
+
+/// Which corner of the viewport the HUD is anchored to
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum HudCorner {
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+impl HudCorner {
+    pub const ALL: [HudCorner; 4] = [Self::TopLeft, Self::TopRight, Self::BottomLeft, Self::BottomRight];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::TopLeft => "Top Left",
+            Self::TopRight => "Top Right",
+            Self::BottomLeft => "Bottom Left",
+            Self::BottomRight => "Bottom Right",
+        }
+    }
+}
+
+impl Default for Preferences {
+    fn default() -> Self {
+        Self {
+            dock_autosave_secs: 2.0,
+            genome_autosave_secs: 30.0,
+            genome_autosave_backups: 3,
+            genome_safety_backups: 5,
+            show_viewport_hud: true,
+            viewport_hud_corner: HudCorner::TopLeft,
+            separator_width: 1.0,
+            separator_grab_width: 2.0,
+            separator_hover_color: None,
+            remember_window_geometry: true,
+            display_decimals: 2,
+            collapsed_sections: BTreeSet::new(),
+            ground_height: 0.0,
+            clamp_drag_to_ground: true,
+            drag_grid_snap: false,
+            drag_grid_spacing: 0.5,
+            show_drag_snap_marker: true,
+            qball_snap_degrees: crate::widgets::DEFAULT_GRID_SNAP_DEGREES,
+            settle_on_release: false,
+            show_adhesions: true,
+            genome_graph_layout: GraphLayout::default(),
+            show_advanced_parameters: false,
+            show_euler_angles: false,
+            staged_editing: false,
+        }
+    }
+}
+
+impl Preferences {
+    pub fn dock_autosave_interval(&self) -> Option<Duration> {
+        autosave_interval(self.dock_autosave_secs)
+    }
+
+    pub fn genome_autosave_interval(&self) -> Option<Duration> {
+        autosave_interval(self.genome_autosave_secs)
+    }
+
+    /// Backups to keep of the genome autosave, capped in case the file was edited by hand
+    pub fn genome_autosave_backups(&self) -> usize {
+        self.genome_autosave_backups.min(MAX_AUTOSAVE_BACKUPS)
+    }
+
+    /// Snapshots to keep from before destructive genome operations, capped like the autosave backups
+    pub fn genome_safety_backups(&self) -> usize {
+        self.genome_safety_backups.min(MAX_AUTOSAVE_BACKUPS)
+    }
+
+    /// Grid a released sphere snaps to, if snapping is on. A zero or negative spacing from a
+    /// hand-edited file counts as off.
+    pub fn drag_grid_spacing(&self) -> Option<f32> {
+        (self.drag_grid_snap && self.drag_grid_spacing > 0.0).then_some(self.drag_grid_spacing)
+    }
+
+    pub fn section_open(&self, key: &str) -> bool {
+        !self.collapsed_sections.contains(key)
+    }
+
+    pub fn set_section_open(&mut self, key: &str, open: bool) {
+        if open {
+            self.collapsed_sections.remove(key);
+        } else {
+            self.collapsed_sections.insert(key.to_string());
+        }
+    }
+
+    /// Preferences from a RON file, e.g. exported from another machine, with out-of-range values
+    /// clamped. Also returns the names of the clamped settings.
+    pub fn from_ron(data: &str) -> Result<(Self, Vec<&'static str>), ron::error::SpannedError> {
+        let mut preferences: Self = ron::from_str(data)?;
+        let clamped = preferences.clamp_to_valid_ranges();
+        Ok((preferences, clamped))
+    }
+
+    pub fn to_ron(&self) -> Result<String, ron::Error> {
+        ron::ser::to_string_pretty(self, Default::default())
+    }
+
+    /// Pull every setting into the range the Settings panel allows, returning the names of those
+    /// that were out of it. Unreadable numbers (NaN) go back to their defaults.
+    pub fn clamp_to_valid_ranges(&mut self) -> Vec<&'static str> {
+        let defaults = Self::default();
+        let mut clamped = Vec::new();
+        let mut check = |name: &'static str, changed: bool| {
+            if changed {
+                clamped.push(name);
+            }
+        };
+
+        check("dock autosave interval", clamp_autosave_secs(&mut self.dock_autosave_secs, defaults.dock_autosave_secs));
+        check("genome autosave interval", clamp_autosave_secs(&mut self.genome_autosave_secs, defaults.genome_autosave_secs));
+        check("genome backups", clamp_count(&mut self.genome_autosave_backups, MAX_AUTOSAVE_BACKUPS));
+        check("safety backups", clamp_count(&mut self.genome_safety_backups, MAX_AUTOSAVE_BACKUPS));
+        check("separator width", clamp_f32(&mut self.separator_width, SEPARATOR_WIDTH_RANGE, defaults.separator_width));
+        check("separator grab width", clamp_f32(&mut self.separator_grab_width, SEPARATOR_GRAB_WIDTH_RANGE, defaults.separator_grab_width));
+        check("decimal places", clamp_count(&mut self.display_decimals, MAX_DISPLAY_DECIMALS));
+        check("ground height", clamp_f32(&mut self.ground_height, f32::MIN..=f32::MAX, defaults.ground_height));
+        check("drag grid spacing", clamp_f32(&mut self.drag_grid_spacing, DRAG_GRID_SPACING_RANGE, defaults.drag_grid_spacing));
+
+        let steps = crate::widgets::GRID_SNAP_DEGREES;
+        if !steps.contains(&self.qball_snap_degrees) {
+            let nearest = steps.iter().copied()
+                .min_by(|a, b| (a - self.qball_snap_degrees).abs().total_cmp(&(b - self.qball_snap_degrees).abs()))
+                .filter(|_| self.qball_snap_degrees.is_finite())
+                .unwrap_or(defaults.qball_snap_degrees);
+            self.qball_snap_degrees = nearest;
+            clamped.push("quaternion ball snap step");
+        }
+        clamped
+    }
+
+    fn load() -> Self {
+        let path = config_dir().join(PREFERENCES_FILE);
+        let Ok(data) = fs::read_to_string(&path) else {
+            return Self::default();
+        };
+        ron::from_str(&data).unwrap_or_else(|err| {
+            warn!("Failed to parse {:?}, using default preferences: {}", path, err);
+            Self::default()
+        })
+    }
+
+    pub fn save(&self) {
+        let path = config_dir().join(PREFERENCES_FILE);
+        match self.to_ron() {
+            Ok(serialized) => {
+                if let Err(err) = fs::write(&path, serialized) {
+                    warn!("Failed to write {:?}: {}", path, err);
+                }
+            }
+            Err(err) => warn!("Failed to serialize preferences: {}", err),
+        }
+    }
+}
+
+/// 0 disables autosave, anything else is clamped to the minimum interval
+fn autosave_interval(secs: f32) -> Option<Duration> {
+    (secs > 0.0).then(|| Duration::from_secs_f32(secs.max(MIN_AUTOSAVE_SECS)))
+}
+
+/// Clamp `value` into `range`, or reset it to `fallback` if it isn't a number. Returns whether it changed.
+fn clamp_f32(value: &mut f32, range: RangeInclusive<f32>, fallback: f32) -> bool {
+    let valid = if value.is_nan() { fallback } else { value.clamp(*range.start(), *range.end()) };
+    // NaN never equals anything, so it always counts as changed
+    let changed = valid != *value;
+    *value = valid;
+    changed
+}
+
+fn clamp_count(value: &mut usize, max: usize) -> bool {
+    let changed = *value > max;
+    *value = (*value).min(max);
+    changed
+}
+
+/// Autosave intervals are 0 (off) or between the minimum and maximum
+fn clamp_autosave_secs(secs: &mut f32, fallback: f32) -> bool {
+    if *secs <= 0.0 {
+        let changed = *secs < 0.0;
+        *secs = 0.0;
+        return changed;
+    }
+    clamp_f32(secs, MIN_AUTOSAVE_SECS..=MAX_AUTOSAVE_SECS, fallback)
+}
+
+/// Write preferences whenever their value actually changes. While a slider or other widget
+/// is being dragged the change waits until it's released, so a drag writes the file once.
+fn save_preferences_on_change(
+    preferences: Res<Preferences>,
+    mut last_saved: Local<Option<Preferences>>,
+    mut egui_context: Query<&mut EguiContext>,
+) {
+    let dragging = egui_context.single_mut().is_ok_and(|mut ctx| ctx.get_mut().is_using_pointer());
+    match last_saved.as_ref() {
+        // First run - remember what was loaded without rewriting it
+        None => *last_saved = Some(preferences.clone()),
+        Some(saved) if saved != preferences.as_ref() && !dragging => {
+            preferences.save();
+            *last_saved = Some(preferences.clone());
+        }
+        Some(_) => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn imported_preferences_are_clamped_not_rejected() {
+        let data = "(genome_autosave_secs: 0.2, dock_autosave_secs: -5.0, genome_autosave_backups: 99, \
+            separator_width: 50.0, drag_grid_spacing: 0.5, qball_snap_degrees: 14.0, staged_editing: true)";
+
+        let (preferences, clamped) = Preferences::from_ron(data).unwrap();
+
+        assert_eq!(preferences.genome_autosave_secs, MIN_AUTOSAVE_SECS);
+        assert_eq!(preferences.dock_autosave_secs, 0.0);
+        assert_eq!(preferences.genome_autosave_backups, MAX_AUTOSAVE_BACKUPS);
+        assert_eq!(preferences.separator_width, *SEPARATOR_WIDTH_RANGE.end());
+        assert_eq!(preferences.qball_snap_degrees, 15.0);
+        // Valid settings come through untouched
+        assert_eq!(preferences.drag_grid_spacing, 0.5);
+        assert!(preferences.staged_editing);
+        assert_eq!(clamped.len(), 5);
+
+        let (round_trip, clamped) = Preferences::from_ron(&preferences.to_ron().unwrap()).unwrap();
+        assert!(round_trip == preferences && clamped.is_empty());
+    }
+}
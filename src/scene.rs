@@ -1,13 +1,24 @@
 use bevy::prelude::*;
 
+use crate::preferences::Preferences;
+
+const SPHERE_RADIUS: f32 = 0.5;
+
+#[derive(Component)]
+pub struct DraggableSphere {
+    pub radius: f32,
+}
+
+/// The ground plane, kept at `Preferences::ground_height`
 #[derive(Component)]
-pub struct DraggableSphere;
+pub struct Ground;
 
 pub struct ScenePlugin;
 
 impl Plugin for ScenePlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Startup, setup_scene);
+        app.add_systems(Startup, setup_scene)
+            .add_systems(Update, sync_ground_height);
     }
 }
 
@@ -45,13 +56,14 @@ fn setup_scene(
 
     // Add a draggable sphere
     commands.spawn((
-        Mesh3d(meshes.add(Sphere::new(0.5))),
+        Mesh3d(meshes.add(Sphere::new(SPHERE_RADIUS))),
         MeshMaterial3d(materials.add(StandardMaterial {
             base_color: Color::srgb(0.8, 0.3, 0.3),
             ..default()
         })),
         Transform::from_xyz(0.0, 1.0, 0.0),
-        DraggableSphere,
+        DraggableSphere { radius: SPHERE_RADIUS },
+        Name::new("Sphere"),
     ));
 
     // Add a ground plane for reference
@@ -62,6 +74,16 @@ fn setup_scene(
             ..default()
         })),
         Transform::from_xyz(0.0, 0.0, 0.0),
+        Ground,
     ));
 }
 
+fn sync_ground_height(preferences: Res<Preferences>, mut ground_query: Query<&mut Transform, With<Ground>>) {
+    if !preferences.is_changed() {
+        return;
+    }
+    for mut transform in ground_query.iter_mut() {
+        transform.translation.y = preferences.ground_height;
+    }
+}
+
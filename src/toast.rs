@@ -0,0 +1,91 @@
+use bevy::prelude::*;
+use bevy_egui::egui;
+use std::time::{Duration, Instant};
+
+const INFO_DURATION: Duration = Duration::from_secs(4);
+const ERROR_DURATION: Duration = Duration::from_secs(8);
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum ToastLevel {
+    Info,
+    Error,
+}
+
+struct Toast {
+    message: String,
+    level: ToastLevel,
+    created: Instant,
+}
+
+/// Short-lived notifications shown in the bottom-right corner
+#[derive(Resource, Default)]
+pub struct Toasts {
+    toasts: Vec<Toast>,
+}
+
+impl Toasts {
+    pub fn push(&mut self, level: ToastLevel, message: impl Into<String>) {
+        self.toasts.push(Toast {
+            message: message.into(),
+            level,
+            created: Instant::now(),
+        });
+    }
+
+    pub fn info(&mut self, message: impl Into<String>) {
+        self.push(ToastLevel::Info, message);
+    }
+
+    pub fn error(&mut self, message: impl Into<String>) {
+        self.push(ToastLevel::Error, message);
+    }
+
+    /// Draw active toasts stacked upwards from the bottom-right corner and drop expired ones
+    pub fn show(&mut self, ctx: &egui::Context) {
+        self.toasts.retain(|toast| {
+            let duration = match toast.level {
+                ToastLevel::Info => INFO_DURATION,
+                ToastLevel::Error => ERROR_DURATION,
+            };
+            toast.created.elapsed() < duration
+        });
+
+        if self.toasts.is_empty() {
+            return;
+        }
+
+        let mut dismissed = None;
+        egui::Area::new(egui::Id::new("toasts"))
+            .anchor(egui::Align2::RIGHT_BOTTOM, egui::vec2(-10.0, -10.0))
+            .order(egui::Order::Foreground)
+            .interactable(true)
+            .show(ctx, |ui| {
+                ui.with_layout(egui::Layout::bottom_up(egui::Align::Max), |ui| {
+                    for (i, toast) in self.toasts.iter().enumerate().rev() {
+                        let color = match toast.level {
+                            ToastLevel::Info => ui.visuals().text_color(),
+                            ToastLevel::Error => ui.visuals().error_fg_color,
+                        };
+                        let response = egui::Frame::popup(ui.style())
+                            .show(ui, |ui| {
+                                ui.set_max_width(320.0);
+                                ui.colored_label(color, &toast.message);
+                            })
+                            .response
+                            .interact(egui::Sense::click())
+                            .on_hover_text("Click to dismiss");
+                        if response.clicked() {
+                            dismissed = Some(i);
+                        }
+                    }
+                });
+            });
+
+        if let Some(i) = dismissed {
+            self.toasts.remove(i);
+        }
+
+        // Keep repainting so toasts expire even when nothing else changes
+        ctx.request_repaint_after(Duration::from_millis(250));
+    }
+}
@@ -1,10 +1,37 @@
 use bevy::prelude::*;
 use bevy_egui::{egui, EguiContext};
 use egui_dock::{DockArea, Style};
+use egui_dock::tab_viewer::OnCloseResponse;
+use std::collections::HashMap;
 
 use crate::dock::*;
-use crate::widgets;
-use crate::genome::{CurrentGenome, ModeSettings};
+use crate::widgets::{self, Detail};
+use crate::toast::Toasts;
+use crate::asset_browser::AssetBrowser;
+use crate::preferences::{
+    HudCorner, Preferences, DRAG_GRID_SPACING_RANGE, MAX_AUTOSAVE_BACKUPS, MAX_AUTOSAVE_SECS, MAX_DISPLAY_DECIMALS, MIN_AUTOSAVE_SECS,
+    SEPARATOR_GRAB_WIDTH_RANGE, SEPARATOR_WIDTH_RANGE,
+};
+use crate::drag::DragState;
+use crate::genome_graph::{show_genome_graph, GenomeGraphState};
+use crate::genome_json::{show_genome_json, GenomeJsonState};
+use crate::mode_card::{self, ModeCardExport};
+use crate::help::{show_help, HelpState};
+use crate::mode_compare::{show_mode_compare, ModeCompareState};
+use crate::workspace::{show_import_workspace_dialog, WorkspaceRequest, WorkspaceState};
+use crate::performance::{relative_time, BudgetStatus, PerformanceMonitor, StressTestAction, MAX_STRESS_COUNT};
+use crate::file_dialog::{FileDialogs, FileRequest, FileTarget, PickKind};
+use crate::genome::{
+    clean_name, damping_ratio, ChildSettings, CurrentGenome, GenomeArchiveState, GenomeLibrary, ModeSettings, SplitDirectionPreset, SpringHealth,
+    CELL_TYPE_NAMES, HEALTHY_DAMPING_RATIO, SPLIT_INTERVAL_MAX, SPLIT_INTERVAL_MIN, UNTITLED_GENOME_NAME,
+};
+use crate::genome::diff::{copy_field, diff_fields, FieldDiff};
+use crate::genome::palette::RecolorStrategy;
+use crate::genome::query::{search_genomes, ModeQuery};
+use crate::genome::random;
+use crate::genome::ranges;
+use crate::genome::backups::{BackupEntry, Backups};
+use crate::genome::templates::{GenomeTemplate, TEMPLATES};
 
 #[derive(Resource, Default)]
 pub struct ViewportRect {
@@ -27,6 +54,8 @@ pub struct GlobalUiState {
     pub show_theme_editor: bool,
     pub show_camera_settings: bool,
     pub show_lighting_settings: bool,
+    // Debug overlays (only reachable from the Debug menu in debug builds)
+    pub show_viewport_rect_debug: bool,
 }
 
 impl Default for GlobalUiState {
@@ -43,6 +72,7 @@ impl Default for GlobalUiState {
             show_theme_editor: false,
             show_camera_settings: false,
             show_lighting_settings: false,
+            show_viewport_rect_debug: false,
         }
     }
 }
@@ -56,16 +86,130 @@ pub struct WidgetDemoState {
     pub copy_into_dialog_open: bool,
     pub copy_into_source: usize,
     pub color_picker_state: Option<(usize, egui::ecolor::Hsva)>,
+    pub mode_scroll_accumulator: f32,
+    /// Text size of the Modes list relative to the rest of the UI, set with Ctrl+wheel over it
+    pub modes_text_scale: f32,
+    // Scale Split Intervals dialog
+    pub scale_intervals_open: bool,
+    /// Whether "Auto-Name Modes" is asking before it replaces custom names
+    pub auto_name_confirm_open: bool,
+    /// "Append Modes from File" merges appended modes into existing modes they duplicate
+    pub append_merge_duplicates: bool,
+    /// Template or backup waiting on confirmation to replace a genome with unsaved changes
+    pub replacement_confirm: Option<GenomeReplacement>,
+    /// Snapshots listed in "Restore from Backup", read from disk while the submenu is open
+    pub backup_entries: Option<Vec<BackupEntry>>,
+    /// "Find Duplicate Modes" dialog, and whether it counts modes differing only in color as duplicates
+    pub duplicate_modes_open: bool,
+    pub duplicate_modes_ignore_color: bool,
+    pub scale_intervals_factor: f32,
+    pub scale_intervals_minimums: bool,
     // UI state for quaternion balls
     pub qball_snapping: bool,
+    /// Snap the child's X axis to a regular direction set on release (overrides the angle grid)
+    pub qball_direction_snap: Option<widgets::DirectionSet>,
+    /// Child whose target mode the QuaternionBall panel's [ / ] keys step through,
+    /// and that orientations from other modes are copied into
+    pub qball_focused_child: ChildSlot,
+    /// Mode and child an orientation is copied or mirrored from
+    pub orientation_source: (usize, ChildSlot),
+    pub qball_look_at: bool,
     pub qball1_locked_axis: i32,
     pub qball1_initial_distance: f32,
     pub qball2_locked_axis: i32,
     pub qball2_initial_distance: f32,
+    /// Axis locks set from the keyboard; unlike `qballN_locked_axis` these outlive a drag
+    pub qball1_axis_lock: i32,
+    pub qball2_axis_lock: i32,
+    pub mirror_plane: widgets::MirrorPlane,
     // UI state for circular sliders
     pub enable_snapping: bool,
+    /// Circular sliders mark zero and color their arc by the value's sign
+    pub dial_center_zero: bool,
+    pub split_link: SplitDirectionLink,
+    /// Yaw minus pitch, used by `SplitDirectionLink::Offset`
+    pub split_link_offset: f32,
+    pub genome_graph: GenomeGraphState,
+    pub genome_json: GenomeJsonState,
+    pub mode_compare: ModeCompareState,
+    /// Modes selected lately, for the quick-switch dropdown at the top of the mode panels
+    pub recent_modes: widgets::RecentModes,
+    pub help: HelpState,
+    pub mode_card: ModeCardExport,
+    pub modes_layout: widgets::ModesLayout,
     // Time slider
     pub time_value: f32,
+    // Library search filters
+    pub library_query: ModeQuery,
+    /// Which modes the Modes panel lists
+    pub modes_filter: ModeQuery,
+    /// Scratch copies the mode panels edit while staged editing is on, by mode index
+    pub staged_modes: HashMap<usize, StagedMode>,
+}
+
+/// A mode's pending edits under staged editing
+pub struct StagedMode {
+    /// The mode as it was in the genome when staging started
+    base: ModeSettings,
+    /// The copy the panels edit
+    edited: ModeSettings,
+}
+
+impl StagedMode {
+    fn new(mode: &ModeSettings) -> Self {
+        Self { base: mode.clone(), edited: mode.clone() }
+    }
+
+    /// Paths of the fields edited so far, e.g. `adhesion_settings.rest_length`
+    fn changed_fields(&self) -> Vec<String> {
+        changed_fields(&self.base, &self.edited)
+    }
+
+    /// Start over from `mode`, which changed outside the panel, keeping the staged edits to
+    /// fields that change left alone. Returns the fields whose staged edits were dropped.
+    fn rebase(&mut self, mode: &ModeSettings) -> Vec<String> {
+        let outside = changed_fields(&self.base, mode);
+        let mut rebased = StagedMode::new(mode);
+        let mut dropped = Vec::new();
+        for path in self.changed_fields() {
+            if outside.contains(&path) || copy_field(&self.edited, &mut rebased.edited, &path).is_err() {
+                dropped.push(path);
+            }
+        }
+        *self = rebased;
+        dropped
+    }
+}
+
+/// Paths of the fields that differ between `before` and `after`
+fn changed_fields(before: &ModeSettings, after: &ModeSettings) -> Vec<String> {
+    diff_fields(before, after)
+        .map(|diffs| diffs.into_iter().filter(FieldDiff::differs).map(|diff| diff.path).collect())
+        .unwrap_or_default()
+}
+
+/// Readable name for a field path, e.g. `adhesion_settings.rest_length` -> `Rest Length`
+fn field_label(path: &str) -> String {
+    let field = path.rsplit('.').next().unwrap_or(path);
+    field
+        .split('_')
+        .map(|word| {
+            let mut chars = word.chars();
+            chars.next().map(|first| first.to_uppercase().chain(chars).collect::<String>()).unwrap_or_default()
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+impl WidgetDemoState {
+    /// Snapping for the quaternion balls; a direction set takes over from the angle grid
+    fn qball_snap(&self) -> widgets::OrientationSnap {
+        match self.qball_direction_snap {
+            Some(set) => widgets::OrientationSnap::Directions(set),
+            None if self.qball_snapping => widgets::OrientationSnap::Grid,
+            None => widgets::OrientationSnap::Off,
+        }
+    }
 }
 
 impl Default for WidgetDemoState {
@@ -76,24 +220,255 @@ impl Default for WidgetDemoState {
             copy_into_dialog_open: false,
             copy_into_source: 0,
             color_picker_state: None,
+            mode_scroll_accumulator: 0.0,
+            modes_text_scale: 1.0,
+            scale_intervals_open: false,
+            auto_name_confirm_open: false,
+            append_merge_duplicates: true,
+            replacement_confirm: None,
+            backup_entries: None,
+            duplicate_modes_open: false,
+            duplicate_modes_ignore_color: false,
+            scale_intervals_factor: 1.0,
+            scale_intervals_minimums: true,
             qball_snapping: true,
+            qball_direction_snap: None,
+            qball_focused_child: ChildSlot::default(),
+            orientation_source: (0, ChildSlot::A),
+            qball_look_at: false,
             qball1_locked_axis: -1,
             qball1_initial_distance: 0.0,
             qball2_locked_axis: -1,
             qball2_initial_distance: 0.0,
+            qball1_axis_lock: -1,
+            qball2_axis_lock: -1,
+            mirror_plane: widgets::MirrorPlane::XZ,
             enable_snapping: true,
+            dial_center_zero: false,
+            split_link: SplitDirectionLink::Off,
+            split_link_offset: 0.0,
+            genome_graph: GenomeGraphState::default(),
+            genome_json: GenomeJsonState::default(),
+            mode_compare: ModeCompareState::default(),
+            recent_modes: widgets::RecentModes::default(),
+            help: HelpState::default(),
+            mode_card: ModeCardExport::default(),
+            modes_layout: widgets::ModesLayout::default(),
             time_value: 0.0,
+            library_query: ModeQuery::default(),
+            modes_filter: ModeQuery::default(),
+            staged_modes: HashMap::new(),
+        }
+    }
+}
+
+/// One of a mode's two children
+#[derive(Clone, Copy, PartialEq, Eq, Default)]
+pub enum ChildSlot {
+    #[default]
+    A,
+    B,
+}
+
+impl ChildSlot {
+    pub const ALL: [ChildSlot; 2] = [Self::A, Self::B];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::A => "Child A",
+            Self::B => "Child B",
+        }
+    }
+
+    pub fn of(self, mode: &ModeSettings) -> &ChildSettings {
+        match self {
+            Self::A => &mode.child_a,
+            Self::B => &mode.child_b,
         }
     }
+
+    pub fn of_mut(self, mode: &mut ModeSettings) -> &mut ChildSettings {
+        match self {
+            Self::A => &mut mode.child_a,
+            Self::B => &mut mode.child_b,
+        }
+    }
+}
+
+/// How the pitch and yaw sliders of the split direction follow each other
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum SplitDirectionLink {
+    Off,
+    /// Yaw is the negative of pitch
+    Mirror,
+    Equal,
+    /// Yaw stays a fixed number of degrees from pitch
+    Offset,
+}
+
+impl SplitDirectionLink {
+    pub const ALL: [SplitDirectionLink; 4] = [Self::Off, Self::Mirror, Self::Equal, Self::Offset];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Off => "Unlinked",
+            Self::Mirror => "Mirror",
+            Self::Equal => "Equal",
+            Self::Offset => "Fixed Offset",
+        }
+    }
+
+    /// Yaw that goes with `pitch`, or None when unlinked
+    fn yaw_for(self, pitch: f32, offset: f32) -> Option<f32> {
+        match self {
+            Self::Off => None,
+            Self::Mirror => Some(-pitch),
+            Self::Equal => Some(pitch),
+            Self::Offset => Some(wrap_degrees(pitch + offset)),
+        }
+    }
+
+    /// Pitch that goes with `yaw`, or None when unlinked
+    fn pitch_for(self, yaw: f32, offset: f32) -> Option<f32> {
+        match self {
+            Self::Off => None,
+            Self::Mirror => Some(-yaw),
+            Self::Equal => Some(yaw),
+            Self::Offset => Some(wrap_degrees(yaw - offset)),
+        }
+    }
+}
+
+/// Wrap an angle into the sliders' -180..=180 range
+fn wrap_degrees(degrees: f32) -> f32 {
+    let wrapped = (degrees + 180.0).rem_euclid(360.0) - 180.0;
+    // rem_euclid maps +180 to -180; keep +180 where it was asked for
+    if wrapped == -180.0 && degrees > 0.0 { 180.0 } else { wrapped }
+}
+
+/// A quaternion ball widget is this many radii wide and tall
+const QBALL_CONTAINER_SCALE: f32 = 2.5;
+const QBALL_MIN_RADIUS: f32 = 32.5;
+const QBALL_MAX_RADIUS: f32 = 130.0;
+/// Height of the label, axis lock, checkbox and mode dropdown around each ball
+const QBALL_CONTROLS_HEIGHT: f32 = 110.0;
+/// Height of the rows below the balls (mirror controls)
+const QBALL_FOOTER_HEIGHT: f32 = 40.0;
+
+/// Radius for both quaternion balls in a panel area of `available` size, and whether to stack them
+/// vertically. Whichever arrangement gives the larger balls wins.
+fn quaternion_ball_layout(available: egui::Vec2) -> (f32, bool) {
+    let height = available.y - QBALL_FOOTER_HEIGHT;
+    let side_by_side = ((available.x - 30.0) / 2.0).min(height - QBALL_CONTROLS_HEIGHT) / QBALL_CONTAINER_SCALE;
+    let stacked = (available.x - 10.0).min(height / 2.0 - QBALL_CONTROLS_HEIGHT) / QBALL_CONTAINER_SCALE;
+
+    let stack = stacked > side_by_side;
+    let radius = if stack { stacked } else { side_by_side };
+    (radius.clamp(QBALL_MIN_RADIUS, QBALL_MAX_RADIUS), stack)
+}
+
+/// Preferred radius of the Parent Split Angle dials
+const SPLIT_DIAL_RADIUS: f32 = 29.25;
+/// Smallest radius that still fits the value box in the middle of a dial
+const SPLIT_DIAL_MIN_RADIUS: f32 = 24.0;
+/// A circular slider is this much wider than its diameter
+const SPLIT_DIAL_PADDING: f32 = 20.0;
+
+/// Radius for the pitch and yaw dials in a panel `available_width` wide, and whether to stack them.
+/// They stay side by side while both fit at the preferred radius; stacked dials shrink to the panel width.
+fn split_dial_layout(available_width: f32, item_spacing: f32) -> (f32, bool) {
+    let indent = 10.0;
+    let dial_width = |radius: f32| radius * 2.0 + SPLIT_DIAL_PADDING;
+    if indent + dial_width(SPLIT_DIAL_RADIUS) * 2.0 + item_spacing * 2.0 <= available_width {
+        return (SPLIT_DIAL_RADIUS, false);
+    }
+    let radius = (available_width - SPLIT_DIAL_PADDING) / 2.0;
+    (radius.clamp(SPLIT_DIAL_MIN_RADIUS, SPLIT_DIAL_RADIUS), true)
+}
+
+/// (preferences key, heading) of the collapsible sections in the Parent Settings panel
+const PARENT_SECTIONS: [(&str, &str); 3] = [
+    ("parent.splitting", "Splitting"),
+    ("parent.nutrients", "Nutrients"),
+    ("parent.connections", "Connections"),
+];
+
+/// (preferences key, heading) of the collapsible sections in the Adhesion Settings panel
+const ADHESION_SECTIONS: [(&str, &str); 3] = [
+    ("adhesion.breaking", "Breaking"),
+    ("adhesion.springs", "Springs"),
+    ("adhesion.twist", "Twist Constraint"),
+];
+
+/// Collapsible section whose open state is remembered in preferences
+fn settings_section(
+    ui: &mut egui::Ui,
+    preferences: &mut Preferences,
+    (key, heading): (&str, &str),
+    add_contents: impl FnOnce(&mut egui::Ui),
+) {
+    let open = preferences.section_open(key);
+    let response = egui::CollapsingHeader::new(heading)
+        .id_salt(key)
+        .open(Some(open))
+        .show(ui, add_contents);
+    if response.header_response.clicked() {
+        preferences.set_section_open(key, !open);
+    }
+}
+
+/// Warning icon for a stiffness/damping pair whose damping ratio is outside the healthy band.
+/// The mode's split mass stands in for the mass the spring moves, so this is only a rough guide.
+fn spring_health_warning(ui: &mut egui::Ui, stiffness: f32, damping: f32, mass: f32) {
+    let ratio = damping_ratio(stiffness, damping, mass);
+    let (text, hint) = match SpringHealth::classify(ratio) {
+        SpringHealth::Healthy => return,
+        SpringHealth::UnderDamped => ("⚠ Under-damped", "Under-damped: may oscillate. Raise the damping or lower the stiffness."),
+        SpringHealth::OverDamped => ("⚠ Over-damped", "Over-damped: sluggish. Lower the damping or raise the stiffness."),
+    };
+    ui.colored_label(egui::Color32::YELLOW, text).on_hover_text(format!(
+        "{}\nDamping ratio ≈ {:.2} (healthy {:.1} to {:.1})",
+        hint,
+        ratio,
+        HEALTHY_DAMPING_RATIO.start(),
+        HEALTHY_DAMPING_RATIO.end(),
+    ));
 }
 
+/// "Collapse All" / "Expand All" buttons for a panel's sections
+fn section_toggle_buttons(ui: &mut egui::Ui, preferences: &mut Preferences, sections: &[(&str, &str)]) {
+    ui.horizontal(|ui| {
+        if ui.small_button("Collapse All").clicked() {
+            for (key, _) in sections {
+                preferences.set_section_open(key, false);
+            }
+        }
+        if ui.small_button("Expand All").clicked() {
+            for (key, _) in sections {
+                preferences.set_section_open(key, true);
+            }
+        }
+    });
+}
+
+#[allow(clippy::too_many_arguments)]
 pub fn ui_system(
     mut contexts: Query<&mut EguiContext>,
     mut dock_resource: ResMut<DockResource>,
     mut viewport_rect: ResMut<ViewportRect>,
     mut current_genome: ResMut<CurrentGenome>,
     mut widget_demo_state: ResMut<WidgetDemoState>,
-    global_ui_state: Res<GlobalUiState>,
+    mut global_ui_state: ResMut<GlobalUiState>,
+    mut archive_state: ResMut<GenomeArchiveState>,
+    genome_library: Res<GenomeLibrary>,
+    mut toasts: ResMut<Toasts>,
+    mut asset_browser: ResMut<AssetBrowser>,
+    mut preferences: ResMut<Preferences>,
+    drag_state: Res<DragState>,
+    names: Query<&Name>,
+    mut file_dialogs: ResMut<FileDialogs>,
+    mut workspace: ResMut<WorkspaceState>,
+    mut performance_monitor: ResMut<PerformanceMonitor>,
 ) {
     for mut egui_context in contexts.iter_mut() {
         let ctx = egui_context.get_mut();
@@ -105,6 +480,25 @@ pub fn ui_system(
             style.spacing.scroll.bar_inner_margin = 0.0;  // Content sits close to scrollbar
             style.spacing.scroll.floating_allocated_width = 0.0;  // No allocated space for floating bars
         });
+        widgets::set_display_decimals(ctx, preferences.display_decimals.min(MAX_DISPLAY_DECIMALS));
+        widgets::set_show_advanced(ctx, preferences.show_advanced_parameters);
+        // Backstop for anything that shrank the genome without going through CurrentGenome
+        current_genome.clamp_selection();
+        let mode_count = current_genome.genome.modes.len();
+        // Reading the queue isn't an edit, so don't wake the systems watching the genome
+        for remap in current_genome.bypass_change_detection().take_mode_remaps() {
+            dock_resource.remap_pinned_modes(&remap);
+            widget_demo_state.recent_modes.remap(&remap, mode_count);
+            // Staged edits follow their mode; a removed mode's go with it
+            widget_demo_state.staged_modes = std::mem::take(&mut widget_demo_state.staged_modes)
+                .into_iter()
+                .filter_map(|(idx, staged)| Some((remap.get(idx).copied().flatten()?, staged)))
+                .collect();
+        }
+        dock_resource.unpin_missing_modes(mode_count);
+        widget_demo_state.recent_modes.visit(current_genome.selected_mode_index as usize, mode_count);
+        // A zero step from a hand-edited preferences file would make the snap grid infinitely fine
+        widgets::set_grid_snap_degrees(ctx, preferences.qball_snap_degrees.max(widgets::GRID_SNAP_DEGREES[0]));
 
         // Clear viewport rect at the start of each frame
         viewport_rect.rect = None;
@@ -112,17 +506,147 @@ pub fn ui_system(
         // Show menu bar at the top
         egui::TopBottomPanel::top("menu_bar").show(ctx, |ui| {
             egui::MenuBar::new().ui(ui, |ui| {
+                ui.menu_button("Edit", |ui| {
+                    show_edit_menu(ui, &mut current_genome, &mut toasts);
+                });
+                ui.menu_button("Genome", |ui| {
+                    // Also here so saving and loading don't depend on the Name & Type panel being open
+                    if ui.button("Save Genome...").clicked() {
+                        request_genome_save(&mut file_dialogs, &current_genome.genome.name);
+                        ui.close();
+                    }
+                    if ui.button("Load Genome...").clicked() {
+                        request_genome_load(&mut file_dialogs);
+                        ui.close();
+                    }
+                    if ui.button("Append Modes from File...").on_hover_text("Add another genome's modes after this genome's").clicked() {
+                        request_append_modes(&mut file_dialogs, widget_demo_state.append_merge_duplicates);
+                        ui.close();
+                    }
+                    ui.checkbox(&mut widget_demo_state.append_merge_duplicates, "Merge duplicates when appending")
+                        .on_hover_text("Appended modes that only differ by name from an existing mode are merged into it");
+                    ui.menu_button("Templates", |ui| {
+                        for (i, template) in TEMPLATES.iter().enumerate() {
+                            if ui.button(template.name).on_hover_text(template.description).clicked() {
+                                replace_genome(&mut current_genome, &mut widget_demo_state, &mut toasts, GenomeReplacement::Template(i));
+                                ui.close();
+                            }
+                        }
+                    });
+                    let restore_menu = ui.menu_button("Restore from Backup", |ui| {
+                        let entries = widget_demo_state.backup_entries.get_or_insert_with(|| Backups::default().list());
+                        if entries.is_empty() {
+                            ui.weak("No backups yet");
+                        }
+                        let mut picked = None;
+                        for entry in entries.iter() {
+                            if ui.button(entry.label()).clicked() {
+                                picked = Some(entry.clone());
+                            }
+                        }
+                        if let Some(entry) = picked {
+                            replace_genome(&mut current_genome, &mut widget_demo_state, &mut toasts, GenomeReplacement::Backup(entry));
+                            ui.close();
+                        }
+                    });
+                    restore_menu.response.on_hover_text("Snapshots taken before the genome was replaced or had modes removed");
+                    if restore_menu.inner.is_none() {
+                        widget_demo_state.backup_entries = None;
+                    }
+                    ui.separator();
+                    show_genome_menu(ui, &mut archive_state, &genome_library, &mut file_dialogs);
+                    ui.separator();
+                    if ui.button("Scale Split Intervals...").clicked() {
+                        widget_demo_state.scale_intervals_open = true;
+                        ui.close();
+                    }
+                    if ui.button("Auto-Name Modes...").on_hover_text("Name every mode after its cell type and index").clicked() {
+                        if current_genome.genome.custom_named_modes() > 0 {
+                            widget_demo_state.auto_name_confirm_open = true;
+                        } else {
+                            auto_name_modes(&mut current_genome, &mut toasts);
+                        }
+                        ui.close();
+                    }
+                    if ui.button("Find Duplicate Modes...").on_hover_text("Find modes that only differ by name and merge them").clicked() {
+                        widget_demo_state.duplicate_modes_open = true;
+                        ui.close();
+                    }
+                    if ui.button("Paste Genome from Clipboard").on_hover_text("Replace the genome with JSON from the clipboard").clicked() {
+                        widget_demo_state.genome_json.paste_requested = true;
+                        ui.close();
+                    }
+                    ui.separator();
+                    show_workspace_menu(ui, &workspace, &mut file_dialogs);
+                    #[cfg(feature = "opener")]
+                    {
+                        ui.separator();
+                        show_file_manager_menu(ui, &current_genome, &mut toasts);
+                    }
+                });
                 ui.menu_button("Windows", |ui| {
-                    show_windows_menu(ui, &mut dock_resource, &global_ui_state);
+                    show_windows_menu(ui, &mut dock_resource, &global_ui_state, &mut toasts);
+                });
+                if cfg!(debug_assertions) {
+                    ui.menu_button("Debug", |ui| {
+                        ui.checkbox(&mut global_ui_state.show_viewport_rect_debug, "Show Viewport Rect");
+                    });
+                }
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    let help = &mut widget_demo_state.help.open;
+                    if ui.selectable_label(*help, " ? ").on_hover_text("Help: the genome model and keyboard shortcuts").clicked() {
+                        *help = !*help;
+                    }
                 });
             });
         });
 
+        // Undo/redo hotkeys, left to text fields while one has focus
+        if !ctx.wants_keyboard_input() {
+            let undo_shortcut = egui::KeyboardShortcut::new(egui::Modifiers::COMMAND, egui::Key::Z);
+            let redo_shortcut = egui::KeyboardShortcut::new(egui::Modifiers::COMMAND | egui::Modifiers::SHIFT, egui::Key::Z);
+            // Check the more specific shortcut first so Ctrl+Shift+Z isn't taken as Ctrl+Z
+            if ctx.input_mut(|i| i.consume_shortcut(&redo_shortcut)) {
+                redo_genome_edit(&mut current_genome, &mut toasts);
+            } else if ctx.input_mut(|i| i.consume_shortcut(&undo_shortcut)) {
+                undo_genome_edit(&mut current_genome, &mut toasts);
+            }
+        }
+
+        // Solo hotkey: show only the focused panel and the viewport, press again to restore
+        let solo_shortcut = egui::KeyboardShortcut::new(egui::Modifiers::COMMAND | egui::Modifiers::SHIFT, egui::Key::F);
+        if !ctx.wants_keyboard_input() && ctx.input_mut(|i| i.consume_shortcut(&solo_shortcut)) {
+            solo_focused_panel(&mut dock_resource);
+        }
+
+        // Duplicate hotkey: a second copy of the focused panel beside it
+        let duplicate_shortcut = egui::KeyboardShortcut::new(egui::Modifiers::COMMAND | egui::Modifiers::SHIFT, egui::Key::D);
+        if !ctx.wants_keyboard_input() && ctx.input_mut(|i| i.consume_shortcut(&duplicate_shortcut)) {
+            duplicate_focused_panel(&mut dock_resource);
+        }
+
+        // Reopen hotkey: bring back the most recently closed panel
+        let reopen_shortcut = egui::KeyboardShortcut::new(egui::Modifiers::COMMAND | egui::Modifiers::SHIFT, egui::Key::T);
+        if !ctx.wants_keyboard_input() && ctx.input_mut(|i| i.consume_shortcut(&reopen_shortcut)) {
+            dock_resource.reopen_closed();
+        }
+
+        let mut solo_request = None;
+        let mut close_request = None;
+        let mut closed_tabs = Vec::new();
+        let soloed = dock_resource.solo_panel().cloned();
+        let last_essential_panels = dock_resource.last_essential_panels();
+
         // Show dock area in remaining space (only if not hidden)
         if !dock_resource.all_hidden {
             let mut style = Style::from_egui(ctx.style().as_ref());
             // Reduce separator minimum constraint to allow smaller panels
             style.separator.extra = 75.0; // Reduced from default 175.0
+            style.separator.width = preferences.separator_width;
+            style.separator.extra_interact_width = preferences.separator_grab_width;
+            if let Some([r, g, b]) = preferences.separator_hover_color {
+                style.separator.color_hovered = egui::Color32::from_rgb(r, g, b);
+            }
             
             DockArea::new(&mut dock_resource.tree)
                 .style(style)
@@ -132,29 +656,978 @@ pub fn ui_system(
                     viewport_rect: &mut viewport_rect,
                     current_genome: &mut current_genome,
                     widget_demo_state: &mut widget_demo_state,
+                    genome_library: &genome_library,
+                    toasts: &mut toasts,
+                    asset_browser: &mut asset_browser,
+                    preferences: &mut preferences,
+                    file_dialogs: &mut file_dialogs,
+                    performance_monitor: &mut performance_monitor,
+                    soloed,
+                    solo_request: &mut solo_request,
+                    last_essential_panels,
+                    close_request: &mut close_request,
+                    closed_tabs: &mut closed_tabs,
                 });
         } else {
             // When hidden, set viewport to entire available screen area
             viewport_rect.rect = Some(ctx.available_rect());
         }
+
+        if let Some(panel) = solo_request {
+            dock_resource.toggle_solo(panel);
+        }
+        if close_request.is_some() {
+            dock_resource.confirm_close = close_request;
+        }
+        for tab in &closed_tabs {
+            dock_resource.close_tab(tab);
+        }
+
+        if preferences.show_viewport_hud
+            && let Some(rect) = viewport_rect.rect
+        {
+            let dragged_name = drag_state.dragging.map(|entity| {
+                names.get(entity).map_or_else(|_| format!("{}", entity), |name| name.to_string())
+            });
+            draw_viewport_hud(
+                ctx,
+                rect,
+                preferences.viewport_hud_corner,
+                &current_genome,
+                widget_demo_state.time_value,
+                dragged_name.as_deref(),
+            );
+        }
+
+        if widget_demo_state.scale_intervals_open {
+            show_scale_intervals_dialog(ctx, &mut current_genome, &mut widget_demo_state, &mut toasts);
+        }
+        if widget_demo_state.auto_name_confirm_open {
+            show_auto_name_dialog(ctx, &mut current_genome, &mut widget_demo_state, &mut toasts);
+        }
+        if widget_demo_state.replacement_confirm.is_some() {
+            show_replacement_dialog(ctx, &mut current_genome, &mut widget_demo_state, &mut toasts);
+        }
+        if widget_demo_state.duplicate_modes_open {
+            show_duplicate_modes_dialog(ctx, &mut current_genome, &mut widget_demo_state, &mut toasts);
+        }
+        if dock_resource.confirm_reset {
+            show_reset_layout_dialog(ctx, &mut dock_resource, &mut toasts);
+        }
+        show_close_panel_dialog(ctx, &mut dock_resource);
+        show_import_workspace_dialog(ctx, &mut workspace, &current_genome);
+
+        file_dialogs.show_prompt(ctx);
+        if let Some((target, path)) = file_dialogs.take_picked() {
+            let follow_up = handle_picked_path(
+                target,
+                path,
+                &mut archive_state,
+                &genome_library,
+                &mut current_genome,
+                &mut asset_browser,
+                &mut toasts,
+            );
+            match follow_up {
+                Some(PickedFollowUp::Workspace(WorkspaceRequest::Import(path))) => workspace.import(path, &current_genome),
+                Some(PickedFollowUp::Workspace(request)) => workspace.pending = Some(request),
+                Some(PickedFollowUp::ModeCard(idx, path)) if idx < current_genome.genome.modes.len() => {
+                    let decimals = preferences.display_decimals.min(MAX_DISPLAY_DECIMALS);
+                    let card = mode_card::render_mode_card(ctx, &current_genome.genome, idx, decimals);
+                    info!("Exporting mode card for mode {} to {:?}", idx, path);
+                    widget_demo_state.mode_card.start(path, card);
+                }
+                Some(PickedFollowUp::ReplaceGenome(replacement)) => {
+                    replace_genome(&mut current_genome, &mut widget_demo_state, &mut toasts, replacement);
+                }
+                Some(PickedFollowUp::ExportPreferences(path)) => export_preferences(&preferences, &path, &mut toasts),
+                Some(PickedFollowUp::ImportPreferences(path)) => import_preferences(&mut preferences, &path, &mut toasts),
+                Some(PickedFollowUp::ModeCard(..)) | None => {}
+            }
+        }
+        for result in widget_demo_state.mode_card.poll() {
+            match result {
+                Ok(path) => {
+                    info!("Saved mode card to {:?}", path);
+                    toasts.info(format!("Saved mode card to {}", path.display()));
+                }
+                Err((path, err)) => {
+                    warn!("Failed to save mode card to {:?}: {}", path, err);
+                    toasts.error(format!("Could not save mode card to {}: {}", path.display(), err));
+                }
+            }
+        }
+
+        if widget_demo_state.genome_graph.open {
+            show_genome_graph(ctx, &mut widget_demo_state.genome_graph, &mut current_genome, &mut preferences.genome_graph_layout);
+        }
+        if widget_demo_state.genome_json.open {
+            show_genome_json(ctx, &mut widget_demo_state.genome_json, &current_genome);
+        }
+        if widget_demo_state.mode_compare.open {
+            show_mode_compare(ctx, &mut widget_demo_state.mode_compare, &mut current_genome);
+        }
+        if widget_demo_state.help.open {
+            show_help(ctx, &mut widget_demo_state.help, &mut dock_resource);
+        }
+
+        toasts.show(ctx);
+        show_archive_import_report(ctx, &mut archive_state);
+        show_archive_export_progress(ctx, &mut archive_state);
+
+        if global_ui_state.show_viewport_rect_debug {
+            draw_viewport_rect_debug(ctx, &viewport_rect);
+        }
+    }
+}
+
+/// Default file name for exporting something called `name`, with the path separators and
+/// drive colons a name may contain replaced so the dialog doesn't read them as folders
+fn export_file_name(name: &str, extension: &str) -> String {
+    format!("{}.{}", name.replace(['/', '\\', ':'], "_"), extension)
+}
+
+fn request_genome_save(file_dialogs: &mut FileDialogs, genome_name: &str) {
+    file_dialogs.request(FileRequest {
+        target: FileTarget::SaveGenome,
+        kind: PickKind::SaveFile,
+        title: "Save Genome",
+        filter: Some(("JSON", &["json"])),
+        file_name: Some(export_file_name(genome_name, "json")),
+    });
+}
+
+fn request_genome_load(file_dialogs: &mut FileDialogs) {
+    file_dialogs.request(FileRequest {
+        target: FileTarget::LoadGenome,
+        kind: PickKind::OpenFile,
+        title: "Load Genome",
+        filter: Some(("JSON", &["json"])),
+        file_name: None,
+    });
+}
+
+fn request_append_modes(file_dialogs: &mut FileDialogs, merge_duplicates: bool) {
+    file_dialogs.request(FileRequest {
+        target: FileTarget::AppendModes { merge_duplicates },
+        kind: PickKind::OpenFile,
+        title: "Append Modes from Genome",
+        filter: Some(("JSON", &["json"])),
+        file_name: None,
+    });
+}
+
+fn show_genome_menu(
+    ui: &mut egui::Ui,
+    archive_state: &mut GenomeArchiveState,
+    genome_library: &GenomeLibrary,
+    file_dialogs: &mut FileDialogs,
+) {
+    let import_button = egui::Button::new("Import Archive...");
+    if ui.add_enabled(!archive_state.is_importing(), import_button).clicked() {
+        file_dialogs.request(FileRequest {
+            target: FileTarget::ImportArchive,
+            kind: PickKind::OpenFile,
+            title: "Import Archive",
+            filter: Some(("Zip archive", &["zip"])),
+            file_name: None,
+        });
+        ui.close();
+    }
+
+    let export_button = egui::Button::new("Export Library...");
+    let can_export = !archive_state.is_exporting() && !genome_library.genomes.is_empty();
+    let export_response = ui.add_enabled(can_export, export_button)
+        .on_disabled_hover_text("The genome library is empty");
+    if export_response.clicked() {
+        file_dialogs.request(FileRequest {
+            target: FileTarget::ExportLibrary,
+            kind: PickKind::SaveFile,
+            title: "Export Library",
+            filter: Some(("Zip archive", &["zip"])),
+            file_name: Some("genome_library.zip".to_string()),
+        });
+        ui.close();
+    }
+}
+
+/// Genome, scene, layout and preferences together in one file
+fn show_workspace_menu(ui: &mut egui::Ui, workspace: &WorkspaceState, file_dialogs: &mut FileDialogs) {
+    let busy = workspace.pending.is_some() || workspace.confirm_import.is_some();
+    if ui.add_enabled(!busy, egui::Button::new("Export Workspace...")).clicked() {
+        file_dialogs.request(FileRequest {
+            target: FileTarget::ExportWorkspace,
+            kind: PickKind::SaveFile,
+            title: "Export Workspace",
+            filter: Some(("Workspace bundle", &["zip"])),
+            file_name: Some("workspace.zip".to_string()),
+        });
+        ui.close();
+    }
+    if ui.add_enabled(!busy, egui::Button::new("Import Workspace...")).clicked() {
+        file_dialogs.request(FileRequest {
+            target: FileTarget::ImportWorkspace,
+            kind: PickKind::OpenFile,
+            title: "Import Workspace",
+            filter: Some(("Workspace bundle", &["zip"])),
+            file_name: None,
+        });
+        ui.close();
+    }
+}
+
+fn show_edit_menu(ui: &mut egui::Ui, current_genome: &mut CurrentGenome, toasts: &mut Toasts) {
+    let undo_text = current_genome.history.undo_label().map_or("Undo".to_string(), |label| format!("Undo {}", label));
+    let undo_button = egui::Button::new(undo_text).shortcut_text("Ctrl+Z");
+    if ui.add_enabled(current_genome.history.undo_label().is_some(), undo_button).clicked() {
+        undo_genome_edit(current_genome, toasts);
+        ui.close();
+    }
+
+    let redo_text = current_genome.history.redo_label().map_or("Redo".to_string(), |label| format!("Redo {}", label));
+    let redo_button = egui::Button::new(redo_text).shortcut_text("Ctrl+Shift+Z");
+    if ui.add_enabled(current_genome.history.redo_label().is_some(), redo_button).clicked() {
+        redo_genome_edit(current_genome, toasts);
+        ui.close();
+    }
+}
+
+fn undo_genome_edit(current_genome: &mut CurrentGenome, toasts: &mut Toasts) {
+    if let Some(label) = current_genome.undo() {
+        info!("Undid {}", label);
+        toasts.info(format!("Undid {}", label));
+    }
+}
+
+fn redo_genome_edit(current_genome: &mut CurrentGenome, toasts: &mut Toasts) {
+    if let Some(label) = current_genome.redo() {
+        info!("Redid {}", label);
+        toasts.info(format!("Redid {}", label));
+    }
+}
+
+fn show_scale_intervals_dialog(
+    ctx: &egui::Context,
+    current_genome: &mut CurrentGenome,
+    widget_demo_state: &mut WidgetDemoState,
+    toasts: &mut Toasts,
+) {
+    let result = widgets::modal_dialog(ctx, "Scale Split Intervals", |ui| {
+        ui.label(format!(
+            "Multiply every mode's split interval (results are kept within {:.0}-{:.0}s).",
+            SPLIT_INTERVAL_MIN, SPLIT_INTERVAL_MAX,
+        ));
+        let response = ui.horizontal(|ui| {
+            ui.label("Multiplier:");
+            ui.add(egui::DragValue::new(&mut widget_demo_state.scale_intervals_factor)
+                .speed(0.01)
+                .range(0.01..=60.0)
+                .prefix("×"))
+        }).inner;
+        ui.checkbox(&mut widget_demo_state.scale_intervals_minimums, "Also scale randomized minimums");
+        Some(response)
+    });
+
+    match result {
+        widgets::DialogResult::Open => {}
+        widgets::DialogResult::Confirmed => {
+            let factor = widget_demo_state.scale_intervals_factor;
+            current_genome.record_edit("Scale Split Intervals");
+            current_genome.genome.scale_split_intervals(factor, widget_demo_state.scale_intervals_minimums);
+            info!("Scaled split intervals by {}", factor);
+            toasts.info(format!("Scaled split intervals by ×{}", factor));
+            widget_demo_state.scale_intervals_open = false;
+        }
+        widgets::DialogResult::Cancelled => widget_demo_state.scale_intervals_open = false,
+    }
+}
+
+/// Genome waiting to replace the edited one
+pub enum GenomeReplacement {
+    Template(usize),
+    Backup(BackupEntry),
+    /// A genome file, which becomes the genome's file once loaded
+    File(std::path::PathBuf),
+    /// Genome JSON from the clipboard, replaced as one undoable step
+    Paste(Box<crate::genome::GenomeData>),
+    /// A library genome, opened at one of its matching modes
+    Library { genome: Box<crate::genome::GenomeData>, mode_index: usize },
+}
+
+impl GenomeReplacement {
+    fn description(&self) -> String {
+        match self {
+            GenomeReplacement::Template(i) => TEMPLATES.get(*i).map_or("the template".to_string(), |template| format!("the {} template", template.name)),
+            GenomeReplacement::Backup(entry) => format!("the backup of {}", entry.genome_name),
+            GenomeReplacement::File(path) => path.file_name().map_or_else(
+                || path.display().to_string(),
+                |name| name.to_string_lossy().into_owned(),
+            ),
+            GenomeReplacement::Paste(genome) => format!("the pasted genome {}", genome.name),
+            GenomeReplacement::Library { genome, .. } => format!("{} from the library", genome.name),
+        }
+    }
+}
+
+/// Open `replacement` over the edited genome, asking first if it has unsaved changes
+pub fn replace_genome(
+    current_genome: &mut CurrentGenome,
+    widget_demo_state: &mut WidgetDemoState,
+    toasts: &mut Toasts,
+    replacement: GenomeReplacement,
+) {
+    if current_genome.is_dirty() {
+        widget_demo_state.replacement_confirm = Some(replacement);
+    } else {
+        open_replacement(current_genome, toasts, replacement);
+    }
+}
+
+fn open_replacement(current_genome: &mut CurrentGenome, toasts: &mut Toasts, replacement: GenomeReplacement) {
+    match replacement {
+        GenomeReplacement::Template(i) => {
+            if let Some(template) = TEMPLATES.get(i) {
+                open_template(current_genome, toasts, template);
+            }
+        }
+        GenomeReplacement::Backup(entry) => match crate::genome::GenomeData::load_from_file(&entry.path) {
+            Ok(genome) => {
+                current_genome.back_up();
+                current_genome.open(genome);
+                info!("Restored genome backup {}", entry.path.display());
+                toasts.info(format!("Restored {} from its backup", entry.genome_name));
+            }
+            Err(err) => {
+                error!("Failed to restore genome backup {}: {}", entry.path.display(), err);
+                toasts.error(format!("Couldn't restore the backup: {}", err));
+            }
+        },
+        GenomeReplacement::File(path) => match crate::genome::GenomeData::load_from_file(&path) {
+            Ok(genome) => {
+                current_genome.back_up();
+                current_genome.open(genome);
+                info!("Loaded genome from: {:?}", path);
+                toasts.info(format!("Loaded {}", path.display()));
+                current_genome.file_path = Some(path);
+            }
+            Err(err) => {
+                warn!("Failed to load genome from {:?}: {}", path, err);
+                toasts.error(format!("Could not load {}: {}", path.display(), err));
+            }
+        },
+        GenomeReplacement::Paste(genome) => {
+            info!("Pasted genome {} from the clipboard", genome.name);
+            toasts.info(format!("Pasted genome {} (Ctrl+Z to undo)", genome.name));
+            current_genome.replace_undoably(*genome, "Paste Genome");
+        }
+        GenomeReplacement::Library { genome, mode_index } => {
+            current_genome.back_up();
+            current_genome.open(*genome);
+            current_genome.selected_mode_index = mode_index as i32;
+            info!("Opened genome '{}' from library", current_genome.genome.name);
+        }
+    }
+}
+
+fn open_template(current_genome: &mut CurrentGenome, toasts: &mut Toasts, template: &GenomeTemplate) {
+    current_genome.back_up();
+    current_genome.open(template.build());
+    info!("Opened template {}", template.name);
+    toasts.info(format!("Opened the {} template", template.name));
+}
+
+/// Confirmation before another genome replaces one with unsaved changes
+fn show_replacement_dialog(
+    ctx: &egui::Context,
+    current_genome: &mut CurrentGenome,
+    widget_demo_state: &mut WidgetDemoState,
+    toasts: &mut Toasts,
+) {
+    let Some(replacement) = &widget_demo_state.replacement_confirm else {
+        return;
+    };
+    let result = widgets::modal_dialog(ctx, "Replace Genome", |ui| {
+        ui.label(format!("{} has unsaved changes that {} will replace.", current_genome.genome.name, replacement.description()));
+        ui.label("Save the genome first to keep them.");
+        None
+    });
+
+    match result {
+        widgets::DialogResult::Open => {}
+        widgets::DialogResult::Confirmed => {
+            if let Some(replacement) = widget_demo_state.replacement_confirm.take() {
+                open_replacement(current_genome, toasts, replacement);
+            }
+        }
+        widgets::DialogResult::Cancelled => widget_demo_state.replacement_confirm = None,
+    }
+}
+
+/// Groups of modes that only differ by name, each with a button merging it into its first mode
+fn show_duplicate_modes_dialog(
+    ctx: &egui::Context,
+    current_genome: &mut CurrentGenome,
+    widget_demo_state: &mut WidgetDemoState,
+    toasts: &mut Toasts,
+) {
+    let mut merge = None;
+    let result = widgets::modal_dialog(ctx, "Duplicate Modes", |ui| {
+        ui.checkbox(&mut widget_demo_state.duplicate_modes_ignore_color, "Ignore colors");
+        let groups = current_genome.genome.duplicate_mode_groups(widget_demo_state.duplicate_modes_ignore_color);
+        if groups.is_empty() {
+            ui.label("Every mode differs from the others in more than its name.");
+            return None;
+        }
+        ui.label("Merging keeps the first mode of a group and points every transition to the others at it.");
+        egui::ScrollArea::vertical().max_height(300.0).show(ui, |ui| {
+            for group in groups {
+                ui.horizontal(|ui| {
+                    if ui.button("Merge").clicked() {
+                        merge = Some(group.clone());
+                    }
+                    let names: Vec<&str> = group.iter().map(|&i| current_genome.genome.modes[i].name.as_str()).collect();
+                    ui.label(names.join(", "));
+                });
+            }
+        });
+        None
+    });
+
+    if let Some(group) = merge {
+        let name = current_genome.genome.modes[group[0]].name.clone();
+        if current_genome.merge_modes(&group) {
+            info!("Merged modes {:?} into {}", group, name);
+            toasts.info(format!("Merged {} modes into {}", group.len(), name));
+        }
+    }
+    if result != widgets::DialogResult::Open {
+        widget_demo_state.duplicate_modes_open = false;
+    }
+}
+
+fn auto_name_modes(current_genome: &mut CurrentGenome, toasts: &mut Toasts) {
+    current_genome.record_edit("Auto-Name Modes");
+    current_genome.genome.auto_name_modes();
+    info!("Auto-named {} modes", current_genome.genome.modes.len());
+    toasts.info("Named modes after their cell type");
+}
+
+/// Confirmation before "Auto-Name Modes" replaces names the user chose
+fn show_auto_name_dialog(
+    ctx: &egui::Context,
+    current_genome: &mut CurrentGenome,
+    widget_demo_state: &mut WidgetDemoState,
+    toasts: &mut Toasts,
+) {
+    let custom = current_genome.genome.custom_named_modes();
+    let result = widgets::modal_dialog(ctx, "Auto-Name Modes", |ui| {
+        let which = if custom == 1 { "1 mode has a custom name".to_string() } else { format!("{} modes have custom names", custom) };
+        ui.label(format!("{} that will be replaced by names like \"{}\".", which, crate::genome::auto_mode_name(0, 0)));
+        ui.label("This can be undone with Ctrl+Z.");
+        None
+    });
+
+    match result {
+        widgets::DialogResult::Open => {}
+        widgets::DialogResult::Confirmed => {
+            auto_name_modes(current_genome, toasts);
+            widget_demo_state.auto_name_confirm_open = false;
+        }
+        widgets::DialogResult::Cancelled => widget_demo_state.auto_name_confirm_open = false,
+    }
+}
+
+/// Picked paths that [`handle_picked_path`] can't finish on its own
+enum PickedFollowUp {
+    /// Workspace bundles touch more than the UI holds
+    Workspace(WorkspaceRequest),
+    /// Mode cards are drawn with the egui context's fonts
+    ModeCard(usize, std::path::PathBuf),
+    /// Replacing the genome may first ask about unsaved changes
+    ReplaceGenome(GenomeReplacement),
+    /// Settings live in the preferences resource
+    ExportPreferences(std::path::PathBuf),
+    ImportPreferences(std::path::PathBuf),
+}
+
+/// Act on a path picked through [`FileDialogs`], handing back anything that needs more
+/// than this has access to.
+fn handle_picked_path(
+    target: FileTarget,
+    path: std::path::PathBuf,
+    archive_state: &mut GenomeArchiveState,
+    genome_library: &GenomeLibrary,
+    current_genome: &mut CurrentGenome,
+    asset_browser: &mut AssetBrowser,
+    toasts: &mut Toasts,
+) -> Option<PickedFollowUp> {
+    match target {
+        FileTarget::ImportArchive => {
+            info!("Importing genome archive: {:?}", path);
+            archive_state.start_import(path);
+        }
+        FileTarget::ExportLibrary => {
+            info!("Exporting {} genome(s) to archive: {:?}", genome_library.genomes.len(), path);
+            archive_state.start_export(path, genome_library.genomes.clone());
+        }
+        FileTarget::SaveGenome => match current_genome.genome_for_save().save_to_file(&path) {
+            Ok(()) => {
+                info!("Saved genome to: {:?}", path);
+                toasts.info(format!("Saved {}", path.display()));
+                current_genome.mark_saved(path);
+            }
+            Err(err) => {
+                warn!("Failed to save genome to {:?}: {}", path, err);
+                toasts.error(format!("Could not save {}: {}", path.display(), err));
+            }
+        },
+        FileTarget::LoadGenome => return Some(PickedFollowUp::ReplaceGenome(GenomeReplacement::File(path))),
+        FileTarget::AppendModes { merge_duplicates } => match crate::genome::GenomeData::load_from_file(&path) {
+            Ok(other) => {
+                let (appended, merged) = current_genome.append_modes(&other, merge_duplicates);
+                info!("Appended {} modes from {:?}, merging {} duplicates", appended, path, merged);
+                let merged_note = if merged > 0 { format!(" ({} merged into existing modes)", merged) } else { String::new() };
+                toasts.info(format!("Appended {} modes from {}{}", appended, other.name, merged_note));
+            }
+            Err(err) => {
+                warn!("Failed to append modes from {:?}: {}", path, err);
+                toasts.error(format!("Could not load {}: {}", path.display(), err));
+            }
+        },
+        FileTarget::AssetFolder => asset_browser.set_directory(path),
+        FileTarget::ExportMode(idx) => {
+            if let Some(mode) = current_genome.genome.modes.get(idx) {
+                export_mode(mode, &path, toasts);
+            }
+        }
+        FileTarget::ImportMode(idx) => {
+            if idx < current_genome.genome.modes.len() {
+                import_mode(current_genome, idx, &path, toasts);
+            }
+        }
+        FileTarget::ExportWorkspace => return Some(PickedFollowUp::Workspace(WorkspaceRequest::Export(path))),
+        FileTarget::ImportWorkspace => return Some(PickedFollowUp::Workspace(WorkspaceRequest::Import(path))),
+        FileTarget::ExportModeCard(idx) => return Some(PickedFollowUp::ModeCard(idx, path)),
+        FileTarget::ExportPreferences => return Some(PickedFollowUp::ExportPreferences(path)),
+        FileTarget::ImportPreferences => return Some(PickedFollowUp::ImportPreferences(path)),
+    }
+    None
+}
+
+fn export_preferences(preferences: &Preferences, path: &std::path::Path, toasts: &mut Toasts) {
+    let result = preferences.to_ron()
+        .map_err(|err| err.to_string())
+        .and_then(|data| std::fs::write(path, data).map_err(|err| err.to_string()));
+    match result {
+        Ok(()) => {
+            info!("Exported settings to {:?}", path);
+            toasts.info(format!("Exported settings to {}", path.display()));
+        }
+        Err(err) => {
+            warn!("Failed to export settings to {:?}: {}", path, err);
+            toasts.error(format!("Could not export settings to {}: {}", path.display(), err));
+        }
+    }
+}
+
+/// Replace the settings with ones from `path`, clamping any that are out of range
+fn import_preferences(preferences: &mut Preferences, path: &std::path::Path, toasts: &mut Toasts) {
+    let result = std::fs::read_to_string(path)
+        .map_err(|err| err.to_string())
+        .and_then(|data| Preferences::from_ron(&data).map_err(|err| err.to_string()));
+    match result {
+        Ok((imported, clamped)) => {
+            *preferences = imported;
+            info!("Imported settings from {:?}", path);
+            if clamped.is_empty() {
+                toasts.info(format!("Imported settings from {}", path.display()));
+            } else {
+                warn!("Clamped imported settings: {}", clamped.join(", "));
+                toasts.info(format!("Imported settings, adjusting out-of-range {}", clamped.join(", ")));
+            }
+        }
+        Err(err) => {
+            warn!("Failed to import settings from {:?}: {}", path, err);
+            toasts.error(format!("Could not import {}: {}", path.display(), err));
+        }
+    }
+}
+
+/// OS file manager shortcuts for the config folder and the current genome file
+#[cfg(feature = "opener")]
+fn show_file_manager_menu(ui: &mut egui::Ui, current_genome: &CurrentGenome, toasts: &mut Toasts) {
+    if ui.button("Open Config Folder").clicked() {
+        let dir = config_dir();
+        if let Err(err) = opener::open(&dir) {
+            warn!("Failed to open config folder {:?}: {}", dir, err);
+            toasts.error(format!("Could not open {}: {}", dir.display(), err));
+        }
+        ui.close();
+    }
+
+    let reveal_button = egui::Button::new("Reveal Genome File");
+    let reveal_response = ui.add_enabled(current_genome.file_path.is_some(), reveal_button)
+        .on_disabled_hover_text("The current genome hasn't been saved or loaded from a file");
+    if reveal_response.clicked() {
+        if let Some(path) = &current_genome.file_path
+            && let Err(err) = opener::reveal(path)
+        {
+            warn!("Failed to reveal {:?}: {}", path, err);
+            toasts.error(format!("Could not reveal {}: {}", path.display(), err));
+        }
+        ui.close();
+    }
+}
+
+/// Progress of the running library export, then its result until dismissed
+fn show_archive_export_progress(ctx: &egui::Context, archive_state: &mut GenomeArchiveState) {
+    let Some(summary) = archive_state.last_export.as_ref() else {
+        return;
+    };
+
+    let mut open = true;
+    egui::Window::new("Library Export")
+        .open(&mut open)
+        .collapsible(false)
+        .resizable(false)
+        .show(ctx, |ui| {
+            ui.label(summary.archive_path.display().to_string());
+            ui.separator();
+
+            match &summary.result {
+                None => {
+                    let written = summary.written.load(std::sync::atomic::Ordering::Relaxed);
+                    let fraction = written as f32 / summary.total.max(1) as f32;
+                    ui.add(egui::ProgressBar::new(fraction)
+                        .text(format!("{} / {}", written, summary.total))
+                        .animate(true));
+                    ctx.request_repaint();
+                }
+                Some(Ok(())) => {
+                    ui.colored_label(egui::Color32::LIGHT_GREEN, format!("✔ Exported {} genome(s)", summary.total));
+                }
+                Some(Err(err)) => {
+                    ui.colored_label(egui::Color32::LIGHT_RED, format!("✖ Export failed: {}", err));
+                }
+            }
+        });
+
+    if !open && summary.result.is_some() {
+        archive_state.last_export = None;
+    }
+}
+
+/// Per-file results of the last archive import, until the user dismisses them
+fn show_archive_import_report(ctx: &egui::Context, archive_state: &mut GenomeArchiveState) {
+    let importing = archive_state.is_importing();
+    let Some(summary) = archive_state.last_import.as_ref() else {
+        return;
+    };
+
+    let mut open = true;
+    egui::Window::new("Archive Import")
+        .open(&mut open)
+        .collapsible(false)
+        .resizable(true)
+        .show(ctx, |ui| {
+            ui.label(summary.archive_path.display().to_string());
+            ui.separator();
+
+            if importing {
+                ui.horizontal(|ui| {
+                    ui.spinner();
+                    ui.label("Importing...");
+                });
+                return;
+            }
+
+            match &summary.entries {
+                Err(err) => {
+                    ui.colored_label(egui::Color32::LIGHT_RED, format!("Could not read archive: {}", err));
+                }
+                Ok(entries) if entries.is_empty() => {
+                    ui.label("No genome files (.json/.ron) found in archive");
+                }
+                Ok(entries) => {
+                    let loaded = entries.iter().filter(|e| e.result.is_ok()).count();
+                    ui.label(format!("Loaded {} of {} file(s)", loaded, entries.len()));
+                    egui::ScrollArea::vertical()
+                        .max_height(300.0)
+                        .show(ui, |ui| {
+                        for entry in entries {
+                            match &entry.result {
+                                Ok(name) => {
+                                    ui.colored_label(egui::Color32::LIGHT_GREEN, format!("✔ {} ({})", entry.file_name, name));
+                                }
+                                Err(err) => {
+                                    ui.colored_label(egui::Color32::LIGHT_RED, format!("✖ {}: {}", entry.file_name, err));
+                                }
+                            }
+                        }
+                    });
+                }
+            }
+        });
+
+    if !open && !importing {
+        archive_state.last_import = None;
     }
 }
 
+/// Small overlay pinned to a corner of the viewport with the selected mode, sim time and dragged sphere
+fn draw_viewport_hud(
+    ctx: &egui::Context,
+    viewport: egui::Rect,
+    corner: HudCorner,
+    current_genome: &CurrentGenome,
+    time_value: f32,
+    dragged_name: Option<&str>,
+) {
+    const MARGIN: f32 = 8.0;
+    let (anchor, pivot) = match corner {
+        HudCorner::TopLeft => (viewport.left_top() + egui::vec2(MARGIN, MARGIN), egui::Align2::LEFT_TOP),
+        HudCorner::TopRight => (viewport.right_top() + egui::vec2(-MARGIN, MARGIN), egui::Align2::RIGHT_TOP),
+        HudCorner::BottomLeft => (viewport.left_bottom() + egui::vec2(MARGIN, -MARGIN), egui::Align2::LEFT_BOTTOM),
+        HudCorner::BottomRight => (viewport.right_bottom() + egui::vec2(-MARGIN, -MARGIN), egui::Align2::RIGHT_BOTTOM),
+    };
+
+    egui::Area::new(egui::Id::new("viewport_hud"))
+        .fixed_pos(anchor)
+        .pivot(pivot)
+        .constrain_to(viewport)
+        .interactable(false)
+        .order(egui::Order::Foreground)
+        .show(ctx, |ui| {
+            egui::Frame::popup(ui.style())
+                .fill(ui.visuals().extreme_bg_color.gamma_multiply(0.8))
+                .show(ui, |ui| {
+                ui.set_max_width(viewport.width() - 2.0 * MARGIN);
+                
+                let selected_idx = current_genome.selected_mode_index as usize;
+                match current_genome.genome.modes.get(selected_idx) {
+                    Some(mode) => {
+                        ui.horizontal(|ui| {
+                            let (swatch, _) = ui.allocate_exact_size(egui::vec2(12.0, 12.0), egui::Sense::hover());
+                            let color = egui::Color32::from_rgb(
+                                (mode.color.x * 255.0) as u8,
+                                (mode.color.y * 255.0) as u8,
+                                (mode.color.z * 255.0) as u8,
+                            );
+                            ui.painter().rect_filled(swatch, 2.0, color);
+                            ui.label(format!("Mode: {}", mode.name));
+                        });
+                    }
+                    None => {
+                        ui.label("Mode: none");
+                    }
+                }
+                ui.label(format!("Time: {:.1}", time_value));
+                ui.label(format!("Dragging: {}", dragged_name.unwrap_or("-")));
+            });
+        });
+}
+
+/// Outline the captured viewport rect on top of everything so drag gating can be checked visually
+fn draw_viewport_rect_debug(ctx: &egui::Context, viewport_rect: &ViewportRect) {
+    let painter = ctx.layer_painter(egui::LayerId::debug());
+    let color = egui::Color32::from_rgb(255, 0, 255);
+
+    let Some(rect) = viewport_rect.rect else {
+        painter.debug_text(
+            ctx.content_rect().center_top() + egui::vec2(0.0, 30.0),
+            egui::Align2::CENTER_TOP,
+            color,
+            "Viewport rect: None",
+        );
+        return;
+    };
+
+    painter.rect_stroke(rect, 0.0, egui::Stroke::new(2.0, color), egui::StrokeKind::Inside);
+    painter.debug_text(
+        rect.left_top() + egui::vec2(4.0, 4.0),
+        egui::Align2::LEFT_TOP,
+        color,
+        format!(
+            "Viewport rect: ({:.0}, {:.0}) - ({:.0}, {:.0}) [{:.0} x {:.0}] @ {:.2}x",
+            rect.min.x,
+            rect.min.y,
+            rect.max.x,
+            rect.max.y,
+            rect.width(),
+            rect.height(),
+            ctx.pixels_per_point(),
+        ),
+    );
+}
+
 struct TabViewer<'a> {
     viewport_rect: &'a mut ViewportRect,
     current_genome: &'a mut CurrentGenome,
     widget_demo_state: &'a mut WidgetDemoState,
+    genome_library: &'a GenomeLibrary,
+    toasts: &'a mut Toasts,
+    asset_browser: &'a mut AssetBrowser,
+    preferences: &'a mut Preferences,
+    file_dialogs: &'a mut FileDialogs,
+    performance_monitor: &'a mut PerformanceMonitor,
+    /// Panel currently soloed, if any
+    soloed: Option<Panel>,
+    /// Set from a tab's context menu; applied once the dock is done drawing
+    solo_request: &'a mut Option<DockTab>,
+    /// Panels whose only open copy asks before closing
+    last_essential_panels: Vec<Panel>,
+    /// Set when such a panel's tab is closed; the confirmation shows once the dock is done drawing
+    close_request: &'a mut Option<Panel>,
+    /// Tabs closed from the tab bar this frame
+    closed_tabs: &'a mut Vec<DockTab>,
+}
+
+/// Name and display color of each mode, for the colored mode dropdowns
+fn mode_display_data(modes: &[ModeSettings]) -> Vec<(String, egui::Color32)> {
+    modes.iter()
+        .map(|m| {
+            let color = m.color;
+            let r = (color.x * 255.0) as u8;
+            let g = (color.y * 255.0) as u8;
+            let b = (color.z * 255.0) as u8;
+            (m.name.clone(), egui::Color32::from_rgb(r, g, b))
+        })
+        .collect()
+}
+
+impl TabViewer<'_> {
+    /// Colored dropdown for the mode the genome starts in, kept in sync with the Modes list marker
+    fn initial_mode_control(&mut self, ui: &mut egui::Ui, id_salt: &str) {
+        ui.horizontal(|ui| {
+            ui.label("Initial Mode:");
+            let modes = mode_display_data(&self.current_genome.genome.modes);
+            let mut initial_mode = self.current_genome.genome.initial_mode.max(0) as usize;
+            if widgets::mode_dropdown(ui, id_salt, &modes, &mut initial_mode, None) {
+                self.current_genome.record_edit("Initial Mode");
+                self.current_genome.genome.initial_mode = initial_mode as i32;
+                info!("Initial mode changed to: {}", initial_mode);
+            }
+        });
+    }
+
+    /// Mode a mode panel shows: the one its tab is pinned to, otherwise the selected mode
+    fn target_mode(&self, tab: &DockTab) -> usize {
+        tab.pinned_mode.unwrap_or(self.current_genome.selected_mode_index as usize)
+    }
+
+    /// Under staged editing, show the Apply/Revert bar and swap the mode's scratch copy into the
+    /// genome so the panel edits it. Returns the mode index to hand to `end_staged_edit`.
+    fn begin_staged_edit(&mut self, ui: &mut egui::Ui, tab: &DockTab) -> Option<usize> {
+        if !self.preferences.staged_editing {
+            self.widget_demo_state.staged_modes.clear();
+            return None;
+        }
+        if !tab.panel.shows_mode() {
+            return None;
+        }
+        let idx = self.target_mode(tab);
+        let staged_modes = &mut self.widget_demo_state.staged_modes;
+        let mode = self.current_genome.genome.modes.get_mut(idx)?;
+        let staged = staged_modes.entry(idx).or_insert_with(|| StagedMode::new(mode));
+        // Changed from elsewhere, e.g. by an undo: carry the staged edits over to the new mode
+        if staged.base != *mode {
+            let dropped = staged.rebase(mode);
+            if !dropped.is_empty() {
+                warn!("Dropped staged edits to {} that changed outside the panel: {}", mode.name, dropped.join(", "));
+                self.toasts.error(format!("{} changed elsewhere, so staged edits to {} were dropped", mode.name, dropped.join(", ")));
+            }
+        }
+
+        let changed = staged.changed_fields();
+        let mut apply = false;
+        let mut revert = false;
+        ui.horizontal(|ui| {
+            if changed.is_empty() {
+                ui.weak("Staged editing: changes wait for Apply");
+                return;
+            }
+            let count = if changed.len() == 1 { "1 staged change".to_string() } else { format!("{} staged changes", changed.len()) };
+            ui.label(count).on_hover_text(changed.join("\n"));
+            apply = ui.button("Apply").on_hover_text("Write the staged changes to the genome").clicked();
+            revert = ui.button("Revert").on_hover_text("Discard the staged changes").clicked();
+        });
+        ui.separator();
+
+        if apply {
+            let name = mode.name.clone();
+            self.current_genome.record_edit(format!("Apply Staged Edits to {}", name));
+            let mode = &mut self.current_genome.genome.modes[idx];
+            // Only the edited fields are written, field by field
+            for path in &changed {
+                if let Err(err) = copy_field(&staged.edited, mode, path) {
+                    warn!("Failed to apply staged {}: {}", path, err);
+                }
+            }
+            info!("Applied {} staged change(s) to {}", changed.len(), name);
+            *staged = StagedMode::new(mode);
+        } else if revert {
+            *staged = StagedMode::new(mode);
+        }
+
+        std::mem::swap(&mut self.current_genome.genome.modes[idx], &mut staged.edited);
+        Some(idx)
+    }
+
+    /// Record an undo step if a slider panel changed mode `idx` from `before`. Named after the
+    /// field, so dragging one slider undoes as a single step.
+    fn record_slider_edit(&mut self, idx: usize, before: ModeSettings) {
+        let Some(mode) = self.current_genome.genome.modes.get(idx) else {
+            return;
+        };
+        let label = match changed_fields(&before, mode).as_slice() {
+            [] => return,
+            [path] => field_label(path),
+            _ => format!("Edit {}", mode.name),
+        };
+        self.current_genome.record_mode_edit(idx, before, label);
+    }
+
+    /// Put the committed mode back after the panel drew, keeping its edits in the scratch copy
+    fn end_staged_edit(&mut self, idx: usize) {
+        if let (Some(staged), Some(mode)) = (self.widget_demo_state.staged_modes.get_mut(&idx), self.current_genome.genome.modes.get_mut(idx)) {
+            std::mem::swap(mode, &mut staged.edited);
+        }
+    }
 }
 
 impl<'a> egui_dock::TabViewer for TabViewer<'a> {
-    type Tab = Panel;
+    type Tab = DockTab;
+
+    fn id(&mut self, tab: &mut Self::Tab) -> egui::Id {
+        egui::Id::new(tab.id())
+    }
 
     fn title(&mut self, tab: &mut Self::Tab) -> egui::WidgetText {
-        tab.to_string().into()
+        match tab.pinned_mode.and_then(|idx| self.current_genome.genome.modes.get(idx)) {
+            Some(mode) => format!("{} 📌 {}", tab.panel, mode.name).into(),
+            None => tab.panel.to_string().into(),
+        }
     }
 
     fn ui(&mut self, ui: &mut egui::Ui, tab: &mut Self::Tab) {
-        match tab {
+        // Pinned panels keep their mode, so only panels following the selection can switch it
+        if tab.panel.shows_mode() && tab.pinned_mode.is_none() {
+            let modes = mode_display_data(&self.current_genome.genome.modes);
+            let id = ui.id().with("recent_modes");
+            if let Some(idx) = widgets::recent_modes_dropdown(ui, id, &self.widget_demo_state.recent_modes, &modes) {
+                self.current_genome.selected_mode_index = idx as i32;
+            }
+        }
+        let staged_mode = self.begin_staged_edit(ui, tab);
+        // The slider panels edit the mode in place, so keep a copy to record the edit for undo
+        let slider_edit = match tab.panel {
+            Panel::CellTypeSettings | Panel::AdhesionSettings | Panel::ParentSettings if staged_mode.is_none() => {
+                let idx = self.target_mode(tab);
+                self.current_genome.genome.modes.get(idx).map(|mode| (idx, mode.clone()))
+            }
+            _ => None,
+        };
+        match tab.panel {
             Panel::Viewport => {
                 // Capture the viewport rect for mouse interaction
                 let rect = ui.available_rect_before_wrap();
@@ -183,6 +1656,26 @@ impl<'a> egui_dock::TabViewer for TabViewer<'a> {
                     ui.separator();
                     ui.label("Genome Editor");
                     ui.label("Genome editing interface");
+
+                    ui.add_space(10.0);
+                    self.initial_mode_control(ui, "genome_editor_initial_mode");
+
+                    ui.add_space(10.0);
+                    ui.heading("Statistics");
+                    let genome = &self.current_genome.genome;
+                    egui::Grid::new("genome_statistics").num_columns(2).show(ui, |ui| {
+                        ui.label("Modes:");
+                        ui.label(genome.modes.len().to_string());
+                        ui.end_row();
+                        ui.label("Reachable Modes:");
+                        ui.label(genome.reachable_modes().len().to_string());
+                        ui.end_row();
+                        ui.label("Complexity:");
+                        ui.label(format!("{:.2}", genome.complexity_score()))
+                            .on_hover_text("Reachable modes + distinct transitions between them + 10 × parameter variation\n\
+                                (coefficient of variation of split interval, split mass and nutrient priority)");
+                        ui.end_row();
+                    });
                 });
             }
             Panel::SceneManager => {
@@ -198,9 +1691,62 @@ impl<'a> egui_dock::TabViewer for TabViewer<'a> {
                 egui::ScrollArea::vertical()
                     .auto_shrink([false, false])
                     .show(ui, |ui| {
-                    ui.separator();
-                    ui.label("Performance Monitor");
-                    ui.label("FPS and performance metrics");
+                    let monitor = &mut *self.performance_monitor;
+                    ui.heading("Frame Time");
+                    match monitor.frame_time_stats() {
+                        Some(stats) => {
+                            let budget = monitor.frame_budget_ms;
+                            egui::Grid::new("frame_time_stats").num_columns(2).show(ui, |ui| {
+                                ui.label("Average:");
+                                ui.label(format!("{:.2} ms ({:.0} FPS)", stats.average_ms, 1000.0 / stats.average_ms));
+                                ui.end_row();
+                                ui.label("1% low:");
+                                ui.label(format!("{:.2} ms ({:.0} FPS)", stats.low_1_ms, 1000.0 / stats.low_1_ms));
+                                ui.end_row();
+                                ui.label("0.1% low:");
+                                ui.label(format!("{:.2} ms ({:.0} FPS)", stats.low_01_ms, 1000.0 / stats.low_01_ms));
+                                ui.end_row();
+                                ui.label("Budget:");
+                                ui.label(format!("{:.2} ms ({:.0} Hz)", budget, 1000.0 / budget));
+                                ui.end_row();
+                            });
+
+                            let status = BudgetStatus::of(stats.low_1_ms, budget);
+                            let color = match status {
+                                BudgetStatus::Within => egui::Color32::LIGHT_GREEN,
+                                BudgetStatus::Over => ui.visuals().warn_fg_color,
+                                BudgetStatus::Stutter => ui.visuals().error_fg_color,
+                            };
+                            ui.horizontal(|ui| {
+                                ui.colored_label(color, "⏺");
+                                ui.label(status.label())
+                                    .on_hover_text("Based on the 1% low over the recent frames");
+                            });
+                            let last_over = monitor.last_over_budget
+                                .map_or("never".to_string(), |at| relative_time(monitor.elapsed_secs - at));
+                            ui.label(format!("Last over budget: {}", last_over))
+                                .on_hover_text("A few frames in a row over budget, or one more than twice over");
+                            ui.label(format!("Over the last {} frames", monitor.frame_times.len()));
+                        }
+                        None => {
+                            ui.label("Collecting frame times...");
+                        }
+                    }
+
+                    // Stress test: fill the viewport with draggable spheres to load picking and rendering
+                    ui.add_space(8.0);
+                    ui.heading("Stress Test");
+                    ui.horizontal(|ui| {
+                        ui.label("Spheres:");
+                        ui.add(egui::DragValue::new(&mut monitor.stress_count).range(1..=MAX_STRESS_COUNT).speed(10.0));
+                        if ui.button("Spawn").on_hover_text("Replace any stress spheres with a new grid").clicked() {
+                            monitor.stress_request = Some(StressTestAction::Spawn(monitor.stress_count));
+                        }
+                        if ui.add_enabled(monitor.stress_spawned > 0, egui::Button::new("Clear")).clicked() {
+                            monitor.stress_request = Some(StressTestAction::Clear);
+                        }
+                    });
+                    ui.label(format!("{} stress spheres in the scene", monitor.stress_spawned));
                 });
             }
             Panel::RenderingControls => {
@@ -248,6 +1794,203 @@ impl<'a> egui_dock::TabViewer for TabViewer<'a> {
                     ui.label("Lighting configuration");
                 });
             }
+            Panel::CellTypeSettings => {
+                egui::ScrollArea::vertical()
+                    .auto_shrink([false, false])
+                    .show(ui, |ui| {
+                    // Force content to fill available width
+                    ui.set_width(ui.available_width());
+                    ui.add_space(10.0);
+                    
+                    // Mode shown here: the pinned one, otherwise the selected one
+                    let selected_idx = self.target_mode(tab);
+                    if selected_idx >= self.current_genome.genome.modes.len() {
+                        ui.label("No mode selected");
+                        return;
+                    }
+                    let mode = &mut self.current_genome.genome.modes[selected_idx];
+                    
+                    let type_name = CELL_TYPE_NAMES.get(mode.cell_type as usize).copied().unwrap_or("Unknown");
+                    ui.label(format!("Type: {}", type_name));
+                    ui.add_space(4.0);
+                    
+                    match mode.cell_type {
+                        // Photocyte / Phagocyte - passive and food-based growth
+                        0 | 1 => {
+                            // Nutrient Gain Rate (0.0 to 5.0)
+                            ui.label("Nutrient Gain Rate:");
+                            widgets::slider_row(ui, &mut mode.nutrient_gain_rate, ranges::NUTRIENT_GAIN_RATE, 0.05, "");
+                        }
+                        // Flagellocyte
+                        2 => {
+                            // Swim Force (0.0 to 5.0)
+                            ui.label("Swim Force:");
+                            widgets::slider_row(ui, &mut mode.swim_force, ranges::SWIM_FORCE, 0.05, "");
+                        }
+                        // Devorocyte
+                        3 => {
+                            // Devour Rate (0.0 to 10.0)
+                            ui.label("Devour Rate:");
+                            widgets::slider_row(ui, &mut mode.devour_rate, ranges::DEVOUR_RATE, 0.1, "");
+                        }
+                        // Lipocyte
+                        4 => {
+                            // Storage Capacity (0.0 to 20.0)
+                            ui.label("Storage Capacity:");
+                            widgets::slider_row(ui, &mut mode.storage_capacity, ranges::STORAGE_CAPACITY, 0.1, "");
+                        }
+                        _ => {
+                            ui.label("No settings for this cell type");
+                        }
+                    }
+                });
+            }
+            Panel::LibrarySearch => {
+                render_library_search(ui, self.genome_library, self.current_genome, self.widget_demo_state, self.toasts);
+            }
+            Panel::Settings => {
+                egui::ScrollArea::vertical()
+                    .auto_shrink([false, false])
+                    .show(ui, |ui| {
+                    ui.add_space(10.0);
+                    ui.horizontal(|ui| {
+                        if ui.button("Export Settings...").on_hover_text("Save these settings to a file, e.g. to carry them to another machine").clicked() {
+                            self.file_dialogs.request(FileRequest {
+                                target: FileTarget::ExportPreferences,
+                                kind: PickKind::SaveFile,
+                                title: "Export Settings",
+                                filter: Some(("Settings", &["ron"])),
+                                file_name: Some("settings.ron".to_string()),
+                            });
+                        }
+                        if ui.button("Import Settings...").on_hover_text("Replace these settings with ones from a file; out-of-range values are clamped").clicked() {
+                            self.file_dialogs.request(FileRequest {
+                                target: FileTarget::ImportPreferences,
+                                kind: PickKind::OpenFile,
+                                title: "Import Settings",
+                                filter: Some(("Settings", &["ron"])),
+                                file_name: None,
+                            });
+                        }
+                    });
+                    
+                    ui.add_space(10.0);
+                    ui.heading("Autosave");
+                    ui.label(format!("Intervals in seconds (0 = off, minimum {:.0}s)", MIN_AUTOSAVE_SECS));
+                    ui.add_space(4.0);
+                    
+                    egui::Grid::new("autosave_settings")
+                        .num_columns(2)
+                        .spacing([8.0, 4.0])
+                        .show(ui, |ui| {
+                        ui.label("Dock Layout:");
+                        autosave_interval_field(ui, &mut self.preferences.dock_autosave_secs);
+                        ui.end_row();
+                        
+                        ui.label("Genome:");
+                        autosave_interval_field(ui, &mut self.preferences.genome_autosave_secs);
+                        ui.end_row();
+
+                        ui.label("Genome Backups:");
+                        ui.add(egui::DragValue::new(&mut self.preferences.genome_autosave_backups).range(0..=MAX_AUTOSAVE_BACKUPS))
+                            .on_hover_text("Previous genome autosaves kept as numbered copies; older ones are deleted");
+                        ui.end_row();
+
+                        ui.label("Safety Backups:");
+                        ui.add(egui::DragValue::new(&mut self.preferences.genome_safety_backups).range(0..=MAX_AUTOSAVE_BACKUPS))
+                            .on_hover_text("Snapshots taken before the genome is replaced or loses modes, listed in Genome > Restore from Backup (0 = off)");
+                        ui.end_row();
+                    });
+                    
+                    ui.add_space(10.0);
+                    ui.heading("Display");
+                    ui.horizontal(|ui| {
+                        ui.label("Decimal places:");
+                        ui.add(egui::DragValue::new(&mut self.preferences.display_decimals).range(0..=MAX_DISPLAY_DECIMALS));
+                    }).response.on_hover_text("Digits shown in value fields; stored values keep full precision");
+                    ui.checkbox(&mut self.preferences.show_advanced_parameters, "Show advanced parameters")
+                        .on_hover_text("Twist constraint, max angular deviation, min connections and other rarely needed controls");
+                    ui.checkbox(&mut self.preferences.show_euler_angles, "Show Euler angles under the orientation balls")
+                        .on_hover_text("Edit child orientations as X/Y/Z rotations in degrees");
+                    ui.checkbox(&mut self.preferences.staged_editing, "Staged mode editing")
+                        .on_hover_text("Mode panels edit a scratch copy; changes reach the genome only on Apply. Turning this off discards pending changes.");
+                    
+                    ui.add_space(10.0);
+                    ui.heading("Scene");
+                    ui.horizontal(|ui| {
+                        ui.label("Ground height:");
+                        ui.add(egui::DragValue::new(&mut self.preferences.ground_height).speed(0.05));
+                    });
+                    ui.checkbox(&mut self.preferences.clamp_drag_to_ground, "Keep dragged spheres above ground")
+                        .on_hover_text("Turn off to drag spheres below the ground plane");
+                    ui.horizontal(|ui| {
+                        ui.checkbox(&mut self.preferences.drag_grid_snap, "Snap dropped spheres to grid")
+                            .on_hover_text("Drop dragged spheres on the nearest grid point");
+                        ui.add_enabled(
+                            self.preferences.drag_grid_snap,
+                            egui::DragValue::new(&mut self.preferences.drag_grid_spacing).speed(0.01).range(DRAG_GRID_SPACING_RANGE),
+                        );
+                    });
+                    ui.add_enabled(
+                        self.preferences.drag_grid_snap,
+                        egui::Checkbox::new(&mut self.preferences.show_drag_snap_marker, "Show where a dragged sphere will snap"),
+                    );
+                    ui.checkbox(&mut self.preferences.settle_on_release, "Settle dropped spheres")
+                        .on_hover_text("Spring a dropped sphere towards the selected mode's adhesion rest length from its nearest neighbour");
+                    ui.checkbox(&mut self.preferences.show_adhesions, "Show adhesions")
+                        .on_hover_text("Draw a spring from a dropped sphere to the neighbour it stuck to: orange if it can break, blue if not, more coils when stiffer");
+                    
+                    ui.add_space(10.0);
+                    ui.heading("Window");
+                    ui.checkbox(&mut self.preferences.remember_window_geometry, "Remember size and position")
+                        .on_hover_text("Otherwise the window opens maximized");
+                    
+                    ui.add_space(10.0);
+                    ui.heading("Dock Separators");
+                    egui::Grid::new("separator_settings")
+                        .num_columns(2)
+                        .spacing([8.0, 4.0])
+                        .show(ui, |ui| {
+                        ui.label("Width:");
+                        ui.add(egui::DragValue::new(&mut self.preferences.separator_width).speed(0.1).range(SEPARATOR_WIDTH_RANGE).suffix("px"));
+                        ui.end_row();
+                        
+                        ui.label("Grab Margin:");
+                        ui.add(egui::DragValue::new(&mut self.preferences.separator_grab_width).speed(0.1).range(SEPARATOR_GRAB_WIDTH_RANGE).suffix("px"))
+                            .on_hover_text("Extra grabbable space on each side of a splitter");
+                        ui.end_row();
+                        
+                        ui.label("Hover Color:");
+                        ui.horizontal(|ui| {
+                            let mut custom = self.preferences.separator_hover_color.is_some();
+                            if ui.checkbox(&mut custom, "Custom").changed() {
+                                let theme = ui.visuals().widgets.hovered.fg_stroke.color;
+                                self.preferences.separator_hover_color = custom.then_some([theme.r(), theme.g(), theme.b()]);
+                            }
+                            if let Some(color) = self.preferences.separator_hover_color.as_mut() {
+                                ui.color_edit_button_srgb(color);
+                            }
+                        });
+                        ui.end_row();
+                    });
+                    
+                    ui.add_space(10.0);
+                    ui.heading("Viewport HUD");
+                    ui.checkbox(&mut self.preferences.show_viewport_hud, "Show HUD");
+                    ui.add_enabled_ui(self.preferences.show_viewport_hud, |ui| {
+                        ui.horizontal(|ui| {
+                            ui.label("Corner:");
+                            egui::ComboBox::from_id_salt("viewport_hud_corner")
+                                .selected_text(self.preferences.viewport_hud_corner.label())
+                                .show_ui(ui, |ui| {
+                                    for corner in HudCorner::ALL {
+                                        ui.selectable_value(&mut self.preferences.viewport_hud_corner, corner, corner.label());
+                                    }
+                                });
+                        });
+                    });
+                });
+            }
             Panel::Inspector => {
                 egui::ScrollArea::vertical()
                     .auto_shrink([false, false])
@@ -280,63 +2023,110 @@ impl<'a> egui_dock::TabViewer for TabViewer<'a> {
                 });
             }
             Panel::Assets => {
-                egui::ScrollArea::vertical()
-                    .auto_shrink([false, false])
-                    .show(ui, |ui| {
-                    ui.separator();
-                    ui.label("Project assets and resources");
-                    ui.add_space(10.0);
-                    ui.label("📂 Textures");
-                    ui.label("📂 Models");
-                    ui.label("📂 Scripts");
-                });
+                render_assets_panel(ui, self.asset_browser, self.current_genome, self.widget_demo_state, self.toasts, self.file_dialogs);
             }
             Panel::CircleSliders => {
                 egui::ScrollArea::vertical()
                     .auto_shrink([false, false])
                     .show(ui, |ui| {
                     ui.checkbox(&mut self.widget_demo_state.enable_snapping, "Enable Snapping (11.25°)");
-                    ui.add_space(10.0);
+                    ui.checkbox(&mut self.widget_demo_state.dial_center_zero, "Center-Zero Fill")
+                        .on_hover_text("Mark zero at the top and color the arc by direction: positive right, negative left");
                     
-                    // Get current mode
-                    let selected_idx = self.current_genome.selected_mode_index as usize;
+                    // Mode shown here: the pinned one, otherwise the selected one
+                    let selected_idx = self.target_mode(tab);
                     if selected_idx < self.current_genome.genome.modes.len() {
+                        ui.horizontal_wrapped(|ui| {
+                            ui.label("Presets:");
+                            for preset in SplitDirectionPreset::ALL {
+                                if ui.button(preset.label()).clicked() {
+                                    // Staged edits are recorded once, on Apply
+                                    if !self.preferences.staged_editing {
+                                        self.current_genome.record_edit(format!("{} Split Direction", preset.label()));
+                                    }
+                                    let mut rng = random::SeededRng::new(random::fresh_seed());
+                                    self.current_genome.genome.modes[selected_idx].parent_split_direction = preset.direction(&mut rng);
+                                }
+                            }
+                        });
+
                         let mode = &mut self.current_genome.genome.modes[selected_idx];
                         
-                        // Calculate available space and size for two sliders
-                        let _available_width = ui.available_width();
-                        let radius = 29.25;
-                        
-                        // Always side by side
+                        // Pitch/yaw link
                         ui.horizontal(|ui| {
-                            ui.add_space(10.0);
+                            ui.label("Link:");
+                            let previous_link = self.widget_demo_state.split_link;
+                            egui::ComboBox::from_id_salt("split_direction_link")
+                                .selected_text(self.widget_demo_state.split_link.label())
+                                .show_ui(ui, |ui| {
+                                    for link in SplitDirectionLink::ALL {
+                                        ui.selectable_value(&mut self.widget_demo_state.split_link, link, link.label());
+                                    }
+                                });
+                            if self.widget_demo_state.split_link == SplitDirectionLink::Offset {
+                                if previous_link != SplitDirectionLink::Offset {
+                                    // Start from the current spread so turning the link on doesn't move anything
+                                    self.widget_demo_state.split_link_offset =
+                                        wrap_degrees(mode.parent_split_direction.y - mode.parent_split_direction.x);
+                                }
+                                ui.add(egui::DragValue::new(&mut self.widget_demo_state.split_link_offset)
+                                    .speed(0.5)
+                                    .range(-180.0..=180.0)
+                                    .suffix("°"))
+                                    .on_hover_text("Yaw minus pitch");
+                            }
+                        });
+                        ui.add_space(10.0);
+                        
+                        let (radius, stacked) = split_dial_layout(ui.available_width(), ui.spacing().item_spacing.x);
+                        let layout = if stacked {
+                            egui::Layout::top_down(egui::Align::Min)
+                        } else {
+                            egui::Layout::left_to_right(egui::Align::Min)
+                        };
+                        ui.with_layout(layout, |ui| {
+                            if !stacked {
+                                ui.add_space(10.0);
+                            }
                             
                             ui.vertical(|ui| {
                                 ui.label("Pitch:");
                                 let mut pitch = mode.parent_split_direction.x;
-                                widgets::circular_slider_float(
+                                let response = widgets::circular_slider_float(
                                     ui,
                                     &mut pitch,
                                     -180.0,
                                     180.0,
                                     radius,
                                     self.widget_demo_state.enable_snapping,
+                                    self.widget_demo_state.dial_center_zero,
                                 );
                                 mode.parent_split_direction.x = pitch;
+                                if response.changed()
+                                    && let Some(yaw) = self.widget_demo_state.split_link.yaw_for(pitch, self.widget_demo_state.split_link_offset)
+                                {
+                                    mode.parent_split_direction.y = yaw;
+                                }
                             });
                             
                             ui.vertical(|ui| {
                                 ui.label("Yaw:");
                                 let mut yaw = mode.parent_split_direction.y;
-                                widgets::circular_slider_float(
+                                let response = widgets::circular_slider_float(
                                     ui,
                                     &mut yaw,
                                     -180.0,
                                     180.0,
                                     radius,
                                     self.widget_demo_state.enable_snapping,
+                                    self.widget_demo_state.dial_center_zero,
                                 );
                                 mode.parent_split_direction.y = yaw;
+                                if response.changed()
+                                    && let Some(pitch) = self.widget_demo_state.split_link.pitch_for(yaw, self.widget_demo_state.split_link_offset)
+                                {
+                                    mode.parent_split_direction.x = pitch;
+                                }
                             });
                         });
                     }
@@ -346,68 +2136,147 @@ impl<'a> egui_dock::TabViewer for TabViewer<'a> {
                 egui::ScrollArea::vertical()
                     .auto_shrink([false, false])
                     .show(ui, |ui| {
-                    ui.checkbox(&mut self.widget_demo_state.qball_snapping, "Enable Snapping (11.25°)");
+                    ui.horizontal(|ui| {
+                        ui.checkbox(&mut self.widget_demo_state.qball_snapping, "Enable Snapping");
+                        ui.add_enabled_ui(self.widget_demo_state.qball_snapping, |ui| {
+                            egui::ComboBox::from_id_salt("qball_snap_degrees")
+                                .selected_text(format!("{}°", self.preferences.qball_snap_degrees))
+                                .width(60.0)
+                                .show_ui(ui, |ui| {
+                                    for degrees in widgets::GRID_SNAP_DEGREES {
+                                        ui.selectable_value(&mut self.preferences.qball_snap_degrees, degrees, format!("{}°", degrees));
+                                    }
+                                });
+                        });
+                        ui.checkbox(&mut self.widget_demo_state.qball_look_at, "Look-At Mode")
+                            .on_hover_text("Click or drag to point the child's X axis at a direction (hold Shift for the back hemisphere)");
+                    });
+                    ui.horizontal(|ui| {
+                        ui.label("Direction Snap:");
+                        let direction_snap = &mut self.widget_demo_state.qball_direction_snap;
+                        egui::ComboBox::from_id_salt("qball_direction_snap")
+                            .selected_text(direction_snap.map_or("Off", widgets::DirectionSet::label))
+                            .show_ui(ui, |ui| {
+                                ui.selectable_value(direction_snap, None, "Off");
+                                for set in widgets::DirectionSet::ALL {
+                                    ui.selectable_value(direction_snap, Some(set), set.label());
+                                }
+                            })
+                            .response
+                            .on_hover_text("Point the child's X axis at the nearest direction of a regular polyhedron when released");
+                    });
                     ui.add_space(10.0);
                     
-                    // Calculate responsive ball size
-                    let available_width = ui.available_width();
-                    let available_height = ui.available_height();
-                    
-                    // Reserve space for checkbox and coordinates
-                    let reserved_height = 120.0;
-                    let usable_height = available_height - reserved_height;
+                    // Same size for both balls; stacked when that makes them bigger (narrow panels)
+                    let (ball_radius, stacked) = quaternion_ball_layout(ui.available_size());
+                    let ball_container_width = ball_radius * QBALL_CONTAINER_SCALE;
+                    let qball_snap = self.widget_demo_state.qball_snap();
                     
-                    // Always side by side
-                    let ball_width = (available_width / 2.0) - 40.0;
-                    let max_size = ball_width.min(usable_height);
-                    let ball_radius = (max_size / 2.5).max(32.5).min(130.0);
+                    // Mode shown here: the pinned one, otherwise the selected one
+                    let selected_mode_idx = self.target_mode(tab);
                     
-                    let ball_container_width = ball_radius * 2.0 + 20.0;
-                    
-                    // Get current mode index
-                    let selected_mode_idx = self.current_genome.selected_mode_index as usize;
+                    // [ / ] (or - / +) step the focused child's target mode while the pointer is over the panel.
+                    // Unmodified keys only, so Ctrl+- / Ctrl++ still zoom, and never while a text field has focus.
+                    let mode_count = self.current_genome.genome.modes.len() as i32;
+                    if ui.ui_contains_pointer()
+                        && !ui.ctx().wants_keyboard_input()
+                        && let Some(mode) = self.current_genome.genome.modes.get_mut(selected_mode_idx)
+                    {
+                        let step = ui.input_mut(|i| {
+                            let none = egui::Modifiers::NONE;
+                            let down = i.consume_key(none, egui::Key::OpenBracket) | i.consume_key(none, egui::Key::Minus);
+                            let up = i.consume_key(none, egui::Key::CloseBracket)
+                                | i.consume_key(none, egui::Key::Plus)
+                                | i.consume_key(none, egui::Key::Equals);
+                            up as i32 - down as i32
+                        });
+                        if step != 0 {
+                            let child = self.widget_demo_state.qball_focused_child.of_mut(mode);
+                            child.mode_number = (child.mode_number + step).rem_euclid(mode_count);
+                        }
+                    }
                     
                     // Collect mode display data before mutable borrows
-                    let mode_display_data: Vec<(String, egui::Color32)> = self.current_genome.genome.modes.iter()
-                        .map(|m| {
-                            let color = m.color;
-                            let r = (color.x * 255.0) as u8;
-                            let g = (color.y * 255.0) as u8;
-                            let b = (color.z * 255.0) as u8;
-                            (m.name.clone(), egui::Color32::from_rgb(r, g, b))
-                        })
-                        .collect();
+                    let mode_display_data = mode_display_data(&self.current_genome.genome.modes);
                     
-                    // Display balls horizontally with coordinates directly below each ball
-                    ui.horizontal_top(|ui| {
-                        ui.add_space(10.0);
+                    // Display balls with coordinates directly below each ball
+                    let balls_layout = if stacked {
+                        egui::Layout::top_down(egui::Align::Center)
+                    } else {
+                        egui::Layout::left_to_right(egui::Align::Min)
+                    };
+                    ui.with_layout(balls_layout, |ui| {
+                        if !stacked {
+                            ui.add_space(10.0);
+                        }
                         
                         // Ball 1 (Child A) with mode dropdown below
                         ui.allocate_ui_with_layout(
                             egui::vec2(ball_container_width, 0.0),
                             egui::Layout::top_down(egui::Align::Center),
                             |ui| {
-                                ui.label("Child A");
+                                let focused = &mut self.widget_demo_state.qball_focused_child;
+                                if ui.selectable_label(*focused == ChildSlot::A, "Child A")
+                                    .on_hover_text("Focus for stepping the target mode with [ and ]")
+                                    .clicked()
+                                {
+                                    *focused = ChildSlot::A;
+                                }
                                 
                                 if selected_mode_idx >= self.current_genome.genome.modes.len() {
                                     return;
                                 }
                                 let mode = &mut self.current_genome.genome.modes[selected_mode_idx];
                                 
-                                widgets::quaternion_ball(
-                                    ui,
-                                    &mut mode.child_a.orientation,
-                                    &mut mode.child_a.x_axis_lat,
-                                    &mut mode.child_a.x_axis_lon,
-                                    &mut mode.child_a.y_axis_lat,
-                                    &mut mode.child_a.y_axis_lon,
-                                    &mut mode.child_a.z_axis_lat,
-                                    &mut mode.child_a.z_axis_lon,
-                                    ball_radius,
-                                    self.widget_demo_state.qball_snapping,
-                                    &mut self.widget_demo_state.qball1_locked_axis,
-                                    &mut self.widget_demo_state.qball1_initial_distance,
-                                );
+                                if self.widget_demo_state.qball_look_at {
+                                    let response = widgets::quaternion_ball_look_at(
+                                        ui,
+                                        &mut mode.child_a.orientation,
+                                        ball_radius,
+                                        qball_snap,
+                                    );
+                                    if response.changed() {
+                                        resync_child_lat_lon(&mut mode.child_a);
+                                    }
+                                    if response.is_pointer_button_down_on() {
+                                        self.widget_demo_state.qball_focused_child = ChildSlot::A;
+                                    }
+                                } else {
+                                    let axis_lock = &mut self.widget_demo_state.qball1_axis_lock;
+                                    if *axis_lock != -1 {
+                                        self.widget_demo_state.qball1_locked_axis = *axis_lock;
+                                    }
+                                    let response = widgets::quaternion_ball(
+                                        ui,
+                                        &mut mode.child_a.orientation,
+                                        &mut mode.child_a.x_axis_lat,
+                                        &mut mode.child_a.x_axis_lon,
+                                        &mut mode.child_a.y_axis_lat,
+                                        &mut mode.child_a.y_axis_lon,
+                                        &mut mode.child_a.z_axis_lat,
+                                        &mut mode.child_a.z_axis_lon,
+                                        ball_radius,
+                                        qball_snap,
+                                        &mut self.widget_demo_state.qball1_locked_axis,
+                                        &mut self.widget_demo_state.qball1_initial_distance,
+                                    );
+                                    if widgets::axis_lock_hotkeys(ui, &response, axis_lock) && *axis_lock == -1 {
+                                        // Hand control back to the drag-direction heuristic
+                                        self.widget_demo_state.qball1_locked_axis = -1;
+                                    }
+                                    if response.is_pointer_button_down_on() {
+                                        self.widget_demo_state.qball_focused_child = ChildSlot::A;
+                                    }
+                                    ui.small(format!("Axis lock: {}", widgets::axis_lock_label(*axis_lock)))
+                                        .on_hover_text("Hover the ball and press X/Y/Z to lock an axis, L to cycle");
+                                }
+                                
+                                if self.preferences.show_euler_angles
+                                    && widgets::euler_angles_edit(ui, "qball1", &mut mode.child_a.orientation)
+                                {
+                                    resync_child_lat_lon(&mut mode.child_a);
+                                    self.widget_demo_state.qball_focused_child = ChildSlot::A;
+                                }
                                 
                                 ui.add_space(5.0);
                                 
@@ -416,42 +2285,11 @@ impl<'a> egui_dock::TabViewer for TabViewer<'a> {
                                 
                                 // Mode label and dropdown for ball 1
                                 ui.label("Mode:");
-                                let child_a_mode_idx = mode.child_a.mode_number as usize;
-                                let mode_color = mode_display_data[child_a_mode_idx].1;
-                                let brightness = mode_color.r() as f32 * 0.299 + mode_color.g() as f32 * 0.587 + mode_color.b() as f32 * 0.114;
-                                let text_color = if brightness > 127.5 {
-                                    egui::Color32::BLACK
-                                } else {
-                                    egui::Color32::WHITE
-                                };
-                                egui::ComboBox::from_id_salt("qball1_mode")
-                                    .selected_text(
-                                        egui::RichText::new(&mode_display_data[child_a_mode_idx].0)
-                                            .color(text_color)
-                                            .background_color(mode_color)
-                                    )
-                                    .width(ball_container_width - 20.0)
-                                    .show_ui(ui, |ui| {
-                                        for (i, (mode_name, mode_color)) in mode_display_data.iter().enumerate() {
-                                            // Calculate brightness to determine text color
-                                            let brightness = mode_color.r() as f32 * 0.299 + mode_color.g() as f32 * 0.587 + mode_color.b() as f32 * 0.114;
-                                            let text_color = if brightness > 127.5 {
-                                                egui::Color32::BLACK
-                                            } else {
-                                                egui::Color32::WHITE
-                                            };
-
-                                            let mut current_mode = mode.child_a.mode_number as usize;
-                                            let _response = ui.selectable_value(
-                                                &mut current_mode,
-                                                i,
-                                                egui::RichText::new(mode_name).color(text_color).background_color(*mode_color)
-                                            );
-                                            if current_mode != mode.child_a.mode_number as usize {
-                                                mode.child_a.mode_number = current_mode as i32;
-                                            }
-                                        }
-                                    });
+                                let mut child_a_mode_idx = mode.child_a.mode_number.max(0) as usize;
+                                if widgets::mode_dropdown(ui, "qball1_mode", &mode_display_data, &mut child_a_mode_idx, Some(ball_container_width - 20.0)) {
+                                    mode.child_a.mode_number = child_a_mode_idx as i32;
+                                    self.widget_demo_state.qball_focused_child = ChildSlot::A;
+                                }
                             }
                         );
                         
@@ -460,27 +2298,68 @@ impl<'a> egui_dock::TabViewer for TabViewer<'a> {
                             egui::vec2(ball_container_width, 0.0),
                             egui::Layout::top_down(egui::Align::Center),
                             |ui| {
-                                ui.label("Child B");
+                                let focused = &mut self.widget_demo_state.qball_focused_child;
+                                if ui.selectable_label(*focused == ChildSlot::B, "Child B")
+                                    .on_hover_text("Focus for stepping the target mode with [ and ]")
+                                    .clicked()
+                                {
+                                    *focused = ChildSlot::B;
+                                }
                                 
                                 if selected_mode_idx >= self.current_genome.genome.modes.len() {
                                     return;
                                 }
                                 let mode = &mut self.current_genome.genome.modes[selected_mode_idx];
                                 
-                                widgets::quaternion_ball(
-                                    ui,
-                                    &mut mode.child_b.orientation,
-                                    &mut mode.child_b.x_axis_lat,
-                                    &mut mode.child_b.x_axis_lon,
-                                    &mut mode.child_b.y_axis_lat,
-                                    &mut mode.child_b.y_axis_lon,
-                                    &mut mode.child_b.z_axis_lat,
-                                    &mut mode.child_b.z_axis_lon,
-                                    ball_radius,
-                                    self.widget_demo_state.qball_snapping,
-                                    &mut self.widget_demo_state.qball2_locked_axis,
-                                    &mut self.widget_demo_state.qball2_initial_distance,
-                                );
+                                if self.widget_demo_state.qball_look_at {
+                                    let response = widgets::quaternion_ball_look_at(
+                                        ui,
+                                        &mut mode.child_b.orientation,
+                                        ball_radius,
+                                        qball_snap,
+                                    );
+                                    if response.changed() {
+                                        resync_child_lat_lon(&mut mode.child_b);
+                                    }
+                                    if response.is_pointer_button_down_on() {
+                                        self.widget_demo_state.qball_focused_child = ChildSlot::B;
+                                    }
+                                } else {
+                                    let axis_lock = &mut self.widget_demo_state.qball2_axis_lock;
+                                    if *axis_lock != -1 {
+                                        self.widget_demo_state.qball2_locked_axis = *axis_lock;
+                                    }
+                                    let response = widgets::quaternion_ball(
+                                        ui,
+                                        &mut mode.child_b.orientation,
+                                        &mut mode.child_b.x_axis_lat,
+                                        &mut mode.child_b.x_axis_lon,
+                                        &mut mode.child_b.y_axis_lat,
+                                        &mut mode.child_b.y_axis_lon,
+                                        &mut mode.child_b.z_axis_lat,
+                                        &mut mode.child_b.z_axis_lon,
+                                        ball_radius,
+                                        qball_snap,
+                                        &mut self.widget_demo_state.qball2_locked_axis,
+                                        &mut self.widget_demo_state.qball2_initial_distance,
+                                    );
+                                    if widgets::axis_lock_hotkeys(ui, &response, axis_lock) && *axis_lock == -1 {
+                                        // Hand control back to the drag-direction heuristic
+                                        self.widget_demo_state.qball2_locked_axis = -1;
+                                    }
+                                    if response.is_pointer_button_down_on() {
+                                        self.widget_demo_state.qball_focused_child = ChildSlot::B;
+                                    }
+                                    ui.small(format!("Axis lock: {}", widgets::axis_lock_label(*axis_lock)))
+                                        .on_hover_text("Hover the ball and press X/Y/Z to lock an axis, L to cycle");
+                                }
+                                
+                                if self.preferences.show_euler_angles
+                                    && widgets::euler_angles_edit(ui, "qball2", &mut mode.child_b.orientation)
+                                {
+                                    resync_child_lat_lon(&mut mode.child_b);
+                                    self.widget_demo_state.qball_focused_child = ChildSlot::B;
+                                }
                                 
                                 ui.add_space(5.0);
                                 
@@ -489,49 +2368,85 @@ impl<'a> egui_dock::TabViewer for TabViewer<'a> {
                                 
                                 // Mode label and dropdown for ball 2
                                 ui.label("Mode:");
-                                let child_b_mode_idx = mode.child_b.mode_number as usize;
-                                let mode_color = mode_display_data[child_b_mode_idx].1;
-                                let brightness = mode_color.r() as f32 * 0.299 + mode_color.g() as f32 * 0.587 + mode_color.b() as f32 * 0.114;
-                                let text_color = if brightness > 127.5 {
-                                    egui::Color32::BLACK
-                                } else {
-                                    egui::Color32::WHITE
-                                };
-                                egui::ComboBox::from_id_salt("qball2_mode")
-                                    .selected_text(
-                                        egui::RichText::new(&mode_display_data[child_b_mode_idx].0)
-                                            .color(text_color)
-                                            .background_color(mode_color)
-                                    )
-                                    .width(ball_container_width - 20.0)
-                                    .show_ui(ui, |ui| {
-                                        for (i, (mode_name, mode_color)) in mode_display_data.iter().enumerate() {
-                                            // Calculate brightness to determine text color
-                                            let brightness = mode_color.r() as f32 * 0.299 + mode_color.g() as f32 * 0.587 + mode_color.b() as f32 * 0.114;
-                                            let text_color = if brightness > 127.5 {
-                                                egui::Color32::BLACK
-                                            } else {
-                                                egui::Color32::WHITE
-                                            };
-
-                                            let mut current_mode = mode.child_b.mode_number as usize;
-                                            let _response = ui.selectable_value(
-                                                &mut current_mode,
-                                                i,
-                                                egui::RichText::new(mode_name).color(text_color).background_color(*mode_color)
-                                            );
-                                            if current_mode != mode.child_b.mode_number as usize {
-                                                mode.child_b.mode_number = current_mode as i32;
-                                            }
-                                        }
-                                    });
+                                let mut child_b_mode_idx = mode.child_b.mode_number.max(0) as usize;
+                                if widgets::mode_dropdown(ui, "qball2_mode", &mode_display_data, &mut child_b_mode_idx, Some(ball_container_width - 20.0)) {
+                                    mode.child_b.mode_number = child_b_mode_idx as i32;
+                                    self.widget_demo_state.qball_focused_child = ChildSlot::B;
+                                }
                             }
                         );
                     });
+                    
+                    ui.add_space(10.0);
+                    
+                    // Mirror child B from child A
+                    ui.horizontal(|ui| {
+                        let Some(mode) = self.current_genome.genome.modes.get_mut(selected_mode_idx) else {
+                            return;
+                        };
+                        if ui.button("Mirror B from A").on_hover_text("Set child B to child A reflected across the plane").clicked() {
+                            let plane = self.widget_demo_state.mirror_plane;
+                            mode.child_b.orientation = widgets::mirror_orientation(mode.child_a.orientation, plane.normal());
+                            resync_child_lat_lon(&mut mode.child_b);
+                            info!("Mirrored child B from child A across the {}", plane.label());
+                        }
+                        egui::ComboBox::from_id_salt("mirror_plane")
+                            .selected_text(self.widget_demo_state.mirror_plane.label())
+                            .show_ui(ui, |ui| {
+                                for plane in widgets::MirrorPlane::ALL {
+                                    ui.selectable_value(&mut self.widget_demo_state.mirror_plane, plane, plane.label());
+                                }
+                            });
+                    });
+                    
+                    // Copy or mirror an orientation from any mode's child into the focused child
+                    ui.horizontal(|ui| {
+                        let modes = &mut self.current_genome.genome.modes;
+                        if selected_mode_idx >= modes.len() {
+                            return;
+                        }
+                        let (source_mode, source_child) = &mut self.widget_demo_state.orientation_source;
+                        *source_mode = (*source_mode).min(modes.len() - 1);
+                        
+                        ui.label("From:");
+                        egui::ComboBox::from_id_salt("orientation_source_mode")
+                            .selected_text(&modes[*source_mode].name)
+                            .width(100.0)
+                            .show_ui(ui, |ui| {
+                                for (i, mode) in modes.iter().enumerate() {
+                                    ui.selectable_value(source_mode, i, &mode.name);
+                                }
+                            });
+                        egui::ComboBox::from_id_salt("orientation_source_child")
+                            .selected_text(source_child.label())
+                            .show_ui(ui, |ui| {
+                                for slot in ChildSlot::ALL {
+                                    ui.selectable_value(source_child, slot, slot.label());
+                                }
+                            });
+                        
+                        let target = self.widget_demo_state.qball_focused_child;
+                        let plane = self.widget_demo_state.mirror_plane;
+                        let copy = ui.button("Copy")
+                            .on_hover_text(format!("Set {} to this orientation", target.label()));
+                        let mirror = ui.button("Mirror")
+                            .on_hover_text(format!("Set {} to this orientation reflected across the {}", target.label(), plane.label()));
+                        let transfer = if copy.clicked() {
+                            Some(widgets::OrientationTransfer::Copy)
+                        } else if mirror.clicked() {
+                            Some(widgets::OrientationTransfer::Mirror(plane))
+                        } else {
+                            None
+                        };
+                        if let Some(transfer) = transfer {
+                            transfer_child_orientation(modes, (*source_mode, *source_child), (selected_mode_idx, target), transfer);
+                            info!("Set {} orientation from {} of {}", target.label(), source_child.label(), modes[*source_mode].name);
+                        }
+                    });
                 });
             }
             Panel::Modes => {
-                render_modes_panel(ui, self.current_genome, self.widget_demo_state);
+                render_modes_panel(ui, self.current_genome, self.widget_demo_state, self.file_dialogs);
             }
             Panel::NameTypeEditor => {
                 egui::ScrollArea::vertical()
@@ -542,28 +2457,19 @@ impl<'a> egui_dock::TabViewer for TabViewer<'a> {
                     // Three buttons at the top
                     ui.horizontal(|ui| {
                         if ui.button("Save Genome").clicked() {
-                            // Open save dialog
-                            if let Some(path) = rfd::FileDialog::new()
-                                .add_filter("JSON", &["json"])
-                                .set_file_name(&format!("{}.json", self.current_genome.genome.name))
-                                .save_file()
-                            {
-                                info!("Would save genome to: {:?}", path);
-                                // TODO: Implement actual save
-                            }
+                            request_genome_save(self.file_dialogs, &self.current_genome.genome.name);
                         }
                         if ui.button("Load Genome").clicked() {
-                            // Open load dialog
-                            if let Some(path) = rfd::FileDialog::new()
-                                .add_filter("JSON", &["json"])
-                                .pick_file()
-                            {
-                                info!("Would load genome from: {:?}", path);
-                                // TODO: Implement actual load
-                            }
+                            request_genome_load(self.file_dialogs);
                         }
                         if ui.button("Genome Graph").clicked() {
-                            // TODO: Implement genome graph
+                            self.widget_demo_state.genome_graph.open = true;
+                        }
+                        if ui.button("Raw JSON").on_hover_text("Read-only view of the genome file").clicked() {
+                            self.widget_demo_state.genome_json.open = true;
+                        }
+                        if ui.button("Compare Modes").clicked() {
+                            self.widget_demo_state.mode_compare.open = true;
                         }
                     });
                     
@@ -572,13 +2478,48 @@ impl<'a> egui_dock::TabViewer for TabViewer<'a> {
                     // Genome Name label and field on same line
                     ui.horizontal(|ui| {
                         ui.label("Genome Name:");
-                        ui.text_edit_singleline(&mut self.current_genome.genome.name);
+                        let genome = &mut self.current_genome.genome;
+                        if widgets::name_edit(ui, &mut genome.name).lost_focus() {
+                            genome.name = clean_name(&genome.name, UNTITLED_GENOME_NAME);
+                        }
+                    });
+                    
+                    // Seed used by Randomize (blank until the first randomize or set by hand)
+                    ui.horizontal(|ui| {
+                        ui.label("Seed:");
+                        let genome = &mut self.current_genome.genome;
+                        match genome.seed.as_mut() {
+                            Some(seed) => {
+                                ui.add(egui::DragValue::new(seed).speed(1.0));
+                                if ui.small_button("✖").on_hover_text("Clear seed").clicked() {
+                                    genome.seed = None;
+                                }
+                            }
+                            None => {
+                                if ui.small_button("Set").on_hover_text("Pick a seed without randomizing").clicked() {
+                                    genome.seed = Some(random::fresh_seed());
+                                }
+                            }
+                        }
+                        
+                        if ui.button("🎲 Randomize").on_hover_text("Regenerate all modes from the seed").clicked() {
+                            let seed = *genome.seed.get_or_insert_with(random::fresh_seed);
+                            random::randomize_genome(genome, seed);
+                            for mode in &mut genome.modes {
+                                resync_child_lat_lon(&mut mode.child_a);
+                                resync_child_lat_lon(&mut mode.child_b);
+                            }
+                            info!("Randomized genome with seed {}", seed);
+                        }
                     });
                     
+                    // Initial mode, the same setting as the marker in the Modes list
+                    self.initial_mode_control(ui, "name_type_initial_mode");
+                    
                     ui.add_space(4.0);
                     
-                    // Get current mode
-                    let selected_idx = self.current_genome.selected_mode_index as usize;
+                    // Mode shown here: the pinned one, otherwise the selected one
+                    let selected_idx = self.target_mode(tab);
                     if selected_idx >= self.current_genome.genome.modes.len() {
                         ui.label("No mode selected");
                         return;
@@ -588,11 +2529,10 @@ impl<'a> egui_dock::TabViewer for TabViewer<'a> {
                     // Type dropdown and checkbox on the same line
                     ui.horizontal(|ui| {
                         ui.label("Type:");
-                        let cell_types = ["Photocyte", "Phagocyte", "Flagellocyte", "Devorocyte", "Lipocyte"];
                         egui::ComboBox::from_id_salt("cell_type")
-                            .selected_text(cell_types[mode.cell_type as usize])
+                            .selected_text(CELL_TYPE_NAMES[mode.cell_type as usize])
                             .show_ui(ui, |ui| {
-                                for (i, type_name) in cell_types.iter().enumerate() {
+                                for (i, type_name) in CELL_TYPE_NAMES.iter().enumerate() {
                                     ui.selectable_value(&mut mode.cell_type, i as i32, *type_name);
                                 }
                             });
@@ -609,110 +2549,70 @@ impl<'a> egui_dock::TabViewer for TabViewer<'a> {
                     ui.set_width(ui.available_width());
                     ui.add_space(10.0);
                     
-                    // Get current mode
-                    let selected_idx = self.current_genome.selected_mode_index as usize;
+                    // Mode shown here: the pinned one, otherwise the selected one
+                    let selected_idx = self.target_mode(tab);
                     if selected_idx >= self.current_genome.genome.modes.len() {
                         ui.label("No mode selected");
                         return;
                     }
                     let mode = &mut self.current_genome.genome.modes[selected_idx];
                     
-                    // Adhesion Can Break checkbox
-                    ui.checkbox(&mut mode.adhesion_settings.can_break, "Adhesion Can Break");
-                    
-                    // Adhesion Break Force (0.1 to 100.0)
-                    ui.label("Adhesion Break Force:");
-                    ui.horizontal(|ui| {
-                        let available = ui.available_width();
-                        let slider_width = if available > 80.0 { available - 70.0 } else { 50.0 };
-                        ui.style_mut().spacing.slider_width = slider_width;
-                        ui.add(egui::Slider::new(&mut mode.adhesion_settings.break_force, 0.1..=100.0).show_value(false));
-                        ui.add(egui::DragValue::new(&mut mode.adhesion_settings.break_force).speed(0.1).range(0.1..=100.0));
-                    });
-                    
-                    // Adhesion Rest Length (0.5 to 5.0)
-                    ui.label("Adhesion Rest Length:");
-                    ui.horizontal(|ui| {
-                        let available = ui.available_width();
-                        let slider_width = if available > 80.0 { available - 70.0 } else { 50.0 };
-                        ui.style_mut().spacing.slider_width = slider_width;
-                        ui.add(egui::Slider::new(&mut mode.adhesion_settings.rest_length, 0.5..=5.0).show_value(false));
-                        ui.add(egui::DragValue::new(&mut mode.adhesion_settings.rest_length).speed(0.01).range(0.5..=5.0));
-                    });
-                    
-                    // Linear Spring Stiffness (0.1 to 500.0)
-                    ui.label("Linear Spring Stiffness:");
-                    ui.horizontal(|ui| {
-                        let available = ui.available_width();
-                        let slider_width = if available > 80.0 { available - 70.0 } else { 50.0 };
-                        ui.style_mut().spacing.slider_width = slider_width;
-                        ui.add(egui::Slider::new(&mut mode.adhesion_settings.linear_spring_stiffness, 0.1..=500.0).show_value(false));
-                        ui.add(egui::DragValue::new(&mut mode.adhesion_settings.linear_spring_stiffness).speed(0.1).range(0.1..=500.0));
-                    });
-                    
-                    // Linear Spring Damping (0.0 to 10.0)
-                    ui.label("Linear Spring Damping:");
-                    ui.horizontal(|ui| {
-                        let available = ui.available_width();
-                        let slider_width = if available > 80.0 { available - 70.0 } else { 50.0 };
-                        ui.style_mut().spacing.slider_width = slider_width;
-                        ui.add(egui::Slider::new(&mut mode.adhesion_settings.linear_spring_damping, 0.0..=10.0).show_value(false));
-                        ui.add(egui::DragValue::new(&mut mode.adhesion_settings.linear_spring_damping).speed(0.01).range(0.0..=10.0));
-                    });
-                    
-                    // Orientation Spring Stiffness (0.1 to 100.0)
-                    ui.label("Orientation Spring Stiffness:");
-                    ui.horizontal(|ui| {
-                        let available = ui.available_width();
-                        let slider_width = if available > 80.0 { available - 70.0 } else { 50.0 };
-                        ui.style_mut().spacing.slider_width = slider_width;
-                        ui.add(egui::Slider::new(&mut mode.adhesion_settings.orientation_spring_stiffness, 0.1..=100.0).show_value(false));
-                        ui.add(egui::DragValue::new(&mut mode.adhesion_settings.orientation_spring_stiffness).speed(0.1).range(0.1..=100.0));
-                    });
-                    
-                    // Orientation Spring Damping (0.0 to 10.0)
-                    ui.label("Orientation Spring Damping:");
-                    ui.horizontal(|ui| {
-                        let available = ui.available_width();
-                        let slider_width = if available > 80.0 { available - 70.0 } else { 50.0 };
-                        ui.style_mut().spacing.slider_width = slider_width;
-                        ui.add(egui::Slider::new(&mut mode.adhesion_settings.orientation_spring_damping, 0.0..=10.0).show_value(false));
-                        ui.add(egui::DragValue::new(&mut mode.adhesion_settings.orientation_spring_damping).speed(0.01).range(0.0..=10.0));
-                    });
-                    
-                    // Max Angular Deviation (0.0 to 180.0)
-                    ui.label("Max Angular Deviation:");
-                    ui.horizontal(|ui| {
-                        let available = ui.available_width();
-                        let slider_width = if available > 80.0 { available - 70.0 } else { 50.0 };
-                        ui.style_mut().spacing.slider_width = slider_width;
-                        ui.add(egui::Slider::new(&mut mode.adhesion_settings.max_angular_deviation, 0.0..=180.0).show_value(false));
-                        ui.add(egui::DragValue::new(&mut mode.adhesion_settings.max_angular_deviation).speed(0.1).range(0.0..=180.0));
-                    });
+                    // Master toggle - the settings below only apply to adhesions this mode creates
+                    ui.checkbox(&mut mode.parent_make_adhesion, "Make Adhesion");
+                    if !mode.parent_make_adhesion {
+                        ui.label(egui::RichText::new("This mode doesn't make adhesions, so these settings have no effect.").weak());
+                    }
+                    ui.add_space(6.0);
                     
-                    ui.add_space(10.0);
+                    section_toggle_buttons(ui, self.preferences, &ADHESION_SECTIONS);
                     
-                    // Enable Twist Constraint checkbox
-                    ui.checkbox(&mut mode.adhesion_settings.enable_twist_constraint, "Enable Twist Constraint");
+                    ui.add_enabled_ui(mode.parent_make_adhesion, |ui| {
+                        settings_section(ui, self.preferences, ADHESION_SECTIONS[0], |ui| {
+                            // Adhesion Can Break checkbox
+                            ui.checkbox(&mut mode.adhesion_settings.can_break, "Adhesion Can Break");
+                        
+                            // Adhesion Break Force (0.1 to 100.0)
+                            widgets::labeled_slider(ui, "Adhesion Break Force", Detail::Basic, &mut mode.adhesion_settings.break_force, ranges::BREAK_FORCE, 0.5, "");
+                        });
                     
-                    // Twist Constraint Stiffness (0.0 to 2.0)
-                    ui.label("Twist Constraint Stiffness:");
-                    ui.horizontal(|ui| {
-                        let available = ui.available_width();
-                        let slider_width = if available > 80.0 { available - 70.0 } else { 50.0 };
-                        ui.style_mut().spacing.slider_width = slider_width;
-                        ui.add(egui::Slider::new(&mut mode.adhesion_settings.twist_constraint_stiffness, 0.0..=2.0).show_value(false));
-                        ui.add(egui::DragValue::new(&mut mode.adhesion_settings.twist_constraint_stiffness).speed(0.01).range(0.0..=2.0));
-                    });
+                        settings_section(ui, self.preferences, ADHESION_SECTIONS[1], |ui| {
+                            // Adhesion Rest Length (0.5 to 5.0)
+                            widgets::labeled_slider(ui, "Adhesion Rest Length", Detail::Basic, &mut mode.adhesion_settings.rest_length, ranges::REST_LENGTH, 0.05, "");
+                        
+                            // Linear Spring Stiffness (0.1 to 500.0)
+                            widgets::labeled_slider(ui, "Linear Spring Stiffness", Detail::Basic, &mut mode.adhesion_settings.linear_spring_stiffness, ranges::LINEAR_SPRING_STIFFNESS, 1.0, "");
+                        
+                            // Linear Spring Damping (0.0 to 10.0)
+                            widgets::labeled_slider(ui, "Linear Spring Damping", Detail::Basic, &mut mode.adhesion_settings.linear_spring_damping, ranges::SPRING_DAMPING, 0.05, "");
+                            spring_health_warning(ui, mode.adhesion_settings.linear_spring_stiffness, mode.adhesion_settings.linear_spring_damping, mode.split_mass);
+                        
+                            // Orientation Spring Stiffness (0.1 to 100.0)
+                            widgets::labeled_slider(ui, "Orientation Spring Stiffness", Detail::Basic, &mut mode.adhesion_settings.orientation_spring_stiffness, ranges::ORIENTATION_SPRING_STIFFNESS, 0.5, "");
+                        
+                            // Orientation Spring Damping (0.0 to 10.0)
+                            widgets::labeled_slider(ui, "Orientation Spring Damping", Detail::Basic, &mut mode.adhesion_settings.orientation_spring_damping, ranges::SPRING_DAMPING, 0.05, "");
+                            spring_health_warning(ui, mode.adhesion_settings.orientation_spring_stiffness, mode.adhesion_settings.orientation_spring_damping, mode.split_mass);
+                        
+                            // Max Angular Deviation (0.0 to 180.0)
+                            widgets::labeled_slider(ui, "Max Angular Deviation", Detail::Advanced, &mut mode.adhesion_settings.max_angular_deviation, ranges::MAX_ANGULAR_DEVIATION, 1.0, "");
+                        });
                     
-                    // Twist Constraint Damping (0.0 to 10.0)
-                    ui.label("Twist Constraint Damping:");
-                    ui.horizontal(|ui| {
-                        let available = ui.available_width();
-                        let slider_width = if available > 80.0 { available - 70.0 } else { 50.0 };
-                        ui.style_mut().spacing.slider_width = slider_width;
-                        ui.add(egui::Slider::new(&mut mode.adhesion_settings.twist_constraint_damping, 0.0..=10.0).show_value(false));
-                        ui.add(egui::DragValue::new(&mut mode.adhesion_settings.twist_constraint_damping).speed(0.01).range(0.0..=10.0));
+                        // The whole twist group is advanced
+                        if !widgets::show_advanced(ui) {
+                            ui.label(egui::RichText::new("Twist constraint hidden; turn on advanced parameters in Settings to edit it.").weak());
+                            return;
+                        }
+                        settings_section(ui, self.preferences, ADHESION_SECTIONS[2], |ui| {
+                            // Enable Twist Constraint checkbox
+                            ui.checkbox(&mut mode.adhesion_settings.enable_twist_constraint, "Enable Twist Constraint");
+                        
+                            // Twist Constraint Stiffness (0.0 to 2.0)
+                            widgets::labeled_slider(ui, "Twist Constraint Stiffness", Detail::Advanced, &mut mode.adhesion_settings.twist_constraint_stiffness, ranges::TWIST_CONSTRAINT_STIFFNESS, 0.01, "");
+                        
+                            // Twist Constraint Damping (0.0 to 10.0)
+                            widgets::labeled_slider(ui, "Twist Constraint Damping", Detail::Advanced, &mut mode.adhesion_settings.twist_constraint_damping, ranges::SPRING_DAMPING, 0.05, "");
+                            spring_health_warning(ui, mode.adhesion_settings.twist_constraint_stiffness, mode.adhesion_settings.twist_constraint_damping, mode.split_mass);
+                        });
                     });
                 });
             }
@@ -724,77 +2624,41 @@ impl<'a> egui_dock::TabViewer for TabViewer<'a> {
                     ui.set_width(ui.available_width());
                     ui.add_space(10.0);
                     
-                    // Get current mode
-                    let selected_idx = self.current_genome.selected_mode_index as usize;
+                    // Mode shown here: the pinned one, otherwise the selected one
+                    let selected_idx = self.target_mode(tab);
                     if selected_idx >= self.current_genome.genome.modes.len() {
                         ui.label("No mode selected");
                         return;
                     }
                     let mode = &mut self.current_genome.genome.modes[selected_idx];
                     
-                    // Split Mass (1.0 to 3.0)
-                    ui.label("Split Mass:");
-                    ui.horizontal(|ui| {
-                        let available = ui.available_width();
-                        let slider_width = if available > 80.0 { available - 70.0 } else { 50.0 };
-                        ui.style_mut().spacing.slider_width = slider_width;
-                        ui.add(egui::Slider::new(&mut mode.split_mass, 1.0..=3.0).show_value(false));
-                        ui.add(egui::DragValue::new(&mut mode.split_mass).speed(0.01).range(1.0..=3.0));
-                    });
-                    
-                    // Split Interval (1.0 to 60.0 seconds)
-                    ui.label("Split Interval:");
-                    ui.horizontal(|ui| {
-                        let available = ui.available_width();
-                        let slider_width = if available > 80.0 { available - 70.0 } else { 50.0 };
-                        ui.style_mut().spacing.slider_width = slider_width;
-                        ui.add(egui::Slider::new(&mut mode.split_interval, 1.0..=60.0).show_value(false));
-                        ui.add(egui::DragValue::new(&mut mode.split_interval).speed(0.1).range(1.0..=60.0).suffix("s"));
-                    });
-                    
-                    // Nutrient Priority (0.1 to 10.0)
-                    ui.label("Nutrient Priority:");
-                    ui.horizontal(|ui| {
-                        let available = ui.available_width();
-                        let slider_width = if available > 80.0 { available - 70.0 } else { 50.0 };
-                        ui.style_mut().spacing.slider_width = slider_width;
-                        ui.add(egui::Slider::new(&mut mode.nutrient_priority, 0.1..=10.0).show_value(false));
-                        ui.add(egui::DragValue::new(&mut mode.nutrient_priority).speed(0.01).range(0.1..=10.0));
-                    });
-                    
-                    // Prioritize When Low checkbox
-                    ui.checkbox(&mut mode.prioritize_when_low, "Prioritize When Low");
+                    section_toggle_buttons(ui, self.preferences, &PARENT_SECTIONS);
                     
-                    ui.add_space(10.0);
-                    
-                    // Max Connections (0 to 20)
-                    ui.label("Max Connections:");
-                    ui.horizontal(|ui| {
-                        let available = ui.available_width();
-                        let slider_width = if available > 80.0 { available - 70.0 } else { 50.0 };
-                        ui.style_mut().spacing.slider_width = slider_width;
-                        ui.add(egui::Slider::new(&mut mode.max_adhesions, 0..=20).show_value(false));
-                        ui.add(egui::DragValue::new(&mut mode.max_adhesions).speed(1).range(0..=20));
+                    settings_section(ui, self.preferences, PARENT_SECTIONS[0], |ui| {
+                        // Split Mass (1.0 to 3.0)
+                        widgets::labeled_slider(ui, "Split Mass", Detail::Basic, &mut mode.split_mass, ranges::SPLIT_MASS, 0.01, "");
+                        
+                        // Split Interval (1.0 to 60.0 seconds)
+                        widgets::labeled_slider(ui, "Split Interval", Detail::Basic, &mut mode.split_interval, ranges::SPLIT_INTERVAL, 0.1, "s");
+                        
+                        // Max Splits (-1 to 20, where -1 = infinite)
+                        widgets::labeled_slider(ui, "Max Splits", Detail::Basic, &mut mode.max_splits, ranges::MAX_SPLITS, 1.0, "");
                     });
                     
-                    // Min Connections (0 to 20)
-                    ui.label("Min Connections:");
-                    ui.horizontal(|ui| {
-                        let available = ui.available_width();
-                        let slider_width = if available > 80.0 { available - 70.0 } else { 50.0 };
-                        ui.style_mut().spacing.slider_width = slider_width;
-                        ui.add(egui::Slider::new(&mut mode.min_adhesions, 0..=20).show_value(false));
-                        ui.add(egui::DragValue::new(&mut mode.min_adhesions).speed(1).range(0..=20));
+                    settings_section(ui, self.preferences, PARENT_SECTIONS[1], |ui| {
+                        // Nutrient Priority (0.1 to 10.0)
+                        widgets::labeled_slider(ui, "Nutrient Priority", Detail::Basic, &mut mode.nutrient_priority, ranges::NUTRIENT_PRIORITY, 0.05, "");
+                        
+                        // Prioritize When Low checkbox
+                        ui.checkbox(&mut mode.prioritize_when_low, "Prioritize When Low");
                     });
                     
-                    // Max Splits (-1 to 20, where -1 = infinite)
-                    ui.label("Max Splits:");
-                    ui.horizontal(|ui| {
-                        let available = ui.available_width();
-                        let slider_width = if available > 80.0 { available - 70.0 } else { 50.0 };
-                        ui.style_mut().spacing.slider_width = slider_width;
-                        ui.add(egui::Slider::new(&mut mode.max_splits, -1..=20).show_value(false));
-                        ui.add(egui::DragValue::new(&mut mode.max_splits).speed(0.1).range(-1.0..=20.0));
+                    settings_section(ui, self.preferences, PARENT_SECTIONS[2], |ui| {
+                        // Max Connections (0 to 20)
+                        widgets::labeled_slider(ui, "Max Connections", Detail::Basic, &mut mode.max_adhesions, ranges::CONNECTIONS, 1.0, "");
+                        
+                        // Min Connections (0 to 20)
+                        widgets::labeled_slider(ui, "Min Connections", Detail::Advanced, &mut mode.min_adhesions, ranges::CONNECTIONS, 1.0, "");
                     });
                 });
             }
@@ -806,112 +2670,477 @@ impl<'a> egui_dock::TabViewer for TabViewer<'a> {
                     
                     ui.horizontal(|ui| {
                         ui.label("Time:");
-                        
-                        let available = ui.available_width();
-                        let slider_width = if available > 80.0 { available - 70.0 } else { 50.0 };
-                        ui.style_mut().spacing.slider_width = slider_width;
-                        ui.add(egui::Slider::new(&mut self.widget_demo_state.time_value, 0.0..=100.0).show_value(false));
-                        ui.add(egui::DragValue::new(&mut self.widget_demo_state.time_value).speed(0.1).range(0.0..=100.0));
+                        widgets::slider_row(ui, &mut self.widget_demo_state.time_value, 0.0..=100.0, 0.5, "");
                     });
                 });
             }
         }
+        if let Some(idx) = staged_mode {
+            self.end_staged_edit(idx);
+        }
+        if let Some((idx, before)) = slider_edit {
+            self.record_slider_edit(idx, before);
+        }
+    }
+
+    fn context_menu(
+        &mut self,
+        ui: &mut egui::Ui,
+        tab: &mut Self::Tab,
+        _surface: egui_dock::SurfaceIndex,
+        _node: egui_dock::NodeIndex,
+    ) {
+        let label = if self.soloed.as_ref() == Some(&tab.panel) { "Exit Solo" } else { "Solo" };
+        if ui.add(egui::Button::new(label).shortcut_text(SOLO_SHORTCUT_TEXT)).clicked() {
+            *self.solo_request = Some(tab.clone());
+            ui.close();
+        }
+
+        if tab.panel.shows_mode() {
+            let pinned = tab.pinned_mode;
+            let target = self.target_mode(tab);
+            let Some(mode_name) = self.current_genome.genome.modes.get(target).map(|mode| mode.name.clone()) else {
+                return;
+            };
+            if pinned.is_some() {
+                if ui.button(format!("Unpin from {}", mode_name)).on_hover_text("Follow the selected mode again").clicked() {
+                    tab.pinned_mode = None;
+                    ui.close();
+                }
+            } else if ui.button(format!("Pin to {}", mode_name)).on_hover_text("Keep showing this mode while selecting others").clicked() {
+                tab.pinned_mode = Some(target);
+                ui.close();
+            }
+        }
     }
 
     fn is_placeholder(&self, tab: &Self::Tab) -> bool {
         // Placeholder panels hold space but don't show tabs or allow dragging
-        tab.is_placeholder()
+        tab.panel.is_placeholder()
     }
 
     fn is_viewport(&self, tab: &Self::Tab) -> bool {
         // Viewport is a special panel for 3D rendering
-        matches!(tab, Panel::Viewport)
+        tab.panel == Panel::Viewport
     }
 
     fn clear_background(&self, tab: &Self::Tab) -> bool {
         // Return false for viewport to skip drawing background (make it transparent)
         // Return true for other panels to draw the background
-        !matches!(tab, Panel::Viewport)
+        tab.panel != Panel::Viewport
+    }
+
+    fn on_close(&mut self, tab: &mut Self::Tab) -> OnCloseResponse {
+        // Closed after the dock is drawn, through DockResource so the tab can be reopened
+        if self.last_essential_panels.contains(&tab.panel) {
+            *self.close_request = Some(tab.panel.clone());
+        } else {
+            self.closed_tabs.push(tab.clone());
+        }
+        OnCloseResponse::Ignore
     }
 
     fn is_closeable(&self, tab: &Self::Tab) -> bool {
         // Only non-placeholder, non-viewport panels can be closed
-        !tab.is_placeholder() && !self.is_viewport(tab)
+        !tab.panel.is_placeholder() && !self.is_viewport(tab)
     }
 
     fn allowed_in_windows(&self, tab: &mut Self::Tab) -> bool {
         // Only non-placeholder, non-viewport panels can be ejected to floating windows
-        !tab.is_placeholder() && !self.is_viewport(tab)
+        !tab.panel.is_placeholder() && !self.is_viewport(tab)
     }
     
     fn min_fraction(&self, tab: &Self::Tab) -> Option<f32> {
         // Bottom, left, and right panels should have the same minimum size (about 32px on a 720p window)
-        match tab {
+        match tab.panel {
             Panel::BottomPanel | Panel::LeftPanel | Panel::RightPanel => Some(0.045),
-            Panel::Modes => None, // Disable minimum to debug the actual issue
+            Panel::Modes => None, // Held open by the pixel minimum on its split, see apply_panel_minimums
             _ => None,
         }
     }
 }
 
-fn render_modes_panel(ui: &mut egui::Ui, current_genome: &mut CurrentGenome, widget_demo_state: &mut WidgetDemoState) {
-    // Handle rename dialog (outside scroll area)
-    let mut rename_confirmed = false;
-    let mut rename_cancelled = false;
-
-    if let Some(_rename_idx) = widget_demo_state.renaming_mode {
-        egui::Window::new("Rename Mode")
-            .collapsible(false)
-            .resizable(false)
-            .show(ui.ctx(), |ui| {
-                ui.label("Mode Name:");
-                let response = ui.text_edit_singleline(&mut widget_demo_state.rename_buffer);
-
-                if response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
-                    rename_confirmed = true;
-                }
+/// Seconds field that snaps anything between 0 and the minimum up to the minimum
+fn autosave_interval_field(ui: &mut egui::Ui, secs: &mut f32) {
+    let response = ui.add(egui::DragValue::new(secs).speed(0.5).range(0.0..=MAX_AUTOSAVE_SECS).suffix("s"));
+    if response.changed() && *secs > 0.0 && *secs < MIN_AUTOSAVE_SECS {
+        *secs = MIN_AUTOSAVE_SECS;
+    }
+}
 
-                ui.horizontal(|ui| {
-                    if ui.button("OK").clicked() {
-                        rename_confirmed = true;
+fn render_assets_panel(
+    ui: &mut egui::Ui,
+    asset_browser: &mut AssetBrowser,
+    current_genome: &mut CurrentGenome,
+    widget_demo_state: &mut WidgetDemoState,
+    toasts: &mut Toasts,
+    file_dialogs: &mut FileDialogs,
+) {
+    // Directory controls (outside scroll area)
+    ui.horizontal(|ui| {
+        if ui.button("Choose Folder...").clicked() {
+            file_dialogs.request(FileRequest {
+                target: FileTarget::AssetFolder,
+                kind: PickKind::Folder,
+                title: "Choose Asset Folder",
+                filter: None,
+                file_name: None,
+            });
+        }
+        if ui.add_enabled(asset_browser.directory.is_some(), egui::Button::new("⟳"))
+            .on_hover_text("Rescan folder")
+            .clicked()
+        {
+            asset_browser.rescan();
+        }
+    });
+
+    let Some(directory) = &asset_browser.directory else {
+        ui.label("Choose a project folder to browse its genomes (.json)");
+        return;
+    };
+    ui.weak(directory.display().to_string());
+    if let Some(err) = &asset_browser.scan_error {
+        ui.colored_label(ui.visuals().error_fg_color, format!("Could not read folder: {}", err));
+    }
+    ui.separator();
+
+    let mut load_request = None;
+    egui::ScrollArea::vertical()
+        .auto_shrink([false, false])
+        .show(ui, |ui| {
+        if asset_browser.entries.is_empty() {
+            ui.label("No genomes in this folder");
+        }
+
+        for (i, entry) in asset_browser.entries.iter().enumerate() {
+            let response = ui.vertical(|ui| {
+                ui.label(format!("🧬 {}", entry.file_name()));
+
+                // Palette strip thumbnail
+                if !entry.palette.is_empty() {
+                    let (rect, _) = ui.allocate_exact_size(
+                        egui::vec2(ui.available_width(), 6.0),
+                        egui::Sense::hover(),
+                    );
+                    let swatch_width = rect.width() / entry.palette.len() as f32;
+                    for (j, color) in entry.palette.iter().enumerate() {
+                        let min = egui::pos2(rect.left() + swatch_width * j as f32, rect.top());
+                        let swatch = egui::Rect::from_min_size(min, egui::vec2(swatch_width, rect.height()));
+                        ui.painter().rect_filled(swatch, 0.0, *color);
                     }
-                    if ui.button("Cancel").clicked() {
-                        rename_cancelled = true;
+                }
+                if let Some(err) = &entry.error {
+                    ui.colored_label(ui.visuals().error_fg_color, "Unreadable genome").on_hover_text(err);
+                }
+            }).response.interact(egui::Sense::click());
+
+            let response = response.on_hover_text("Double-click to load");
+            if response.double_clicked() {
+                load_request = Some(i);
+            }
+            ui.add_space(2.0);
+        }
+    });
+
+    if let Some(i) = load_request {
+        let replacement = GenomeReplacement::File(asset_browser.entries[i].path.clone());
+        replace_genome(current_genome, widget_demo_state, toasts, replacement);
+    }
+}
+
+/// Name, type and favorites filter above the Modes list
+fn show_modes_filter(ui: &mut egui::Ui, filter: &mut ModeQuery) {
+    ui.horizontal(|ui| {
+        ui.add(egui::TextEdit::singleline(&mut filter.name_contains)
+            .hint_text("Filter modes")
+            .desired_width(ui.available_width() - 110.0));
+
+        let type_label = filter.cell_type.map_or("Any type", |cell_type| CELL_TYPE_NAMES[cell_type as usize]);
+        egui::ComboBox::from_id_salt("modes_filter_cell_type")
+            .selected_text(type_label)
+            .width(70.0)
+            .show_ui(ui, |ui| {
+                ui.selectable_value(&mut filter.cell_type, None, "Any type");
+                for (i, type_name) in CELL_TYPE_NAMES.iter().enumerate() {
+                    ui.selectable_value(&mut filter.cell_type, Some(i as i32), *type_name);
+                }
+            });
+
+        ui.toggle_value(&mut filter.favorites_only, "★")
+            .on_hover_text("Show favorites only");
+    });
+}
+
+fn render_library_search(
+    ui: &mut egui::Ui,
+    genome_library: &GenomeLibrary,
+    current_genome: &mut CurrentGenome,
+    widget_demo_state: &mut WidgetDemoState,
+    toasts: &mut Toasts,
+) {
+    let query = &mut widget_demo_state.library_query;
+    let duplicates = genome_library.duplicate_names();
+    if !duplicates.is_empty() {
+        ui.colored_label(
+            egui::Color32::YELLOW,
+            format!("⚠ Several library genomes share a name: {}", duplicates.join(", ")),
+        ).on_hover_text("Saving or exporting them can overwrite each other's files");
+        ui.add_space(4.0);
+    }
+
+    // Filters (outside scroll area)
+    egui::Grid::new("library_search_filters")
+        .num_columns(2)
+        .spacing([8.0, 4.0])
+        .show(ui, |ui| {
+        ui.label("Name contains:");
+        ui.text_edit_singleline(&mut query.name_contains);
+        ui.end_row();
+
+        let mut filter_type = query.cell_type.is_some();
+        ui.checkbox(&mut filter_type, "Cell type:");
+        let mut cell_type = query.cell_type.unwrap_or(0);
+        ui.add_enabled_ui(filter_type, |ui| {
+            egui::ComboBox::from_id_salt("library_search_cell_type")
+                .selected_text(CELL_TYPE_NAMES[cell_type as usize])
+                .show_ui(ui, |ui| {
+                    for (i, type_name) in CELL_TYPE_NAMES.iter().enumerate() {
+                        ui.selectable_value(&mut cell_type, i as i32, *type_name);
                     }
                 });
+        });
+        query.cell_type = filter_type.then_some(cell_type);
+        ui.end_row();
+
+        let mut filter_min = query.min_break_force.is_some();
+        ui.checkbox(&mut filter_min, "Break force ≥");
+        let mut min_force = query.min_break_force.unwrap_or(10.0);
+        ui.add_enabled(filter_min, egui::DragValue::new(&mut min_force).speed(0.1).range(0.1..=100.0));
+        query.min_break_force = filter_min.then_some(min_force);
+        ui.end_row();
+
+        let mut filter_max = query.max_break_force.is_some();
+        ui.checkbox(&mut filter_max, "Break force ≤");
+        let mut max_force = query.max_break_force.unwrap_or(10.0);
+        ui.add_enabled(filter_max, egui::DragValue::new(&mut max_force).speed(0.1).range(0.1..=100.0));
+        query.max_break_force = filter_max.then_some(max_force);
+        ui.end_row();
+
+        let mut filter_adhesion = query.makes_adhesion.is_some();
+        ui.checkbox(&mut filter_adhesion, "Make Adhesion:");
+        let mut makes_adhesion = query.makes_adhesion.unwrap_or(true);
+        ui.add_enabled(filter_adhesion, egui::Checkbox::without_text(&mut makes_adhesion));
+        query.makes_adhesion = filter_adhesion.then_some(makes_adhesion);
+        ui.end_row();
+
+        ui.checkbox(&mut query.favorites_only, "Favorites only");
+        ui.end_row();
+    });
+
+    if ui.small_button("Clear Filters").clicked() {
+        *query = ModeQuery::default();
+    }
 
-                // Auto-focus the text field
-                if !response.has_focus() {
-                    response.request_focus();
+    ui.separator();
+
+    let matches = search_genomes(&genome_library.genomes, query);
+    ui.label(format!("{} of {} genome(s) match", matches.len(), genome_library.genomes.len()));
+
+    let mut open_request = None;
+    egui::ScrollArea::vertical()
+        .auto_shrink([false, false])
+        .show(ui, |ui| {
+        for genome_match in &matches {
+            let genome = &genome_library.genomes[genome_match.genome_index];
+            ui.horizontal(|ui| {
+                if ui.small_button("Open").clicked() {
+                    open_request = Some((genome_match.genome_index, genome_match.mode_indices[0]));
                 }
-            });
+                ui.label(&genome.name);
+                ui.weak(format!("({} matching mode(s))", genome_match.mode_indices.len()));
+            }).response.on_hover_text(
+                genome_match.mode_indices.iter()
+                    .map(|&i| genome.modes[i].name.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            );
+        }
+    });
+
+    if let Some((genome_index, mode_index)) = open_request {
+        let genome = Box::new(genome_library.genomes[genome_index].clone());
+        replace_genome(current_genome, widget_demo_state, toasts, GenomeReplacement::Library { genome, mode_index });
     }
+}
 
-    if rename_confirmed {
-        if let Some(_rename_idx) = widget_demo_state.renaming_mode {
-            let trimmed = widget_demo_state.rename_buffer.trim();
-            if !trimmed.is_empty() && _rename_idx < current_genome.genome.modes.len() {
-                current_genome.genome.modes[_rename_idx].name = trimmed.to_string();
-                info!("Renamed mode {} to {}", _rename_idx, trimmed);
-            }
+/// Recompute a child's lat/lon readouts from its orientation after it was set directly
+fn resync_child_lat_lon(child: &mut ChildSettings) {
+    let [(x_lat, x_lon), (y_lat, y_lon), (z_lat, z_lon)] = widgets::axis_lat_lon_offsets(child.orientation);
+    child.x_axis_lat = x_lat;
+    child.x_axis_lon = x_lon;
+    child.y_axis_lat = y_lat;
+    child.y_axis_lon = y_lon;
+    child.z_axis_lat = z_lat;
+    child.z_axis_lon = z_lon;
+}
+
+/// Set the `target` child to the `source` child's orientation, copied or mirrored, with its
+/// axis lat/lon readouts updated to match. Both are (mode index, child) pairs.
+fn transfer_child_orientation(
+    modes: &mut [ModeSettings],
+    (source_mode, source_child): (usize, ChildSlot),
+    (target_mode, target_child): (usize, ChildSlot),
+    transfer: widgets::OrientationTransfer,
+) {
+    let reference = source_child.of(&modes[source_mode]).orientation;
+    let child = target_child.of_mut(&mut modes[target_mode]);
+    child.orientation = widgets::transfer_orientation(reference, transfer);
+    resync_child_lat_lon(child);
+}
+
+/// Save a single mode to `path`
+fn export_mode(mode: &ModeSettings, path: &std::path::Path, toasts: &mut Toasts) {
+    match mode.save_to_file(path) {
+        Ok(()) => {
+            info!("Exported mode {} to {:?}", mode.name, path);
+            toasts.info(format!("Exported {}", path.display()));
+        }
+        Err(err) => {
+            warn!("Failed to export mode to {:?}: {}", path, err);
+            toasts.error(format!("Could not export {}: {}", path.display(), err));
         }
-        widget_demo_state.renaming_mode = None;
-        widget_demo_state.rename_buffer.clear();
     }
+}
+
+/// Replace mode `idx` with one loaded from `path`, fitting its mode references to this genome
+fn import_mode(current_genome: &mut CurrentGenome, idx: usize, path: &std::path::Path, toasts: &mut Toasts) {
+    let mut mode = match ModeSettings::load_from_file(path) {
+        Ok(mode) => mode,
+        Err(err) => {
+            warn!("Failed to import mode from {:?}: {}", path, err);
+            toasts.error(format!("Could not import {}: {}", path.display(), err));
+            return;
+        }
+    };
+
+    let mode_count = current_genome.genome.modes.len();
+    if mode.clamp_mode_references(mode_count) {
+        toasts.info(format!("{}: child modes adjusted to fit this genome's {} modes", mode.name, mode_count));
+    }
+    info!("Imported mode {} into slot {} from {:?}", mode.name, idx, path);
+    toasts.info(format!("Imported {} into slot {}", mode.name, idx));
+    current_genome.genome.modes[idx] = mode;
+}
+
+/// Scroll distance (in points) that moves the mode selection by one when Alt+scrolling the list
+const MODE_SCROLL_STEP: f32 = 20.0;
+
+/// Range of the Modes list text scale that Ctrl+wheel moves within
+const MODES_TEXT_SCALE: std::ops::RangeInclusive<f32> = 0.6..=2.0;
 
-    if rename_cancelled {
-        widget_demo_state.renaming_mode = None;
-        widget_demo_state.rename_buffer.clear();
+fn render_modes_panel(
+    ui: &mut egui::Ui,
+    current_genome: &mut CurrentGenome,
+    widget_demo_state: &mut WidgetDemoState,
+    file_dialogs: &mut FileDialogs,
+) {
+    // Handle rename dialog (outside scroll area)
+    if let Some(rename_idx) = widget_demo_state.renaming_mode {
+        let result = widgets::modal_dialog(ui.ctx(), "Rename Mode", |ui| {
+            ui.label("Mode Name:");
+            Some(widgets::name_edit(ui, &mut widget_demo_state.rename_buffer))
+        });
+
+        match result {
+            widgets::DialogResult::Open => {}
+            widgets::DialogResult::Confirmed => {
+                if let Some(mode) = current_genome.genome.modes.get_mut(rename_idx) {
+                    // A blank name goes back to the one the mode was created with
+                    mode.name = clean_name(&widget_demo_state.rename_buffer, &mode.default_name);
+                    info!("Renamed mode {} to {}", rename_idx, mode.name);
+                }
+                widget_demo_state.renaming_mode = None;
+                widget_demo_state.rename_buffer.clear();
+            }
+            widgets::DialogResult::Cancelled => {
+                widget_demo_state.renaming_mode = None;
+                widget_demo_state.rename_buffer.clear();
+            }
+        }
     }
 
     // Draw buttons outside scroll area
-    let (copy_into_clicked, reset_clicked) = widgets::modes_buttons(
+    let (copy_into_clicked, reset_clicked, add_clicked) = widgets::modes_buttons(
         ui,
         current_genome.genome.modes.len(),
         current_genome.selected_mode_index as usize,
         current_genome.genome.initial_mode as usize,
     );
 
+    ui.horizontal(|ui| {
+        for layout in widgets::ModesLayout::ALL {
+            ui.selectable_value(&mut widget_demo_state.modes_layout, layout, layout.label());
+        }
+    });
+
+    show_modes_filter(ui, &mut widget_demo_state.modes_filter);
+
+    ui.menu_button("🎨 Recolor", |ui| {
+        for strategy in RecolorStrategy::ALL {
+            if ui.button(strategy.label()).clicked() {
+                current_genome.recolor(strategy);
+                info!("Recolored modes: {}", strategy.label());
+                ui.close();
+            }
+        }
+
+        let respread = ui.button("Respread Default Colors")
+            .on_hover_text("Space the generated colors evenly over the current modes, keeping hand-picked and pinned ones");
+        if respread.clicked() {
+            current_genome.respread_default_colors();
+            info!("Respread default mode colors");
+            ui.close();
+        }
+
+        ui.separator();
+
+        let selected_idx = current_genome.selected_mode_index as usize;
+        if let Some(mode) = current_genome.genome.modes.get_mut(selected_idx) {
+            ui.checkbox(&mut mode.color_pinned, "Pin Selected Color")
+                .on_hover_text("Pinned colors are kept when recoloring");
+        }
+    });
+
+    let selected_idx = current_genome.selected_mode_index as usize;
+    ui.menu_button("🔍 Find References", |ui| {
+        let references = current_genome.genome.referencing_modes(selected_idx);
+        if references.is_empty() {
+            ui.label("No other mode splits into this one");
+            return;
+        }
+
+        ui.label(format!("{} mode(s) split into this one:", references.len()));
+        egui::ScrollArea::vertical().max_height(300.0).show(ui, |ui| {
+            for idx in references {
+                let mode = &current_genome.genome.modes[idx];
+                let mut sides = Vec::new();
+                if mode.child_a.mode_number as usize == selected_idx {
+                    sides.push("A");
+                }
+                if mode.child_b.mode_number as usize == selected_idx {
+                    sides.push("B");
+                }
+                let label = format!("{} (child {})", mode.name, sides.join(", "));
+                if ui.button(label).clicked() {
+                    current_genome.selected_mode_index = idx as i32;
+                    info!("Selected mode changed to: {}", idx);
+                    ui.close();
+                }
+            }
+        });
+    });
+
     ui.separator();
 
     // Show instruction text if in copy into mode (also outside scroll area)
@@ -921,21 +3150,44 @@ fn render_modes_panel(ui: &mut egui::Ui, current_genome: &mut CurrentGenome, wid
     }
 
     // Convert modes to display format
-    let modes_display: Vec<(String, egui::Color32)> = current_genome.genome.modes.iter()
+    let modes_display: Vec<widgets::ModeListEntry> = current_genome.genome.modes.iter()
         .map(|m| {
             let color = m.color;
             let r = (color.x * 255.0) as u8;
             let g = (color.y * 255.0) as u8;
             let b = (color.z * 255.0) as u8;
-            (m.name.clone(), egui::Color32::from_rgb(r, g, b))
+            widgets::ModeListEntry {
+                name: m.name.clone(),
+                color: egui::Color32::from_rgb(r, g, b),
+                favorite: m.favorite,
+                shown: widget_demo_state.modes_filter.matches_mode(m),
+            }
         })
         .collect();
+    let hidden = modes_display.iter().filter(|entry| !entry.shown).count();
+    if hidden > 0 {
+        ui.weak(format!("{} of {} modes hidden by the filter", hidden, modes_display.len()));
+    }
+
+    // Drop a color picker left pointing at a mode that no longer exists
+    if widget_demo_state.color_picker_state.as_ref().is_some_and(|(idx, _)| *idx >= modes_display.len()) {
+        widget_demo_state.color_picker_state = None;
+    }
+
+    // Alt+wheel steps through modes below, so the list itself must not scroll meanwhile
+    let stepping = ui.input(|i| i.modifiers.alt);
 
     // Now create scroll area for the list
-    let (selection_changed, initial_changed, rename_idx, color_change) = egui::ScrollArea::vertical()
+    let scroll_output = egui::ScrollArea::vertical()
         .auto_shrink([false, false])
+        .scroll_source(egui::scroll_area::ScrollSource { mouse_wheel: !stepping, ..Default::default() })
         .show(ui, |ui| {
-        let available_width = ui.available_width();
+        let text_scale = widget_demo_state.modes_text_scale;
+        if text_scale != 1.0 {
+            for font in ui.style_mut().text_styles.values_mut() {
+                font.size *= text_scale;
+            }
+        }
 
         let mut selected_mode = current_genome.selected_mode_index as usize;
         let mut initial_mode = current_genome.genome.initial_mode as usize;
@@ -945,16 +3197,45 @@ fn render_modes_panel(ui: &mut egui::Ui, current_genome: &mut CurrentGenome, wid
             &modes_display,
             &mut selected_mode,
             &mut initial_mode,
-            available_width,
+            widget_demo_state.modes_layout,
             widget_demo_state.copy_into_dialog_open,
             &mut widget_demo_state.color_picker_state,
         );
         
         current_genome.selected_mode_index = selected_mode as i32;
-        current_genome.genome.initial_mode = initial_mode as i32;
+        if initial_mode as i32 != current_genome.genome.initial_mode {
+            current_genome.record_edit("Initial Mode");
+            current_genome.genome.initial_mode = initial_mode as i32;
+        }
         
         result
-    }).inner;
+    });
+    let (selection_changed, initial_changed, rename_idx, color_change, menu_action) = scroll_output.inner;
+
+    // Ctrl+wheel over the list scales its text; egui turns Ctrl+scroll into zoom, so the
+    // ScrollArea doesn't move meanwhile. Alt+wheel steps through modes instead.
+    if !widget_demo_state.copy_into_dialog_open && ui.rect_contains_pointer(scroll_output.inner_rect) {
+        let (zoom, scroll_y) = ui.input(|i| (i.zoom_delta(), i.raw_scroll_delta.y));
+        if zoom != 1.0 {
+            widget_demo_state.modes_text_scale = (widget_demo_state.modes_text_scale * zoom)
+                .clamp(*MODES_TEXT_SCALE.start(), *MODES_TEXT_SCALE.end());
+        }
+
+        if stepping && scroll_y != 0.0 {
+            widget_demo_state.mode_scroll_accumulator += scroll_y;
+        } else if !stepping {
+            widget_demo_state.mode_scroll_accumulator = 0.0;
+        }
+
+        let steps = (widget_demo_state.mode_scroll_accumulator / MODE_SCROLL_STEP).trunc();
+        if steps != 0.0 && !current_genome.genome.modes.is_empty() {
+            widget_demo_state.mode_scroll_accumulator -= steps * MODE_SCROLL_STEP;
+            let last = current_genome.genome.modes.len() as i32 - 1;
+            // Scrolling up (positive delta) moves towards the top of the list
+            current_genome.selected_mode_index = (current_genome.selected_mode_index - steps as i32).clamp(0, last);
+            info!("Selected mode changed to: {}", current_genome.selected_mode_index);
+        }
+    }
 
     if selection_changed {
         // If in copy into mode, this is the target selection
@@ -967,8 +3248,10 @@ fn render_modes_panel(ui: &mut egui::Ui, current_genome: &mut CurrentGenome, wid
                 // Copy all settings from source to target (including color, except name)
                 let source_mode = current_genome.genome.modes[source_idx].clone();
                 let target_name = current_genome.genome.modes[target_idx].name.clone();
+                let target_favorite = current_genome.genome.modes[target_idx].favorite;
                 current_genome.genome.modes[target_idx] = source_mode;
                 current_genome.genome.modes[target_idx].name = target_name;
+                current_genome.genome.modes[target_idx].favorite = target_favorite;
                 info!("Copied mode {} into mode {}", source_idx, target_idx);
             }
 
@@ -995,10 +3278,66 @@ fn render_modes_panel(ui: &mut egui::Ui, current_genome: &mut CurrentGenome, wid
             let g = new_color.g() as f32 / 255.0;
             let b = new_color.b() as f32 / 255.0;
             current_genome.genome.modes[idx].color = Vec3::new(r, g, b);
+            current_genome.genome.modes[idx].color_is_custom = true;
             info!("Changed color of mode {}", idx);
         }
     }
 
+    match menu_action {
+        Some(widgets::ModeMenuAction::Export(idx)) => file_dialogs.request(FileRequest {
+            target: FileTarget::ExportMode(idx),
+            kind: PickKind::SaveFile,
+            title: "Export Mode",
+            filter: Some(("JSON", &["json"])),
+            file_name: Some(export_file_name(&current_genome.genome.modes[idx].name, "json")),
+        }),
+        Some(widgets::ModeMenuAction::Import(idx)) => {
+            current_genome.selected_mode_index = idx as i32;
+            file_dialogs.request(FileRequest {
+                target: FileTarget::ImportMode(idx),
+                kind: PickKind::OpenFile,
+                title: "Import Mode",
+                filter: Some(("JSON", &["json"])),
+                file_name: None,
+            });
+        }
+        Some(widgets::ModeMenuAction::MoveUp(idx)) => {
+            // The menu only offers moves and deletes that are possible
+            current_genome.move_mode(idx, idx - 1);
+            info!("Moved mode {} up", idx);
+        }
+        Some(widgets::ModeMenuAction::MoveDown(idx)) => {
+            current_genome.move_mode(idx, idx + 1);
+            info!("Moved mode {} down", idx);
+        }
+        Some(widgets::ModeMenuAction::Delete(idx)) => {
+            current_genome.delete_mode(idx);
+            info!("Deleted mode {}", idx);
+        }
+        Some(widgets::ModeMenuAction::Compare(idx)) => {
+            widget_demo_state.mode_compare.other = idx;
+            widget_demo_state.mode_compare.open = true;
+        }
+        Some(widgets::ModeMenuAction::ToggleFavorite(idx)) => {
+            let mode = &mut current_genome.genome.modes[idx];
+            mode.favorite = !mode.favorite;
+            info!("{} mode {}", if mode.favorite { "Starred" } else { "Unstarred" }, idx);
+        }
+        Some(widgets::ModeMenuAction::ExportCard(idx)) => file_dialogs.request(FileRequest {
+            target: FileTarget::ExportModeCard(idx),
+            kind: PickKind::SaveFile,
+            title: "Export Mode Card",
+            filter: Some(("PNG image", &["png"])),
+            file_name: Some(export_file_name(&current_genome.genome.modes[idx].name, "png")),
+        }),
+        None => {}
+    }
+
+    if add_clicked {
+        current_genome.add_mode();
+        info!("Added mode {}", current_genome.selected_mode_index);
+    }
+
     // Handle copy into mode
     if copy_into_clicked {
         let selected_idx = current_genome.selected_mode_index as usize;
@@ -1013,15 +3352,56 @@ fn render_modes_panel(ui: &mut egui::Ui, current_genome: &mut CurrentGenome, wid
     if reset_clicked {
         let selected_idx = current_genome.selected_mode_index as usize;
         if selected_idx < current_genome.genome.modes.len() {
+            current_genome.back_up();
             // Reset to default values
             let name = current_genome.genome.modes[selected_idx].name.clone();
             let color = current_genome.genome.modes[selected_idx].color;
+            let favorite = current_genome.genome.modes[selected_idx].favorite;
+            let color_is_custom = current_genome.genome.modes[selected_idx].color_is_custom;
             current_genome.genome.modes[selected_idx] = ModeSettings::default();
             current_genome.genome.modes[selected_idx].name = name;
             current_genome.genome.modes[selected_idx].color = color;
+            current_genome.genome.modes[selected_idx].color_is_custom = color_is_custom;
+            current_genome.genome.modes[selected_idx].favorite = favorite;
             current_genome.genome.modes[selected_idx].child_a.mode_number = selected_idx as i32;
             current_genome.genome.modes[selected_idx].child_b.mode_number = selected_idx as i32;
             info!("Reset mode {}", selected_idx);
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn orientation_transfer_sets_the_target_child_from_another_mode() {
+        let mut modes: Vec<ModeSettings> = (0..2)
+            .map(|i| ModeSettings::new_self_splitting(i, format!("M {}", i)))
+            .collect();
+        let reference = Quat::from_euler(EulerRot::XYZ, 0.4, -1.1, 0.7);
+        modes[1].child_a.orientation = reference;
+        resync_child_lat_lon(&mut modes[1].child_a);
+        let untouched = modes[0].child_a.clone();
+
+        transfer_child_orientation(&mut modes, (1, ChildSlot::A), (0, ChildSlot::B), widgets::OrientationTransfer::Copy);
+        let copied = &modes[0].child_b;
+        assert!(copied.orientation.angle_between(reference) < 1e-6);
+        // The lat/lon readouts follow the new orientation instead of keeping the old one's
+        assert_eq!((copied.x_axis_lat, copied.x_axis_lon), (modes[1].child_a.x_axis_lat, modes[1].child_a.x_axis_lon));
+        assert_eq!((copied.z_axis_lat, copied.z_axis_lon), (modes[1].child_a.z_axis_lat, modes[1].child_a.z_axis_lon));
+        assert!(modes[0].child_a == untouched && modes[1].child_a.orientation == reference);
+
+        // Mirrored across the YZ plane: flipping x before and after gives the reference rotation
+        let mirror = widgets::OrientationTransfer::Mirror(widgets::MirrorPlane::YZ);
+        transfer_child_orientation(&mut modes, (1, ChildSlot::A), (0, ChildSlot::B), mirror);
+        let flip = |v: Vec3| Vec3::new(-v.x, v.y, v.z);
+        for v in [Vec3::X, Vec3::Y, Vec3::Z] {
+            assert!((modes[0].child_b.orientation * v - flip(reference * flip(v))).length() < 1e-5);
+        }
+
+        // Within one mode, a child can take its sibling's orientation
+        transfer_child_orientation(&mut modes, (0, ChildSlot::B), (0, ChildSlot::A), widgets::OrientationTransfer::Copy);
+        assert!(modes[0].child_a.orientation.angle_between(modes[0].child_b.orientation) < 1e-6);
+    }
+}
@@ -1,9 +1,21 @@
 use bevy::prelude::*;
 use bevy_egui::egui::{self, Ui, Response, Sense, Stroke, Pos2, Vec2 as EguiVec2};
 use std::f32::consts::PI;
+use std::ops::RangeInclusive;
+
+use crate::genome::MAX_NAME_CHARS;
+
+/// Extra distance (points) the pointer may stray past a circular slider's grab zone before the highlight drops
+const GRAB_ZONE_HYSTERESIS: f32 = 4.0;
+
+/// How long the circular slider's grab highlight takes to fade in/out
+const GRAB_HIGHLIGHT_FADE_SECS: f32 = 0.08;
 
 /// Circular slider for float values with angle snapping
 /// 
+/// With `center_zero`, zero is marked at the top and the arc is colored by the value's sign,
+/// for signed values where the direction matters.
+///
 /// Returns true if the value changed
 pub fn circular_slider_float(
     ui: &mut Ui,
@@ -12,6 +24,7 @@ pub fn circular_slider_float(
     v_max: f32,
     radius: f32,
     enable_snapping: bool,
+    center_zero: bool,
 ) -> Response {
     // Calculate container size based on radius
     let container_width = radius * 2.0 + 20.0;
@@ -29,7 +42,7 @@ pub fn circular_slider_float(
     
     // Get colors from theme
     let bg_color = ui.visuals().widgets.inactive.bg_fill;
-    let slider_color = ui.visuals().selection.bg_fill;
+    let slider_color = if center_zero { signed_arc_color(ui.visuals(), *value) } else { ui.visuals().selection.bg_fill };
     let slider_hovered_color = ui.visuals().widgets.hovered.bg_fill;
     
     // Check mouse position for grab zone
@@ -39,16 +52,25 @@ pub fn circular_slider_float(
     // Define grab zones
     let inner_radius = 15.0;
     let outer_radius = radius + 25.0;
-    let is_mouse_in_grab_zone = distance_from_center >= inner_radius
-        && distance_from_center <= outer_radius
+    
+    // Once highlighted, the zone grows by a few points before the highlight drops,
+    // so resting the pointer on the boundary doesn't strobe. Purely visual - dragging
+    // goes through `response` and is unaffected.
+    let highlight_id = response.id.with("grab_highlight");
+    let was_in_grab_zone = ui.data(|data| data.get_temp::<bool>(highlight_id)).unwrap_or(false);
+    let hysteresis = if was_in_grab_zone { GRAB_ZONE_HYSTERESIS } else { 0.0 };
+    let is_mouse_in_grab_zone = distance_from_center >= inner_radius - hysteresis
+        && distance_from_center <= outer_radius + hysteresis
         && response.hovered();
+    ui.data_mut(|data| data.insert_temp(highlight_id, is_mouse_in_grab_zone));
+    let highlight = ui.ctx().animate_bool_with_time(
+        response.id.with("grab_highlight_fade"),
+        is_mouse_in_grab_zone,
+        GRAB_HIGHLIGHT_FADE_SECS,
+    );
     
     // Draw background circle
-    let current_slider_color = if is_mouse_in_grab_zone {
-        slider_hovered_color
-    } else {
-        bg_color
-    };
+    let current_slider_color = bg_color.lerp_to_gamma(slider_hovered_color, highlight);
     
     ui.painter().circle_stroke(
         center,
@@ -56,15 +78,20 @@ pub fn circular_slider_float(
         Stroke::new(3.0, current_slider_color),
     );
     
+    if center_zero {
+        // Zero tick across the ring at the top, where both directions start
+        let top = center - EguiVec2::new(0.0, radius);
+        ui.painter().line_segment(
+            [top - EguiVec2::new(0.0, 7.0), top + EguiVec2::new(0.0, 7.0)],
+            Stroke::new(2.0, ui.visuals().strong_text_color()),
+        );
+    }
+    
     // Draw directional arc
     if value.abs() > 0.001 {
         let arc_thickness = 8.0;
         let num_segments = (radius * 0.5).max(32.0) as usize;
-        let current_arc_color = if is_mouse_in_grab_zone {
-            slider_hovered_color
-        } else {
-            slider_color
-        };
+        let current_arc_color = slider_color.lerp_to_gamma(slider_hovered_color, highlight);
         
         let start_angle = -PI / 2.0;
         let end_angle = start_angle + (*value / 180.0) * PI;
@@ -96,11 +123,7 @@ pub fn circular_slider_float(
         center.x + handle_angle.cos() * radius,
         center.y + handle_angle.sin() * radius,
     );
-    let handle_color = if is_mouse_in_grab_zone {
-        slider_hovered_color
-    } else {
-        slider_color
-    };
+    let handle_color = slider_color.lerp_to_gamma(slider_hovered_color, highlight);
     
     ui.painter().circle_filled(handle_pos, handle_radius, handle_color);
     
@@ -137,16 +160,27 @@ pub fn circular_slider_float(
         EguiVec2::new(text_input_width, text_input_height),
     );
     
+    // Text typed into the field is kept between frames; only the display is rounded, so the
+    // value is only written back if the text was edited
     let child_ui = &mut ui.new_child(egui::UiBuilder::new().max_rect(text_input_rect));
-    let mut text_value = format!("{:.2}", value);
+    let text_id = response.id.with("value_text");
+    let mut text_value = child_ui
+        .data(|data| data.get_temp::<String>(text_id))
+        .unwrap_or_else(|| format!("{:.*}", display_decimals(ui), value));
     let text_response = child_ui.add(
         egui::TextEdit::singleline(&mut text_value)
+            .id(text_id.with("edit"))
             .desired_width(text_input_width)
             .horizontal_align(egui::Align::Center)
     );
     
+    if text_response.changed() {
+        child_ui.data_mut(|data| data.insert_temp(text_id, text_value));
+    }
     if text_response.lost_focus() {
-        if let Ok(new_value) = text_value.parse::<f32>() {
+        let edited = child_ui.data_mut(|data| data.remove_temp::<String>(text_id));
+        let cancelled = child_ui.input(|i| i.key_pressed(egui::Key::Escape));
+        if let Some(new_value) = edited.filter(|_| !cancelled).and_then(|text| text.trim().parse::<f32>().ok()) {
             *value = new_value.clamp(v_min, v_max);
             response.mark_changed();
         }
@@ -155,6 +189,16 @@ pub fn circular_slider_float(
     response
 }
 
+/// Arc color of a center-zero circular slider: the selection color for positive values and
+/// the warning color for negative ones
+fn signed_arc_color(visuals: &egui::Visuals, value: f32) -> egui::Color32 {
+    if value < 0.0 {
+        visuals.warn_fg_color
+    } else {
+        visuals.selection.bg_fill
+    }
+}
+
 /// Quaternion trackball widget with independent lat/lon tracking per axis
 /// The lat/lon values are relative offsets from each axis's starting position
 /// and are purely for player feedback - they don't affect the quaternion
@@ -168,13 +212,13 @@ pub fn quaternion_ball(
     z_axis_lat: &mut f32,
     z_axis_lon: &mut f32,
     radius: f32,
-    enable_snapping: bool,
+    snap: OrientationSnap,
     locked_axis: &mut i32,
     initial_distance: &mut f32,
 ) -> Response {
     let container_size = radius * 2.5;
     
-    let (rect, response) = ui.allocate_exact_size(
+    let (rect, mut response) = ui.allocate_exact_size(
         EguiVec2::new(container_size, container_size),
         Sense::click_and_drag(),
     );
@@ -189,9 +233,6 @@ pub fn quaternion_ball(
     // Get colors
     let col_ball = ui.visuals().widgets.inactive.weak_bg_fill;
     let col_ball_hovered = ui.visuals().widgets.hovered.weak_bg_fill;
-    let col_axes_x = egui::Color32::from_rgb(79, 120, 255); // Blue for X
-    let col_axes_y = egui::Color32::from_rgb(79, 255, 79);  // Green for Y
-    let col_axes_z = egui::Color32::from_rgb(255, 79, 79);  // Red for Z
     
     // Check mouse position
     let mouse_pos = ui.input(|i| i.pointer.hover_pos()).unwrap_or(Pos2::ZERO);
@@ -201,63 +242,8 @@ pub fn quaternion_ball(
     // Draw filled circle with transparency
     painter.circle_filled(center, radius, egui::Color32::from_rgba_unmultiplied(51, 51, 64, 77));
     
-    // Draw grid lines (only if snapping is enabled)
-    if enable_snapping {
-        let col_grid = egui::Color32::from_rgba_unmultiplied(100, 100, 120, 120);
-        let grid_divisions = 16;
-        let angle_step = 360.0f32 / grid_divisions as f32;
-        
-        // Draw longitude lines
-        for i in 0..grid_divisions {
-            let angle_deg = i as f32 * angle_step;
-            let angle_rad = angle_deg.to_radians();
-            
-            for j in 0..32 {
-                let t1 = (j as f32 / 32.0) * 2.0 * PI;
-                let t2 = ((j + 1) as f32 / 32.0) * 2.0 * PI;
-                
-                let x1 = t1.sin() * angle_rad.cos();
-                let y1 = t1.cos();
-                let z1 = t1.sin() * angle_rad.sin();
-                
-                let x2 = t2.sin() * angle_rad.cos();
-                let y2 = t2.cos();
-                let z2 = t2.sin() * angle_rad.sin();
-                
-                let p1 = Pos2::new(center.x + x1 * radius, center.y - y1 * radius);
-                let p2 = Pos2::new(center.x + x2 * radius, center.y - y2 * radius);
-                
-                if z1 > 0.0 && z2 > 0.0 {
-                    painter.line_segment([p1, p2], Stroke::new(1.0, col_grid));
-                }
-            }
-        }
-        
-        // Draw latitude lines
-        for i in 1..grid_divisions {
-            let angle_deg = i as f32 * angle_step;
-            let angle_rad = (angle_deg - 180.0).to_radians();
-            
-            let circle_y = angle_rad.sin();
-            let circle_radius = angle_rad.cos();
-            
-            for j in 0..32 {
-                let t1 = (j as f32 / 32.0) * 2.0 * PI;
-                let t2 = ((j + 1) as f32 / 32.0) * 2.0 * PI;
-                
-                let x1 = t1.cos() * circle_radius;
-                let z1 = t1.sin() * circle_radius;
-                let x2 = t2.cos() * circle_radius;
-                let z2 = t2.sin() * circle_radius;
-                
-                let p1 = Pos2::new(center.x + x1 * radius, center.y - circle_y * radius);
-                let p2 = Pos2::new(center.x + x2 * radius, center.y - circle_y * radius);
-                
-                if z1 > 0.0 && z2 > 0.0 {
-                    painter.line_segment([p1, p2], Stroke::new(1.0, col_grid));
-                }
-            }
-        }
+    if snap == OrientationSnap::Grid {
+        draw_snap_graticule(painter, center, radius, grid_snap_degrees(ui));
     }
     
     // Get current axis directions from quaternion
@@ -266,53 +252,10 @@ pub fn quaternion_ball(
     let y_axis = rotation_matrix * Vec3::Y;
     let z_axis = rotation_matrix * Vec3::Z;
     
-    // Helper to draw axis with depth-based brightness
-    let draw_axis = |axis: Vec3, color: egui::Color32, axis_length: f32| {
-        let behind_threshold = -0.01;
-        let is_behind = axis.z < behind_threshold;
-        
-        let end = Pos2::new(
-            center.x + axis.x * axis_length,
-            center.y - axis.y * axis_length,
-        );
-        
-        let alpha = ((axis.z + 1.0) / 2.0).clamp(0.2, 1.0) * 0.8 + 0.2;
-        let line_thickness = (2.0 + alpha * 2.0).clamp(2.0, 4.0);
-        
-        let faded_color = egui::Color32::from_rgba_unmultiplied(
-            color.r(),
-            color.g(),
-            color.b(),
-            (alpha * 255.0) as u8,
-        );
-        
-        if is_behind {
-            // Draw dotted line for axes behind the plane
-            let num_dots = 10;
-            for i in (0..num_dots).step_by(2) {
-                let t1 = i as f32 / num_dots as f32;
-                let t2 = (i + 1) as f32 / num_dots as f32;
-                let p1 = Pos2::new(
-                    center.x + (end.x - center.x) * t1,
-                    center.y + (end.y - center.y) * t1,
-                );
-                let p2 = Pos2::new(
-                    center.x + (end.x - center.x) * t2,
-                    center.y + (end.y - center.y) * t2,
-                );
-                painter.line_segment([p1, p2], Stroke::new(line_thickness, faded_color));
-            }
-        } else {
-            painter.line_segment([center, end], Stroke::new(line_thickness, faded_color));
-        }
-        
-        let circle_radius = (4.0 + alpha * 2.0).clamp(4.0, 6.0) * 0.5; // Reduced by 50%
-        painter.circle_filled(end, circle_radius, faded_color);
-    };
-    
-    draw_axis(x_axis, col_axes_x, radius);
-    draw_axis(y_axis, col_axes_y, radius);
-    draw_axis(z_axis, col_axes_z, radius);
+    draw_orientation_axes(painter, center, radius, *orientation);
+    if let OrientationSnap::Directions(set) = snap {
+        draw_direction_guides(painter, center, radius, set);
+    }
     
     // Draw outer circle
     let ball_color = if is_mouse_in_ball {
@@ -472,49 +415,19 @@ pub fn quaternion_ball(
             normalize_coords(z_axis_lat, z_axis_lon);
         }
     } else if response.drag_stopped() && *locked_axis != -1 {
-        if enable_snapping {
-            // Store the identity axis positions for reference
-            let identity_x = Vec3::X;
-            let identity_y = Vec3::Y;
-            let identity_z = Vec3::Z;
-            
+        let snapped = match snap {
+            OrientationSnap::Off => None,
             // Snap quaternion to grid
-            *orientation = snap_quaternion_to_grid(*orientation, 11.25);
+            OrientationSnap::Grid => Some(snap_quaternion_to_grid(*orientation, grid_snap_degrees(ui))),
+            // Point the X axis at the nearest direction of the set
+            OrientationSnap::Directions(set) => Some(snap_primary_axis(*orientation, set)),
+        };
+        if let Some(snapped) = snapped {
+            *orientation = snapped;
+            response.mark_changed();
             
             // Recalculate relative coordinates after snapping
-            let rotation_matrix = Mat3::from_quat(*orientation);
-            let snapped_x = rotation_matrix * Vec3::X;
-            let snapped_y = rotation_matrix * Vec3::Y;
-            let snapped_z = rotation_matrix * Vec3::Z;
-            
-            // Helper to calculate offset from identity position
-            let calc_offset = |current: Vec3, identity: Vec3| -> (f32, f32) {
-                // Clamp z values to avoid NaN
-                let current_z = current.z.clamp(-1.0, 1.0);
-                let identity_z = identity.z.clamp(-1.0, 1.0);
-                
-                let current_lat = current_z.asin().to_degrees();
-                let identity_lat = identity_z.asin().to_degrees();
-                let lat_offset = current_lat - identity_lat;
-                
-                let current_lon = current.y.atan2(current.x).to_degrees();
-                let identity_lon = identity.y.atan2(identity.x).to_degrees();
-                let mut lon_offset = current_lon - identity_lon;
-                
-                // Normalize to -180 to 180
-                while lon_offset > 180.0 {
-                    lon_offset -= 360.0;
-                }
-                while lon_offset < -180.0 {
-                    lon_offset += 360.0;
-                }
-                
-                (lat_offset, lon_offset)
-            };
-            
-            let (x_lat, x_lon) = calc_offset(snapped_x, identity_x);
-            let (y_lat, y_lon) = calc_offset(snapped_y, identity_y);
-            let (z_lat, z_lon) = calc_offset(snapped_z, identity_z);
+            let [(x_lat, x_lon), (y_lat, y_lon), (z_lat, z_lon)] = axis_lat_lon_offsets(*orientation);
             
             *x_axis_lat = x_lat;
             *x_axis_lon = x_lon;
@@ -530,6 +443,577 @@ pub fn quaternion_ball(
     response
 }
 
+/// What a quaternion ball snaps the orientation to
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum OrientationSnap {
+    Off,
+    /// Lat/lon grid, with the step from [`set_grid_snap_degrees`]
+    Grid,
+    /// Nearest direction of a regular set, for the X axis
+    Directions(DirectionSet),
+}
+
+/// Regular sets of directions the quaternion ball can snap a child's X axis to,
+/// named after the polyhedron whose vertices they point at
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum DirectionSet {
+    Tetrahedral,
+    Octahedral,
+    Cubic,
+    Icosahedral,
+    Dodecahedral,
+}
+
+impl DirectionSet {
+    pub const ALL: [DirectionSet; 5] = [
+        Self::Tetrahedral,
+        Self::Octahedral,
+        Self::Cubic,
+        Self::Icosahedral,
+        Self::Dodecahedral,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Tetrahedral => "Tetrahedral (4)",
+            Self::Octahedral => "Octahedral (6)",
+            Self::Cubic => "Cubic (8)",
+            Self::Icosahedral => "Icosahedral (12)",
+            Self::Dodecahedral => "Dodecahedral (20)",
+        }
+    }
+
+    /// Unit directions of the set, generated on first use
+    pub fn directions(self) -> &'static [Vec3] {
+        static SETS: std::sync::OnceLock<[Vec<Vec3>; 5]> = std::sync::OnceLock::new();
+        let sets = SETS.get_or_init(|| Self::ALL.map(generate_directions));
+        &sets[self as usize]
+    }
+
+    /// Direction of the set closest to `direction`
+    pub fn nearest(self, direction: Vec3) -> Vec3 {
+        self.directions()
+            .iter()
+            .copied()
+            .max_by(|a, b| a.dot(direction).total_cmp(&b.dot(direction)))
+            .unwrap_or(direction)
+    }
+}
+
+fn generate_directions(set: DirectionSet) -> Vec<Vec3> {
+    let phi = (1.0 + 5.0f32.sqrt()) / 2.0;
+    let signs = [1.0, -1.0];
+    let mut directions = Vec::new();
+    match set {
+        DirectionSet::Tetrahedral => {
+            directions.extend([
+                Vec3::new(1.0, 1.0, 1.0),
+                Vec3::new(1.0, -1.0, -1.0),
+                Vec3::new(-1.0, 1.0, -1.0),
+                Vec3::new(-1.0, -1.0, 1.0),
+            ]);
+        }
+        DirectionSet::Octahedral => {
+            directions.extend([Vec3::X, Vec3::NEG_X, Vec3::Y, Vec3::NEG_Y, Vec3::Z, Vec3::NEG_Z]);
+        }
+        DirectionSet::Cubic | DirectionSet::Dodecahedral => {
+            for x in signs {
+                for y in signs {
+                    for z in signs {
+                        directions.push(Vec3::new(x, y, z));
+                    }
+                }
+            }
+            if set == DirectionSet::Dodecahedral {
+                // The dodecahedron adds three golden rectangles to the cube's corners
+                for a in signs {
+                    for b in signs {
+                        directions.push(Vec3::new(0.0, a / phi, b * phi));
+                        directions.push(Vec3::new(a / phi, b * phi, 0.0));
+                        directions.push(Vec3::new(a * phi, 0.0, b / phi));
+                    }
+                }
+            }
+        }
+        DirectionSet::Icosahedral => {
+            for a in signs {
+                for b in signs {
+                    directions.push(Vec3::new(0.0, a, b * phi));
+                    directions.push(Vec3::new(a, b * phi, 0.0));
+                    directions.push(Vec3::new(b * phi, 0.0, a));
+                }
+            }
+        }
+    }
+    directions.into_iter().map(Vec3::normalize).collect()
+}
+
+/// Rotate `orientation` by the smallest amount that points its X axis at the nearest direction of `set`
+pub fn snap_primary_axis(orientation: Quat, set: DirectionSet) -> Quat {
+    let primary = orientation * Vec3::X;
+    let target = set.nearest(primary);
+    (Quat::from_rotation_arc(primary, target) * orientation).normalize()
+}
+
+/// Plane to mirror a child orientation across
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum MirrorPlane {
+    XY,
+    XZ,
+    YZ,
+}
+
+impl MirrorPlane {
+    pub const ALL: [MirrorPlane; 3] = [Self::XY, Self::XZ, Self::YZ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::XY => "XY Plane",
+            Self::XZ => "XZ Plane",
+            Self::YZ => "YZ Plane",
+        }
+    }
+
+    pub fn normal(self) -> Vec3 {
+        match self {
+            Self::XY => Vec3::Z,
+            Self::XZ => Vec3::Y,
+            Self::YZ => Vec3::X,
+        }
+    }
+}
+
+/// Mirror image of `orientation` across the plane through the origin with unit normal `plane_normal`.
+/// Conjugating the rotation by the reflection keeps the angle and maps the rotation axis
+/// (a pseudovector) to the negated reflection of itself.
+pub fn mirror_orientation(orientation: Quat, plane_normal: Vec3) -> Quat {
+    let axis = orientation.xyz();
+    let mirrored_axis = 2.0 * axis.dot(plane_normal) * plane_normal - axis;
+    Quat::from_xyzw(mirrored_axis.x, mirrored_axis.y, mirrored_axis.z, orientation.w).normalize()
+}
+
+/// How a reference child's orientation is carried over to another child
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum OrientationTransfer {
+    Copy,
+    Mirror(MirrorPlane),
+}
+
+/// Orientation for a child that takes `reference` from another child (possibly in another mode)
+pub fn transfer_orientation(reference: Quat, transfer: OrientationTransfer) -> Quat {
+    match transfer {
+        OrientationTransfer::Copy => reference,
+        OrientationTransfer::Mirror(plane) => mirror_orientation(reference, plane.normal()),
+    }
+}
+
+/// Black or white, whichever reads better on `background`
+pub fn contrast_text_color(background: egui::Color32) -> egui::Color32 {
+    let brightness = background.r() as f32 * 0.299 + background.g() as f32 * 0.587 + background.b() as f32 * 0.114;
+    if brightness > 127.5 {
+        egui::Color32::BLACK
+    } else {
+        egui::Color32::WHITE
+    }
+}
+
+/// Dropdown over the genome's modes, each drawn on its own color. `selected` is clamped into
+/// range before drawing; shows "None" for an empty list. Returns true when the user picks another mode.
+pub fn mode_dropdown(
+    ui: &mut egui::Ui,
+    id_salt: impl std::hash::Hash,
+    modes: &[(String, egui::Color32)],
+    selected: &mut usize,
+    width: Option<f32>,
+) -> bool {
+    if modes.is_empty() {
+        ui.label("None");
+        return false;
+    }
+    *selected = (*selected).min(modes.len() - 1);
+    let before = *selected;
+    let mode_text = |name: &str, color: egui::Color32| {
+        egui::RichText::new(name).color(contrast_text_color(color)).background_color(color)
+    };
+    let (name, color) = &modes[before];
+    let mut combo = egui::ComboBox::from_id_salt(id_salt).selected_text(mode_text(name, *color));
+    if let Some(width) = width {
+        combo = combo.width(width);
+    }
+    combo.show_ui(ui, |ui| {
+        for (i, (name, color)) in modes.iter().enumerate() {
+            ui.selectable_value(selected, i, mode_text(name, *color));
+        }
+    });
+    *selected != before
+}
+
+/// Most modes the recent-modes dropdown lists
+const MAX_RECENT_MODES: usize = 8;
+
+/// Modes selected lately, most recent first, without repeats
+#[derive(Default)]
+pub struct RecentModes {
+    modes: Vec<usize>,
+    /// Modes in the genome when the list was last updated
+    mode_count: usize,
+}
+
+impl RecentModes {
+    /// Note `selected` as the current selection. The list starts over when `mode_count` changes
+    /// without a [`RecentModes::remap`], since the modes may have been renumbered.
+    pub fn visit(&mut self, selected: usize, mode_count: usize) {
+        if mode_count != self.mode_count {
+            self.modes.clear();
+            self.mode_count = mode_count;
+        }
+        if selected >= mode_count || self.modes.first() == Some(&selected) {
+            return;
+        }
+        self.modes.retain(|&idx| idx != selected);
+        self.modes.insert(0, selected);
+        self.modes.truncate(MAX_RECENT_MODES);
+    }
+
+    /// Follow modes renumbered by a delete, move or merge, dropping removed ones.
+    /// `mode_count` is how many modes there are now.
+    pub fn remap(&mut self, remap: &[Option<usize>], mode_count: usize) {
+        let mut remapped = Vec::with_capacity(self.modes.len());
+        for new in self.modes.iter().filter_map(|&old| remap.get(old).copied().flatten()) {
+            // Merged modes can land on the same index
+            if !remapped.contains(&new) {
+                remapped.push(new);
+            }
+        }
+        self.modes = remapped;
+        self.mode_count = mode_count;
+    }
+
+    /// Recently selected modes other than the current one, most recent first
+    pub fn previous(&self) -> &[usize] {
+        self.modes.get(1..).unwrap_or_default()
+    }
+}
+
+/// Dropdown of the modes selected before the current one, each on its own color.
+/// Returns the mode picked.
+pub fn recent_modes_dropdown(
+    ui: &mut egui::Ui,
+    id_salt: impl std::hash::Hash,
+    recent: &RecentModes,
+    modes: &[(String, egui::Color32)],
+) -> Option<usize> {
+    let mut picked = None;
+    let enabled = !recent.previous().is_empty();
+    ui.add_enabled_ui(enabled, |ui| {
+        egui::ComboBox::from_id_salt(id_salt)
+            .selected_text("Recent")
+            .show_ui(ui, |ui| {
+                for &idx in recent.previous() {
+                    let Some((name, color)) = modes.get(idx) else {
+                        continue;
+                    };
+                    let text = egui::RichText::new(name).color(contrast_text_color(*color)).background_color(*color);
+                    if ui.selectable_label(false, text).clicked() {
+                        picked = Some(idx);
+                    }
+                }
+            });
+    })
+    .response
+    .on_hover_text("Switch back to a mode selected lately")
+    .on_disabled_hover_text("Modes you select are listed here");
+    picked
+}
+
+/// Display name for a `quaternion_ball` axis lock (-1 = free, 0/1/2 = X/Y/Z)
+pub fn axis_lock_label(locked_axis: i32) -> &'static str {
+    match locked_axis {
+        0 => "X (Pitch)",
+        1 => "Y (Yaw)",
+        2 => "Z (Roll)",
+        _ => "Free",
+    }
+}
+
+/// Keyboard control of a ball's axis lock while the pointer is over it:
+/// X/Y/Z toggle a lock on that axis and L cycles free → X → Y → Z → free.
+/// Returns true if the lock changed.
+pub fn axis_lock_hotkeys(ui: &Ui, response: &Response, locked_axis: &mut i32) -> bool {
+    if !response.hovered() || ui.ctx().wants_keyboard_input() {
+        return false;
+    }
+
+    let previous = *locked_axis;
+    ui.input(|i| {
+        for (key, axis) in [(egui::Key::X, 0), (egui::Key::Y, 1), (egui::Key::Z, 2)] {
+            if i.key_pressed(key) {
+                *locked_axis = if *locked_axis == axis { -1 } else { axis };
+            }
+        }
+        if i.key_pressed(egui::Key::L) {
+            *locked_axis = if *locked_axis >= 2 { -1 } else { *locked_axis + 1 };
+        }
+    });
+    *locked_axis != previous
+}
+
+/// Faint lines where grid snapping can put the X axis, every `step_degrees` of latitude and longitude.
+/// The grid's poles are ±Z (towards and away from the viewer), so latitudes project to rings around
+/// the center and longitudes to spokes. The back hemisphere lands on the same lines as the front.
+fn draw_snap_graticule(painter: &egui::Painter, center: Pos2, radius: f32, step_degrees: f32) {
+    let stroke = Stroke::new(1.0, egui::Color32::from_rgba_unmultiplied(100, 100, 120, 90));
+    let steps_per_quadrant = (90.0 / step_degrees).round().max(1.0) as usize;
+    let step = step_degrees.to_radians();
+
+    // The equator is the ball's outline, which is drawn anyway
+    for i in 1..steps_per_quadrant {
+        painter.circle_stroke(center, radius * (i as f32 * step).cos(), stroke);
+    }
+    for i in 0..steps_per_quadrant * 4 {
+        let lon = i as f32 * step;
+        painter.line_segment([center, center + radius * egui::vec2(lon.cos(), -lon.sin())], stroke);
+    }
+}
+
+/// Dots on the ball for each direction of the snap set; ones on the far side are faded
+fn draw_direction_guides(painter: &egui::Painter, center: Pos2, radius: f32, set: DirectionSet) {
+    for direction in set.directions() {
+        let pos = Pos2::new(center.x + direction.x * radius, center.y - direction.y * radius);
+        let alpha = if direction.z >= 0.0 { 200 } else { 60 };
+        painter.circle_filled(pos, 3.0, egui::Color32::from_rgba_unmultiplied(255, 220, 120, alpha));
+    }
+}
+
+/// Draw the X/Y/Z axes of `orientation` projected onto a ball of `radius` at `center`.
+/// Axes pointing away from the viewer are drawn dotted and faded.
+fn draw_orientation_axes(painter: &egui::Painter, center: Pos2, radius: f32, orientation: Quat) {
+    let col_axes_x = egui::Color32::from_rgb(79, 120, 255); // Blue for X
+    let col_axes_y = egui::Color32::from_rgb(79, 255, 79);  // Green for Y
+    let col_axes_z = egui::Color32::from_rgb(255, 79, 79);  // Red for Z
+    
+    let rotation_matrix = Mat3::from_quat(orientation);
+    let x_axis = rotation_matrix * Vec3::X;
+    let y_axis = rotation_matrix * Vec3::Y;
+    let z_axis = rotation_matrix * Vec3::Z;
+    
+    // Helper to draw axis with depth-based brightness
+    let draw_axis = |axis: Vec3, color: egui::Color32, axis_length: f32| {
+        let behind_threshold = -0.01;
+        let is_behind = axis.z < behind_threshold;
+        
+        let end = Pos2::new(
+            center.x + axis.x * axis_length,
+            center.y - axis.y * axis_length,
+        );
+        
+        let alpha = ((axis.z + 1.0) / 2.0).clamp(0.2, 1.0) * 0.8 + 0.2;
+        let line_thickness = (2.0 + alpha * 2.0).clamp(2.0, 4.0);
+        
+        let faded_color = egui::Color32::from_rgba_unmultiplied(
+            color.r(),
+            color.g(),
+            color.b(),
+            (alpha * 255.0) as u8,
+        );
+        
+        if is_behind {
+            // Draw dotted line for axes behind the plane
+            let num_dots = 10;
+            for i in (0..num_dots).step_by(2) {
+                let t1 = i as f32 / num_dots as f32;
+                let t2 = (i + 1) as f32 / num_dots as f32;
+                let p1 = Pos2::new(
+                    center.x + (end.x - center.x) * t1,
+                    center.y + (end.y - center.y) * t1,
+                );
+                let p2 = Pos2::new(
+                    center.x + (end.x - center.x) * t2,
+                    center.y + (end.y - center.y) * t2,
+                );
+                painter.line_segment([p1, p2], Stroke::new(line_thickness, faded_color));
+            }
+        } else {
+            painter.line_segment([center, end], Stroke::new(line_thickness, faded_color));
+        }
+        
+        let circle_radius = (4.0 + alpha * 2.0).clamp(4.0, 6.0) * 0.5; // Reduced by 50%
+        painter.circle_filled(end, circle_radius, faded_color);
+    };
+    
+    draw_axis(x_axis, col_axes_x, radius);
+    draw_axis(y_axis, col_axes_y, radius);
+    draw_axis(z_axis, col_axes_z, radius);
+}
+
+/// Order of the Euler angle readout, intrinsic: X, then Y about the new axes, then Z.
+/// Y is the middle angle, so it is the one limited to -90..=90 and gimbal lock happens at Y = ±90°.
+pub const EULER_ORDER: EulerRot = EulerRot::XYZ;
+
+/// `orientation` as Euler angles in `EULER_ORDER`, in degrees
+pub fn euler_degrees(orientation: Quat) -> Vec3 {
+    let (x, y, z) = orientation.to_euler(EULER_ORDER);
+    Vec3::new(x, y, z) * (180.0 / PI)
+}
+
+/// Orientation from Euler angles in `EULER_ORDER`, in degrees
+pub fn quat_from_euler_degrees(degrees: Vec3) -> Quat {
+    let radians = degrees * (PI / 180.0);
+    Quat::from_euler(EULER_ORDER, radians.x, radians.y, radians.z).normalize()
+}
+
+/// X/Y/Z Euler angle fields for `orientation`. Returns true when an edit rebuilt the quaternion.
+/// The angles last typed are kept while they still describe the orientation, so an edit near
+/// gimbal lock doesn't make the other two fields jump to an equivalent split of the rotation.
+pub fn euler_angles_edit(ui: &mut Ui, id_salt: impl std::hash::Hash, orientation: &mut Quat) -> bool {
+    let id = ui.id().with(("euler_angles", id_salt));
+    let remembered = ui.data(|data| data.get_temp::<(Vec3, Quat)>(id))
+        .filter(|(_, rotation)| rotation.angle_between(*orientation) < 1e-4)
+        .map(|(angles, _)| angles);
+    let mut angles = remembered.unwrap_or_else(|| euler_degrees(*orientation));
+
+    let mut changed = false;
+    ui.horizontal(|ui| {
+        for (label, angle, range) in [
+            ("X", &mut angles.x, -180.0..=180.0),
+            ("Y", &mut angles.y, -90.0..=90.0),
+            ("Z", &mut angles.z, -180.0..=180.0),
+        ] {
+            changed |= ui.add(egui::DragValue::new(angle).prefix(format!("{}: ", label)).suffix("°").speed(1.0).range(range))
+                .changed();
+        }
+    }).response.on_hover_text("Euler angles in XYZ order, in degrees");
+
+    if changed {
+        *orientation = quat_from_euler_degrees(angles);
+        ui.data_mut(|data| data.insert_temp(id, (angles, *orientation)));
+    }
+    changed
+}
+
+/// Lat/lon offsets of each rotated axis from its identity position, as shown next to the balls.
+/// Returns `[(x_lat, x_lon), (y_lat, y_lon), (z_lat, z_lon)]` in degrees.
+pub fn axis_lat_lon_offsets(orientation: Quat) -> [(f32, f32); 3] {
+    let rotation_matrix = Mat3::from_quat(orientation);
+    
+    // Helper to calculate offset from identity position
+    let calc_offset = |current: Vec3, identity: Vec3| -> (f32, f32) {
+        // Clamp z values to avoid NaN
+        let current_z = current.z.clamp(-1.0, 1.0);
+        let identity_z = identity.z.clamp(-1.0, 1.0);
+        
+        let current_lat = current_z.asin().to_degrees();
+        let identity_lat = identity_z.asin().to_degrees();
+        let lat_offset = current_lat - identity_lat;
+        
+        let current_lon = current.y.atan2(current.x).to_degrees();
+        let identity_lon = identity.y.atan2(identity.x).to_degrees();
+        let mut lon_offset = current_lon - identity_lon;
+        
+        // Normalize to -180 to 180
+        while lon_offset > 180.0 {
+            lon_offset -= 360.0;
+        }
+        while lon_offset < -180.0 {
+            lon_offset += 360.0;
+        }
+        
+        (lat_offset, lon_offset)
+    };
+    
+    [
+        calc_offset(rotation_matrix * Vec3::X, Vec3::X),
+        calc_offset(rotation_matrix * Vec3::Y, Vec3::Y),
+        calc_offset(rotation_matrix * Vec3::Z, Vec3::Z),
+    ]
+}
+
+/// "Look-at" variant of the quaternion ball: clicking or dragging on the ball picks a direction
+/// and the orientation becomes the shortest rotation taking the reference X axis onto it.
+/// Points outside the ball land on the rim; the back hemisphere is reached by holding Shift.
+pub fn quaternion_ball_look_at(
+    ui: &mut Ui,
+    orientation: &mut Quat,
+    radius: f32,
+    snap: OrientationSnap,
+) -> Response {
+    let container_size = radius * 2.5;
+    
+    let (rect, mut response) = ui.allocate_exact_size(
+        EguiVec2::new(container_size, container_size),
+        Sense::click_and_drag(),
+    );
+    
+    let center = rect.center();
+    let painter = ui.painter();
+    
+    let col_ball = ui.visuals().widgets.inactive.weak_bg_fill;
+    let col_ball_hovered = ui.visuals().widgets.hovered.weak_bg_fill;
+    let col_target = ui.visuals().selection.stroke.color;
+    
+    painter.circle_filled(center, radius, egui::Color32::from_rgba_unmultiplied(51, 51, 64, 77));
+    if snap == OrientationSnap::Grid {
+        draw_snap_graticule(painter, center, radius, grid_snap_degrees(ui));
+    }
+    draw_orientation_axes(painter, center, radius, *orientation);
+    if let OrientationSnap::Directions(set) = snap {
+        draw_direction_guides(painter, center, radius, set);
+    }
+    
+    // Crosshair on the current look direction
+    let look_dir = *orientation * Vec3::X;
+    let look_pos = Pos2::new(center.x + look_dir.x * radius, center.y - look_dir.y * radius);
+    painter.circle_stroke(look_pos, 6.0, Stroke::new(1.5, col_target));
+    
+    let ball_color = if response.hovered() { col_ball_hovered } else { col_ball };
+    painter.circle_stroke(center, radius, Stroke::new(2.0, ball_color));
+    
+    if (response.clicked() || response.dragged())
+        && let Some(pointer) = response.interact_pointer_pos()
+    {
+        // Map the pointer onto the unit sphere (screen Y is flipped)
+        let mut x = (pointer.x - center.x) / radius;
+        let mut y = -(pointer.y - center.y) / radius;
+        let planar = (x * x + y * y).sqrt();
+        if planar > 1.0 {
+            x /= planar;
+            y /= planar;
+        }
+        let mut z = (1.0 - x * x - y * y).max(0.0).sqrt();
+        if ui.input(|i| i.modifiers.shift) {
+            z = -z;
+        }
+        
+        let mut target = Vec3::new(x, y, z).normalize_or_zero();
+        if target != Vec3::ZERO {
+            match snap {
+                OrientationSnap::Off => {}
+                OrientationSnap::Grid => target = snap_direction_to_grid(target, grid_snap_degrees(ui)),
+                OrientationSnap::Directions(set) => target = set.nearest(target),
+            }
+        }
+        
+        if target != Vec3::ZERO {
+            let new_orientation = Quat::from_rotation_arc(Vec3::X, target).normalize();
+            if new_orientation.dot(*orientation).abs() < 0.999_999 {
+                *orientation = new_orientation;
+                response.mark_changed();
+            }
+        }
+    }
+    
+    response
+}
+
+/// Snap a direction to the nearest lat/lon grid point (same spherical convention as the ball grid)
+fn snap_direction_to_grid(dir: Vec3, grid_angle_deg: f32) -> Vec3 {
+    let lat = dir.z.clamp(-1.0, 1.0).asin().to_degrees();
+    let lon = dir.y.atan2(dir.x).to_degrees();
+    let lat = ((lat / grid_angle_deg).round() * grid_angle_deg).to_radians();
+    let lon = ((lon / grid_angle_deg).round() * grid_angle_deg).to_radians();
+    Vec3::new(lat.cos() * lon.cos(), lat.cos() * lon.sin(), lat.sin())
+}
+
 /// Snap quaternion to nearest grid angles
 fn snap_quaternion_to_grid(q: Quat, grid_angle_deg: f32) -> Quat {
     let rotation_matrix = Mat3::from_quat(q);
@@ -610,124 +1094,484 @@ fn snap_quaternion_to_grid(q: Quat, grid_angle_deg: f32) -> Quat {
     Quat::from_mat3(&snapped_matrix).normalize()
 }
 
+/// What happened to a [`modal_dialog`] this frame
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum DialogResult {
+    Open,
+    Confirmed,
+    Cancelled,
+}
+
+/// Centered dialog window with OK/Cancel buttons.
+/// Enter confirms and Escape cancels while the dialog is the topmost window or its main input
+/// has focus, and the key is used up so nothing else acts on it too. If `add_contents` returns a
+/// response, that widget keeps keyboard focus.
+pub fn modal_dialog(
+    ctx: &egui::Context,
+    title: &str,
+    add_contents: impl FnOnce(&mut Ui) -> Option<Response>,
+) -> DialogResult {
+    let mut result = DialogResult::Open;
+    let mut input_focused = false;
+
+    let shown = egui::Window::new(title)
+        .collapsible(false)
+        .resizable(false)
+        .anchor(egui::Align2::CENTER_CENTER, EguiVec2::ZERO)
+        .show(ctx, |ui| {
+            let focus_target = add_contents(ui);
+
+            ui.horizontal(|ui| {
+                if ui.button("OK").clicked() {
+                    result = DialogResult::Confirmed;
+                }
+                if ui.button("Cancel").clicked() {
+                    result = DialogResult::Cancelled;
+                }
+            });
+
+            // Auto-focus the main input
+            if let Some(response) = focus_target {
+                input_focused = response.has_focus();
+                if !input_focused {
+                    response.request_focus();
+                }
+            }
+        });
+
+    let on_top = shown.is_some_and(|shown| ctx.top_layer_id() == Some(shown.response.layer_id));
+    if result == DialogResult::Open && (on_top || input_focused) {
+        ctx.input_mut(|i| {
+            if i.consume_key(egui::Modifiers::NONE, egui::Key::Enter) {
+                result = DialogResult::Confirmed;
+            } else if i.consume_key(egui::Modifiers::NONE, egui::Key::Escape) {
+                result = DialogResult::Cancelled;
+            }
+        });
+    }
+
+    result
+}
+
+/// Decimal places used when no preference has been set for this frame
+const DEFAULT_DISPLAY_DECIMALS: usize = 2;
+
+fn display_decimals_id() -> egui::Id {
+    egui::Id::new("display_decimals")
+}
+
+/// Share the decimal places preference with the value widgets drawn this frame
+pub fn set_display_decimals(ctx: &egui::Context, decimals: usize) {
+    ctx.data_mut(|data| data.insert_temp(display_decimals_id(), decimals));
+}
+
+/// Decimal places value widgets should display
+pub fn display_decimals(ui: &Ui) -> usize {
+    ui.data(|data| data.get_temp(display_decimals_id())).unwrap_or(DEFAULT_DISPLAY_DECIMALS)
+}
+
+/// Grid snap step used when no preference has been set for this frame
+pub const DEFAULT_GRID_SNAP_DEGREES: f32 = 11.25;
+
+/// Steps the quaternion ball's grid snap can use. Each divides 90° evenly,
+/// so the grid always has lines on the equator and through the poles.
+pub const GRID_SNAP_DEGREES: [f32; 7] = [5.0, 7.5, 11.25, 15.0, 22.5, 30.0, 45.0];
+
+fn grid_snap_degrees_id() -> egui::Id {
+    egui::Id::new("grid_snap_degrees")
+}
+
+/// Share the grid snap step with the quaternion balls drawn this frame
+pub fn set_grid_snap_degrees(ctx: &egui::Context, degrees: f32) {
+    ctx.data_mut(|data| data.insert_temp(grid_snap_degrees_id(), degrees));
+}
+
+/// Lat/lon step quaternion balls snap to and draw guides for
+pub fn grid_snap_degrees(ui: &Ui) -> f32 {
+    ui.data(|data| data.get_temp(grid_snap_degrees_id())).unwrap_or(DEFAULT_GRID_SNAP_DEGREES)
+}
+
+fn show_advanced_id() -> egui::Id {
+    egui::Id::new("show_advanced_parameters")
+}
+
+/// Share the "Show advanced parameters" preference with the controls drawn this frame
+pub fn set_show_advanced(ctx: &egui::Context, show: bool) {
+    ctx.data_mut(|data| data.insert_temp(show_advanced_id(), show));
+}
+
+/// Whether controls tagged [`Detail::Advanced`] are drawn
+pub fn show_advanced(ui: &Ui) -> bool {
+    ui.data(|data| data.get_temp(show_advanced_id())).unwrap_or(false)
+}
+
+/// How commonly a control is needed; advanced ones are hidden unless the user asks for them
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Detail {
+    Basic,
+    Advanced,
+}
+
+/// Label over a [`slider_row`]. Returns `None` when the control is advanced and hidden.
+pub fn labeled_slider<Num: egui::emath::Numeric>(
+    ui: &mut Ui,
+    label: &str,
+    detail: Detail,
+    value: &mut Num,
+    range: RangeInclusive<Num>,
+    step: f64,
+    suffix: &str,
+) -> Option<Response> {
+    if detail == Detail::Advanced && !show_advanced(ui) {
+        return None;
+    }
+    ui.label(format!("{}:", label));
+    Some(slider_row(ui, value, range, step, suffix))
+}
+
+/// Single-line name field limited to [`MAX_NAME_CHARS`], with a live character count beside it.
+/// Trimming and the empty-name fallback are left to the caller, on commit.
+pub fn name_edit(ui: &mut Ui, name: &mut String) -> Response {
+    ui.horizontal(|ui| {
+        let counter_width = 44.0;
+        let field = egui::TextEdit::singleline(name)
+            .char_limit(MAX_NAME_CHARS)
+            .desired_width((ui.available_width() - counter_width).max(60.0));
+        let response = ui.add(field);
+        let count = name.chars().count();
+        let counter = egui::RichText::new(format!("{}/{}", count, MAX_NAME_CHARS)).small();
+        ui.label(if count >= MAX_NAME_CHARS { counter.color(ui.visuals().warn_fg_color) } else { counter.weak() });
+        response
+    })
+    .inner
+}
+
+/// Slider with a DragValue beside it for typing exact values.
+/// `step` is how far one arrow key press (or one point of dragging the number) moves the value,
+/// so tightly ranged fields can step finely and wide ones coarsely.
+pub fn slider_row<Num: egui::emath::Numeric>(
+    ui: &mut Ui,
+    value: &mut Num,
+    range: RangeInclusive<Num>,
+    step: f64,
+    suffix: &str,
+) -> Response {
+    ui.horizontal(|ui| {
+        let available = ui.available_width();
+        let slider_width = if available > 80.0 { available - 70.0 } else { 50.0 };
+        ui.style_mut().spacing.slider_width = slider_width;
+        let before = value.to_f64();
+        let key_presses = ui.input(arrow_key_presses);
+        let slider = ui.add(egui::Slider::new(value, range.clone()).show_value(false));
+        let mut drag_value = egui::DragValue::new(value).speed(step).range(range.clone()).suffix(suffix);
+        if !Num::INTEGRAL {
+            // Formatting alone: fixed_decimals would also round the value on every drag and nudge
+            let decimals = display_decimals(ui);
+            drag_value = drag_value.custom_formatter(move |n, _| format!("{:.*}", decimals, n));
+        }
+        let drag = ui.add(drag_value);
+        let (drag_id, slider_focused, drag_focused) = (drag.id, slider.has_focus(), drag.has_focus());
+        // The union keeps the slider's id, so its has_focus() only covers the slider
+        let mut response = slider | drag;
+
+        // The slider would otherwise move a screen point per key press and the DragValue ignores
+        // Ctrl, so both are overridden with the same step. Left/Right keep moving the text cursor
+        // while the number is being typed into
+        let presses = if drag_focused {
+            key_presses.vertical
+        } else if slider_focused {
+            key_presses.vertical + key_presses.horizontal
+        } else {
+            0
+        };
+        if presses != 0 {
+            let nudged = nudged_value(
+                before,
+                presses,
+                nudge_step(step, key_presses.modifiers),
+                range.start().to_f64()..=range.end().to_f64(),
+                Num::INTEGRAL,
+            );
+            *value = Num::from_f64(nudged);
+            // Drop any half-typed text so it doesn't overwrite the nudged value when focus leaves
+            ui.data_mut(|data| data.remove_temp::<String>(drag_id));
+            response.mark_changed();
+        }
+        response
+    })
+    .inner
+}
+
+/// Net arrow key presses this frame (up/right positive) and the modifiers held with them
+struct ArrowKeyPresses {
+    vertical: i32,
+    horizontal: i32,
+    modifiers: egui::Modifiers,
+}
+
+fn arrow_key_presses(input: &egui::InputState) -> ArrowKeyPresses {
+    let net = |inc, dec| input.num_presses(inc) as i32 - input.num_presses(dec) as i32;
+    ArrowKeyPresses {
+        vertical: net(egui::Key::ArrowUp, egui::Key::ArrowDown),
+        horizontal: net(egui::Key::ArrowRight, egui::Key::ArrowLeft),
+        modifiers: input.modifiers,
+    }
+}
+
+/// How far one arrow key press moves a value field: its base step,
+/// ten times that with Shift and a tenth of it with Ctrl (Cmd on macOS)
+pub fn nudge_step(step: f64, modifiers: egui::Modifiers) -> f64 {
+    if modifiers.shift {
+        step * 10.0
+    } else if modifiers.command {
+        step * 0.1
+    } else {
+        step
+    }
+}
+
+/// `value` moved by `presses` steps and kept in range; integer fields always move at least one
+fn nudged_value(value: f64, presses: i32, step: f64, range: RangeInclusive<f64>, integral: bool) -> f64 {
+    let step = if integral { step.round().max(1.0) } else { step };
+    (value + presses as f64 * step).clamp(*range.start(), *range.end())
+}
+
 /// Modes buttons widget - displays just the control buttons
-/// Returns (copy_into_clicked, reset_clicked)
+/// Returns (copy_into_clicked, reset_clicked, add_clicked)
 pub fn modes_buttons(
     ui: &mut Ui,
     _modes_count: usize,
     _selected_index: usize,
     _initial_mode: usize,
-) -> (bool, bool) {
+) -> (bool, bool, bool) {
     let mut copy_into_clicked = false;
     let mut reset_clicked = false;
+    let mut add_clicked = false;
 
     // Copy Into and Reset buttons on same line
     ui.horizontal(|ui| {
         // Copy Into button
-        if ui.small_button("Copy Into").clicked() {
+        let copy_into = ui.small_button("Copy Into");
+        focus_ring(ui, &copy_into);
+        if copy_into.clicked() {
             copy_into_clicked = true;
         }
 
         // Reset button with counterclockwise arrow circle icon
-        if ui.small_button("⟲").on_hover_text("Reset mode").clicked() {
+        let reset = ui.small_button("⟲").on_hover_text("Reset mode");
+        focus_ring(ui, &reset);
+        if reset.clicked() {
             reset_clicked = true;
         }
+
+        let add = ui.small_button("➕").on_hover_text("Add mode");
+        focus_ring(ui, &add);
+        if add.clicked() {
+            add_clicked = true;
+        }
     });
 
-    (copy_into_clicked, reset_clicked)
+    (copy_into_clicked, reset_clicked, add_clicked)
 }
 
+/// Outline a widget while it has keyboard focus and scroll it into view when focus arrives.
+/// egui's own focus styling is faint on small buttons and hidden by custom-painted ones.
+pub fn focus_ring(ui: &Ui, response: &Response) {
+    if response.gained_focus() {
+        response.scroll_to_me(None);
+    }
+    if response.has_focus() {
+        ui.painter().rect_stroke(response.rect.expand(2.0), 3.0, ui.visuals().selection.stroke, egui::StrokeKind::Outside);
+    }
+}
+
+/// Mode index and the color picked for it in the context menu
+pub type ModeColorChange = (usize, egui::Color32);
+
+/// Actions picked from a mode's context menu
+#[derive(Clone, Copy)]
+pub enum ModeMenuAction {
+    Export(usize),
+    Import(usize),
+    MoveUp(usize),
+    MoveDown(usize),
+    Delete(usize),
+    /// Compare this mode with the selected one
+    Compare(usize),
+    /// Save the mode's color, name and main parameters as a PNG
+    ExportCard(usize),
+    /// Star or unstar the mode
+    ToggleFavorite(usize),
+}
+
+/// One mode as the Modes list shows it
+pub struct ModeListEntry {
+    pub name: String,
+    pub color: egui::Color32,
+    pub favorite: bool,
+    /// Modes filtered out keep their index but aren't drawn
+    pub shown: bool,
+}
+
+/// How the Modes panel lays out its modes
+#[derive(Clone, Copy, PartialEq, Eq, Default)]
+pub enum ModesLayout {
+    #[default]
+    List,
+    /// Palette-style squares showing mode numbers, for genomes told apart by color
+    Grid,
+}
+
+impl ModesLayout {
+    pub const ALL: [ModesLayout; 2] = [ModesLayout::List, ModesLayout::Grid];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ModesLayout::List => "List",
+            ModesLayout::Grid => "Grid",
+        }
+    }
+}
+
+/// Side length of a mode square in the grid layout
+const MODE_GRID_CELL_SIZE: f32 = 30.0;
+
 /// Modes list items widget - displays only the list of modes (for use in scroll area)
-/// Returns (selection_changed, initial_changed, rename_index, color_change)
+/// Returns (selection_changed, initial_changed, rename_index, color_change, menu_action)
 pub fn modes_list_items(
     ui: &mut Ui,
-    modes: &[(String, egui::Color32)], // (name, color) pairs
+    modes: &[ModeListEntry],
     selected_index: &mut usize,
     initial_mode: &mut usize,
-    _width: f32,
+    layout: ModesLayout,
     copy_into_mode: bool,
     color_picker_state: &mut Option<(usize, egui::ecolor::Hsva)>,
-) -> (bool, bool, Option<usize>, Option<(usize, egui::Color32)>) {
+) -> (bool, bool, Option<usize>, Option<ModeColorChange>, Option<ModeMenuAction>) {
     let mut selection_changed = false;
     let mut initial_changed = false;
     let mut rename_index = None;
-    let mut color_picker_index: Option<(usize, egui::Color32)> = None;
+    let mut color_picker_index: Option<ModeColorChange> = None;
+    let mut menu_action = None;
     
-    for (i, (name, color)) in modes.iter().enumerate() {
-        let is_selected = i == *selected_index;
-        let is_initial = i == *initial_mode;
+    let mut show_items = |ui: &mut Ui| {
+        for (i, entry) in modes.iter().enumerate() {
+            if !entry.shown {
+                continue;
+            }
+            let (name, color) = (&entry.name, &entry.color);
+            let is_selected = i == *selected_index;
+            let is_initial = i == *initial_mode;
         
-        // Determine button colors based on selection
-        let button_color = if is_selected {
-            *color
-        } else {
-            egui::Color32::from_rgb(
-                (color.r() as f32 * 0.8) as u8,
-                (color.g() as f32 * 0.8) as u8,
-                (color.b() as f32 * 0.8) as u8,
-            )
-        };
+            // Determine button colors based on selection
+            let button_color = if is_selected {
+                *color
+            } else {
+                egui::Color32::from_rgb(
+                    (color.r() as f32 * 0.8) as u8,
+                    (color.g() as f32 * 0.8) as u8,
+                    (color.b() as f32 * 0.8) as u8,
+                )
+            };
         
-        let button_hovered = egui::Color32::from_rgb(
-            (color.r() as f32 * 0.9) as u8,
-            (color.g() as f32 * 0.9) as u8,
-            (color.b() as f32 * 0.9) as u8,
-        );
+            let button_hovered = egui::Color32::from_rgb(
+                (color.r() as f32 * 0.9) as u8,
+                (color.g() as f32 * 0.9) as u8,
+                (color.b() as f32 * 0.9) as u8,
+            );
         
-        // Determine text color based on brightness
-        let brightness = color.r() as f32 * 0.299 + color.g() as f32 * 0.587 + color.b() as f32 * 0.114;
-        let text_color = if brightness > 127.5 {
-            egui::Color32::BLACK
-        } else {
-            egui::Color32::WHITE
-        };
+            let text_color = contrast_text_color(*color);
         
-        ui.horizontal(|ui| {
-            ui.spacing_mut().item_spacing.x = 4.0; // Reduce spacing between radio and button
+            let button_response = match layout {
+                ModesLayout::List => ui.horizontal(|ui| {
+                    ui.spacing_mut().item_spacing.x = 4.0; // Reduce spacing between radio and button
             
-            // Radio button for initial mode selection (only if not in copy into mode)
-            if !copy_into_mode {
-                let radio_response = ui.radio(is_initial, "");
-                if radio_response.clicked() {
-                    *initial_mode = i;
-                    initial_changed = true;
-                }
-                radio_response.on_hover_text("Make this mode initial");
-            }
+                    // Radio button for initial mode selection (only if not in copy into mode)
+                    if !copy_into_mode {
+                        let radio_response = ui.radio(is_initial, "");
+                        if radio_response.clicked() {
+                            *initial_mode = i;
+                            initial_changed = true;
+                        }
+                        radio_response.on_hover_text("Make this mode initial");
+
+                        let star = if entry.favorite { "★" } else { "☆" };
+                        let star_response = ui.add(egui::Button::new(star).frame(false))
+                            .on_hover_text(if entry.favorite { "Remove from favorites" } else { "Add to favorites" });
+                        if star_response.clicked() {
+                            menu_action = Some(ModeMenuAction::ToggleFavorite(i));
+                        }
+                    }
             
-            // Mode button with custom styling - use remaining width
-            let button_width = ui.available_width();
-            let button_height = ui.spacing().interact_size.y; // Match standard widget height
+                    // Mode button with custom styling - use remaining width
+                    let button_width = ui.available_width();
+                    let button_height = ui.spacing().interact_size.y; // Match standard widget height
             
-            let button = egui::Button::new(egui::RichText::new(name).color(text_color))
-                .fill(button_color)
-                .wrap_mode(egui::TextWrapMode::Truncate); // Allow text to truncate instead of forcing width
+                    let button = egui::Button::new(egui::RichText::new(name).color(text_color))
+                        .fill(button_color)
+                        .wrap_mode(egui::TextWrapMode::Truncate); // Allow text to truncate instead of forcing width
             
-            let mut button_response = ui.add_sized(egui::vec2(button_width, button_height), button);
+                    let button_response = ui.add_sized(egui::vec2(button_width, button_height), button);
             
+                    if button_response.hovered() {
+                        // Draw hover effect manually
+                        let rect = button_response.rect;
+                        ui.painter().rect_filled(rect, 3.0, button_hovered);
+                        ui.painter().text(
+                            rect.center(),
+                            egui::Align2::CENTER_CENTER,
+                            name,
+                            egui::FontId::default(),
+                            text_color,
+                        );
+                    }
+                    focus_ring(ui, &button_response);
+                    button_response
+                }).inner,
+                ModesLayout::Grid => {
+                    let (rect, response) = ui.allocate_exact_size(egui::Vec2::splat(MODE_GRID_CELL_SIZE), Sense::click());
+                    let fill = if response.hovered() { button_hovered } else { button_color };
+                    ui.painter().rect_filled(rect, 3.0, fill);
+                    ui.painter().text(
+                        rect.center(),
+                        egui::Align2::CENTER_CENTER,
+                        i.to_string(),
+                        egui::FontId::proportional(11.0),
+                        text_color,
+                    );
+                    // The initial mode has no radio button here, so mark its corner instead
+                    if is_initial {
+                        ui.painter().circle_filled(rect.right_top() + egui::vec2(-5.0, 5.0), 2.5, text_color);
+                    }
+                    if entry.favorite {
+                        ui.painter().text(
+                            rect.left_top() + egui::vec2(2.0, 1.0),
+                            egui::Align2::LEFT_TOP,
+                            "★",
+                            egui::FontId::proportional(8.0),
+                            text_color,
+                        );
+                    }
+                    focus_ring(ui, &response);
+                    response
+                }
+            };
+        
             // Show tooltip with full name on hover
-            button_response = button_response.on_hover_text(name);
-            
-            if button_response.hovered() {
-                // Draw hover effect manually
-                let rect = button_response.rect;
-                ui.painter().rect_filled(rect, 3.0, button_hovered);
-                ui.painter().text(
-                    rect.center(),
-                    egui::Align2::CENTER_CENTER,
-                    name,
-                    egui::FontId::default(),
-                    text_color,
-                );
-            }
+            let hover_text = match (layout, is_initial) {
+                (ModesLayout::Grid, true) => format!("{}: {} (initial)", i, name),
+                (ModesLayout::Grid, false) => format!("{}: {}", i, name),
+                (ModesLayout::List, _) => name.to_string(),
+            };
+            let button_response = button_response.on_hover_text(hover_text);
             
             if button_response.clicked() {
                 *selected_index = i;
                 selection_changed = true;
             }
             
-            // Double-click to rename (only if not in copy into mode)
-            if !copy_into_mode && button_response.double_clicked() {
+            // Double-click or F2 to rename (only if not in copy into mode)
+            let rename_key = button_response.has_focus() && ui.input(|i| i.key_pressed(egui::Key::F2));
+            if !copy_into_mode && (button_response.double_clicked() || rename_key) {
                 rename_index = Some(i);
             }
             
@@ -736,7 +1580,7 @@ pub fn modes_list_items(
                 let mut should_close = false;
                 let mut confirmed_color = None;
                 
-                button_response.context_menu(|ui| {
+                let menu = button_response.context_menu(|ui| {
                     // Check if we're already editing this mode's color
                     let is_editing = color_picker_state.as_ref().map(|(idx, _)| *idx == i).unwrap_or(false);
                     
@@ -765,9 +1609,65 @@ pub fn modes_list_items(
                             }
                         });
                     }
+                    
+                    ui.separator();
+                    
+                    if ui.add_enabled(!is_initial, egui::Button::new("Make Initial")).clicked() {
+                        *initial_mode = i;
+                        initial_changed = true;
+                        should_close = true;
+                        ui.close();
+                    }
+                    let favorite_label = if entry.favorite { "Remove from Favorites" } else { "Add to Favorites" };
+                    if ui.button(favorite_label).clicked() {
+                        menu_action = Some(ModeMenuAction::ToggleFavorite(i));
+                        should_close = true;
+                        ui.close();
+                    }
+                    if ui.button("Export Mode...").clicked() {
+                        menu_action = Some(ModeMenuAction::Export(i));
+                        should_close = true;
+                        ui.close();
+                    }
+                    if ui.button("Import Mode...").clicked() {
+                        menu_action = Some(ModeMenuAction::Import(i));
+                        should_close = true;
+                        ui.close();
+                    }
+                    if ui.button("Export Mode Card...").clicked() {
+                        menu_action = Some(ModeMenuAction::ExportCard(i));
+                        should_close = true;
+                        ui.close();
+                    }
+                    if ui.add_enabled(!is_selected, egui::Button::new("Compare with Selected")).clicked() {
+                        menu_action = Some(ModeMenuAction::Compare(i));
+                        should_close = true;
+                        ui.close();
+                    }
+                    
+                    ui.separator();
+                    
+                    if ui.add_enabled(i > 0, egui::Button::new("Move Up")).clicked() {
+                        menu_action = Some(ModeMenuAction::MoveUp(i));
+                        should_close = true;
+                        ui.close();
+                    }
+                    if ui.add_enabled(i + 1 < modes.len(), egui::Button::new("Move Down")).clicked() {
+                        menu_action = Some(ModeMenuAction::MoveDown(i));
+                        should_close = true;
+                        ui.close();
+                    }
+                    if ui.add_enabled(modes.len() > 1, egui::Button::new("Delete Mode")).clicked() {
+                        menu_action = Some(ModeMenuAction::Delete(i));
+                        should_close = true;
+                        ui.close();
+                    }
                 });
                 
-                if should_close {
+                // The picker only lives as long as its menu, so a menu dismissed by clicking
+                // elsewhere doesn't leave a stale color behind for a reloaded or edited genome
+                let menu_closed = menu.is_none() && color_picker_state.as_ref().is_some_and(|(idx, _)| *idx == i);
+                if should_close || menu_closed {
                     *color_picker_state = None;
                 }
                 
@@ -828,8 +1728,227 @@ pub fn modes_list_items(
                 draw_dashed_line(rect.left_top(), rect.left_bottom(), false);    // Left
                 draw_dashed_line(rect.right_top(), rect.right_bottom(), false);  // Right
             }
-        });
+        }
+    };
+    
+    match layout {
+        ModesLayout::List => show_items(ui),
+        ModesLayout::Grid => {
+            ui.horizontal_wrapped(|ui| {
+                ui.spacing_mut().item_spacing = egui::vec2(3.0, 3.0);
+                show_items(ui);
+            });
+        }
+    }
+
+    (selection_changed, initial_changed, rename_index, color_picker_index, menu_action)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recent_modes_are_most_recent_first_without_repeats() {
+        let mut recent = RecentModes::default();
+        for selected in [3, 5, 3, 7, 7] {
+            recent.visit(selected, 10);
+        }
+        assert_eq!(recent.previous(), [3, 5]);
+
+        for selected in 0..10 {
+            recent.visit(selected, 10);
+        }
+        assert_eq!(recent.previous().len(), MAX_RECENT_MODES - 1);
+        assert_eq!(recent.previous()[0], 8);
+
+        // The selected mode moves to the front, then the last mode is deleted
+        let moved: Vec<Option<usize>> = (0..10).map(|old| Some((old + 1) % 10)).collect();
+        recent.remap(&moved, 10);
+        assert_eq!(recent.previous()[..3], [9, 8, 7]);
+        let deleted: Vec<Option<usize>> = (0..10).map(|old| (old != 9).then_some(old)).collect();
+        recent.remap(&deleted, 9);
+        recent.visit(0, 9);
+        assert_eq!(recent.previous()[..3], [8, 7, 6]);
+
+        // Renumbered some other way, so the list starts over
+        recent.visit(4, 8);
+        assert!(recent.previous().is_empty());
+    }
+
+    #[test]
+    fn euler_degrees_round_trip() {
+        for degrees in [Vec3::new(30.0, -45.0, 120.0), Vec3::new(-170.0, 80.0, 5.0), Vec3::ZERO] {
+            let angles = euler_degrees(quat_from_euler_degrees(degrees));
+            assert!((angles - degrees).abs().max_element() < 1e-2, "{:?} -> {:?}", degrees, angles);
+        }
+
+        // At gimbal lock the split between X and Z is ambiguous, but the rotation survives
+        let locked = quat_from_euler_degrees(Vec3::new(40.0, 90.0, 10.0));
+        let mut rotation = locked;
+        for _ in 0..100 {
+            rotation = quat_from_euler_degrees(euler_degrees(rotation));
+        }
+        assert!(rotation.angle_between(locked) < 1e-2);
+    }
+
+    #[test]
+    fn nudges_scale_with_modifiers_and_stay_in_range() {
+        let step = 0.5;
+        assert_eq!(nudge_step(step, egui::Modifiers::NONE), 0.5);
+        assert_eq!(nudge_step(step, egui::Modifiers::SHIFT), 5.0);
+        assert!((nudge_step(step, egui::Modifiers::COMMAND) - 0.05).abs() < 1e-12);
+
+        assert_eq!(nudged_value(1.0, 2, 0.5, 0.0..=10.0, false), 2.0);
+        assert_eq!(nudged_value(9.0, 1, 5.0, 0.0..=10.0, false), 10.0);
+        assert_eq!(nudged_value(1.0, -3, 0.5, 0.0..=10.0, false), 0.0);
+        // A fine step on an integer field still moves it
+        assert_eq!(nudged_value(4.0, -1, 0.1, 0.0..=10.0, true), 3.0);
+    }
+
+    #[test]
+    fn arrow_keys_step_the_typed_value_too() {
+        let ctx = egui::Context::default();
+        let mut value = 5.0_f32;
+        let frame = |value: &mut f32, key: Option<egui::Key>, modifiers: egui::Modifiers| {
+            let events = key.into_iter()
+                .map(|key| egui::Event::Key { key, physical_key: None, pressed: true, repeat: false, modifiers })
+                .collect();
+            let _ = ctx.run(egui::RawInput { events, modifiers, ..Default::default() }, |ctx| {
+                egui::CentralPanel::default().show(ctx, |ui| slider_row(ui, value, 0.0..=10.0, 0.5, ""));
+            });
+        };
+        frame(&mut value, None, egui::Modifiers::NONE);
+
+        // Tab to the slider, then on to the DragValue beside it; Ctrl steps a tenth either way
+        frame(&mut value, Some(egui::Key::Tab), egui::Modifiers::NONE);
+        frame(&mut value, Some(egui::Key::ArrowUp), egui::Modifiers::COMMAND);
+        assert!((value - 5.05).abs() < 1e-5);
+        frame(&mut value, Some(egui::Key::Tab), egui::Modifiers::NONE);
+        frame(&mut value, Some(egui::Key::ArrowUp), egui::Modifiers::COMMAND);
+        assert!((value - 5.1).abs() < 1e-5);
+        // Left/Right move the text cursor in the DragValue instead
+        frame(&mut value, Some(egui::Key::ArrowRight), egui::Modifiers::COMMAND);
+        assert!((value - 5.1).abs() < 1e-5);
+    }
+
+    #[test]
+    fn display_decimals_round_only_the_display() {
+        let ctx = egui::Context::default();
+        let mut value = 5.0_f32;
+        let frame = |value: &mut f32, events: Vec<egui::Event>| {
+            let mut rect = egui::Rect::NOTHING;
+            let _ = ctx.run(egui::RawInput { events, ..Default::default() }, |ctx| {
+                set_display_decimals(ctx, 0);
+                egui::CentralPanel::default().show(ctx, |ui| rect = slider_row(ui, value, 0.0..=10.0, 0.05, "").rect);
+            });
+            rect
+        };
+        let button = |pos, pressed| egui::Event::PointerButton { pos, button: egui::PointerButton::Primary, pressed, modifiers: egui::Modifiers::NONE };
+
+        // Drag the DragValue at the end of the row a little way
+        let start = frame(&mut value, vec![]).right_center() - EguiVec2::new(10.0, 0.0);
+        frame(&mut value, vec![egui::Event::PointerMoved(start), button(start, true)]);
+        for step in 1..=30 {
+            frame(&mut value, vec![egui::Event::PointerMoved(start + EguiVec2::new(step as f32 * 0.5, 0.0))]);
+        }
+        frame(&mut value, vec![button(start + EguiVec2::new(15.0, 0.0), false)]);
+        assert!(value != value.round(), "{} was rounded to the displayed decimals", value);
+    }
+
+    #[test]
+    fn circular_slider_text_is_only_written_back_when_edited() {
+        let ctx = egui::Context::default();
+        let mut value = 12.3_f32;
+        let frame = |value: &mut f32, events: Vec<egui::Event>| {
+            let _ = ctx.run(egui::RawInput { events, ..Default::default() }, |ctx| {
+                set_display_decimals(ctx, 0);
+                egui::CentralPanel::default().show(ctx, |ui| circular_slider_float(ui, value, -180.0, 180.0, 40.0, false, false));
+            });
+        };
+        let key = |key, modifiers| egui::Event::Key { key, physical_key: None, pressed: true, repeat: false, modifiers };
+        let tab_to_text = |value: &mut f32| {
+            // The slider itself takes focus first, then the text field in its middle
+            frame(value, vec![key(egui::Key::Tab, egui::Modifiers::NONE)]);
+            frame(value, vec![key(egui::Key::Tab, egui::Modifiers::NONE)]);
+        };
+        frame(&mut value, vec![]);
+
+        // Leaving the field untouched keeps the value, not the rounded "12" it showed
+        tab_to_text(&mut value);
+        frame(&mut value, vec![key(egui::Key::Enter, egui::Modifiers::NONE)]);
+        assert_eq!(value, 12.3);
+
+        tab_to_text(&mut value);
+        frame(&mut value, vec![key(egui::Key::A, egui::Modifiers::COMMAND)]);
+        frame(&mut value, vec![egui::Event::Text("45.5".into())]);
+        frame(&mut value, vec![key(egui::Key::Enter, egui::Modifiers::NONE)]);
+        assert_eq!(value, 45.5);
     }
 
-    (selection_changed, initial_changed, rename_index, color_picker_index)
+    #[test]
+    fn dialog_keys_go_to_the_topmost_dialog_only() {
+        let ctx = egui::Context::default();
+        let frame = |key: Option<egui::Key>| {
+            let events = key.into_iter()
+                .map(|key| egui::Event::Key { key, physical_key: None, pressed: true, repeat: false, modifiers: egui::Modifiers::NONE })
+                .collect();
+            let mut results = (DialogResult::Open, DialogResult::Open, false);
+            let _ = ctx.run(egui::RawInput { events, ..Default::default() }, |ctx| {
+                results.0 = modal_dialog(ctx, "Below", |_| None);
+                results.1 = modal_dialog(ctx, "Above", |_| None);
+                results.2 = ctx.input(|i| i.key_pressed(egui::Key::Enter) || i.key_pressed(egui::Key::Escape));
+            });
+            results
+        };
+        frame(None);
+
+        // The dialog shown last is on top; it takes the key and nothing after it sees it
+        assert!(frame(Some(egui::Key::Enter)) == (DialogResult::Open, DialogResult::Confirmed, false));
+        assert!(frame(Some(egui::Key::Escape)) == (DialogResult::Open, DialogResult::Cancelled, false));
+    }
+
+    #[test]
+    fn direction_sets_are_regular() {
+        for (set, count) in DirectionSet::ALL.into_iter().zip([4, 6, 8, 12, 20]) {
+            let directions = set.directions();
+            assert_eq!(directions.len(), count, "{:?}", set);
+            assert!(directions.iter().all(|d| (d.length() - 1.0).abs() < 1e-5));
+
+            // Every direction has the same angle to its nearest neighbour
+            let nearest_angles: Vec<f32> = directions.iter()
+                .map(|a| directions.iter().filter(|b| *b != a).map(|b| a.angle_between(*b)).fold(f32::MAX, f32::min))
+                .collect();
+            assert!(nearest_angles.iter().all(|angle| (angle - nearest_angles[0]).abs() < 1e-4), "{:?}", set);
+        }
+    }
+
+    #[test]
+    fn snap_primary_axis_points_x_at_nearest_direction() {
+        let orientation = Quat::from_euler(EulerRot::XYZ, 0.2, 0.4, -0.1);
+        let snapped = snap_primary_axis(orientation, DirectionSet::Octahedral);
+        assert!((snapped * Vec3::X).abs_diff_eq(Vec3::X, 1e-5));
+        // Already on a direction: nothing moves
+        assert!(snap_primary_axis(snapped, DirectionSet::Octahedral).abs_diff_eq(snapped, 1e-5));
+
+        let icosahedral = snap_primary_axis(orientation, DirectionSet::Icosahedral) * Vec3::X;
+        assert!(DirectionSet::Icosahedral.directions().iter().any(|d| d.abs_diff_eq(icosahedral, 1e-5)));
+    }
+
+    #[test]
+    fn mirrored_orientation_is_reflection_conjugate() {
+        let orientation = Quat::from_euler(EulerRot::XYZ, 0.3, -1.1, 0.7);
+        for plane in MirrorPlane::ALL {
+            let normal = plane.normal();
+            let reflect = |v: Vec3| v - 2.0 * v.dot(normal) * normal;
+            let mirrored = mirror_orientation(orientation, normal);
+
+            for v in [Vec3::X, Vec3::Y, Vec3::Z, Vec3::new(0.2, -0.5, 0.9)] {
+                let expected = reflect(orientation * reflect(v));
+                assert!((mirrored * v - expected).length() < 1e-5, "{} mismatch for {:?}", plane.label(), v);
+            }
+            // Mirroring twice gets back the original
+            assert!(mirror_orientation(mirrored, normal).angle_between(orientation) < 1e-4);
+        }
+    }
 }
@@ -0,0 +1,169 @@
+use bevy::prelude::*;
+use bevy::window::{Monitor, PrimaryWindow, WindowPosition};
+use serde::{Deserialize, Serialize};
+use std::fs;
+
+use crate::dock::config_dir;
+use crate::genome::CurrentGenome;
+use crate::preferences::Preferences;
+
+const WINDOW_STATE_FILE: &str = "window_state.ron";
+
+/// Window title with nothing open; the genome name and file are shown in front of it
+pub const APP_TITLE: &str = "BioSpheres-Q Egui";
+
+/// At least this much of a restored window (physical pixels) must land on a monitor
+const MIN_VISIBLE_PIXELS: i32 = 100;
+
+pub struct WindowStatePlugin;
+
+impl Plugin for WindowStatePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<LastWindowGeometry>()
+            .add_systems(Startup, restore_window_geometry)
+            .add_systems(Update, (check_window_on_screen, track_window_geometry, update_window_title));
+    }
+}
+
+/// Size and position of the primary window in physical pixels
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+struct WindowGeometry {
+    position: Option<[i32; 2]>,
+    width: u32,
+    height: u32,
+}
+
+impl WindowGeometry {
+    fn load() -> Option<Self> {
+        let path = config_dir().join(WINDOW_STATE_FILE);
+        let data = fs::read_to_string(&path).ok()?;
+        ron::from_str(&data)
+            .inspect_err(|err| warn!("Failed to parse {:?}, ignoring saved window geometry: {}", path, err))
+            .ok()
+    }
+
+    fn save(&self) {
+        let path = config_dir().join(WINDOW_STATE_FILE);
+        match ron::ser::to_string_pretty(self, Default::default()) {
+            Ok(serialized) => {
+                if let Err(err) = fs::write(&path, serialized) {
+                    warn!("Failed to write {:?}: {}", path, err);
+                }
+            }
+            Err(err) => warn!("Failed to serialize window geometry: {}", err),
+        }
+    }
+
+    fn of(window: &Window) -> Self {
+        let position = match window.position {
+            WindowPosition::At(position) => Some(position.to_array()),
+            _ => None,
+        };
+        Self {
+            position,
+            width: window.physical_width(),
+            height: window.physical_height(),
+        }
+    }
+}
+
+/// Latest geometry of the primary window. Kept up to date while running because the
+/// window entity is already gone by the time the exit message arrives.
+#[derive(Resource, Default)]
+pub struct LastWindowGeometry(Option<WindowGeometry>);
+
+/// Apply the saved geometry, or maximize like a first launch
+fn restore_window_geometry(
+    preferences: Res<Preferences>,
+    mut windows: Query<&mut Window, With<PrimaryWindow>>,
+) {
+    let Ok(mut window) = windows.single_mut() else {
+        return;
+    };
+
+    let saved = preferences.remember_window_geometry.then(WindowGeometry::load).flatten();
+    match saved {
+        Some(geometry) if geometry.width > 0 && geometry.height > 0 => {
+            window.resolution.set_physical_resolution(geometry.width, geometry.height);
+            if let Some([x, y]) = geometry.position {
+                window.position = WindowPosition::At(IVec2::new(x, y));
+            }
+            info!("Restored window geometry {}x{} at {:?}", geometry.width, geometry.height, geometry.position);
+        }
+        _ => window.set_maximized(true),
+    }
+}
+
+/// Once monitors are known, pull a restored window back if it ended up off every screen
+/// (e.g. it was last on a monitor that's since been disconnected)
+fn check_window_on_screen(
+    mut checked: Local<bool>,
+    monitors: Query<&Monitor>,
+    mut windows: Query<&mut Window, With<PrimaryWindow>>,
+) {
+    if *checked || monitors.is_empty() {
+        return;
+    }
+    *checked = true;
+
+    let Ok(mut window) = windows.single_mut() else {
+        return;
+    };
+    let WindowPosition::At(position) = window.position else {
+        return;
+    };
+    let size = IVec2::new(window.physical_width() as i32, window.physical_height() as i32);
+
+    let visible = monitors.iter().any(|monitor| {
+        let monitor_min = monitor.physical_position;
+        let monitor_max = monitor_min + IVec2::new(monitor.physical_width as i32, monitor.physical_height as i32);
+        let overlap = (position + size).min(monitor_max) - position.max(monitor_min);
+        overlap.x >= MIN_VISIBLE_PIXELS && overlap.y >= MIN_VISIBLE_PIXELS
+    });
+    if !visible {
+        warn!("Saved window position {:?} is off-screen, falling back to a maximized window", position);
+        window.position = WindowPosition::Automatic;
+        window.set_maximized(true);
+    }
+}
+
+fn track_window_geometry(
+    windows: Query<&Window, (With<PrimaryWindow>, Changed<Window>)>,
+    mut last_geometry: ResMut<LastWindowGeometry>,
+) {
+    if let Ok(window) = windows.single() {
+        last_geometry.0 = Some(WindowGeometry::of(window));
+    }
+}
+
+/// Show the genome name, its file and a `*` for unsaved changes in the title bar
+fn update_window_title(
+    current_genome: Res<CurrentGenome>,
+    mut windows: Query<&mut Window, With<PrimaryWindow>>,
+) {
+    let Ok(mut window) = windows.single_mut() else {
+        return;
+    };
+    let dirty_marker = if current_genome.is_dirty() { "*" } else { "" };
+    let file_name = current_genome.file_path.as_ref()
+        .and_then(|path| path.file_name())
+        .map(|name| format!(" ({})", name.to_string_lossy()))
+        .unwrap_or_default();
+    let title = format!("{}{}{} - {}", current_genome.genome.name, dirty_marker, file_name, APP_TITLE);
+    // Only write on a change so `Changed<Window>` doesn't fire every frame
+    if window.title != title {
+        window.title = title;
+    }
+}
+
+impl LastWindowGeometry {
+    /// Write the last known geometry if the user wants it restored next launch
+    pub fn save(&self, preferences: &Preferences) {
+        if preferences.remember_window_geometry
+            && let Some(geometry) = self.0
+        {
+            geometry.save();
+            info!("Saved window geometry");
+        }
+    }
+}
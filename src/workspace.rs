@@ -0,0 +1,376 @@
+//! The whole working state (genome, scene spheres, dock layout and preferences) in one zip.
+//! Each section is its own entry, so a bundle with one bad section still restores the others.
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+
+use crate::dock::{parse_dock_state, reopen_default_panels, restore_placeholders, DockResource};
+use crate::genome::{CurrentGenome, GenomeData};
+use crate::preferences::Preferences;
+use crate::scene::DraggableSphere;
+use crate::toast::Toasts;
+
+/// Largest section we are willing to decompress (guards against zip bombs)
+const MAX_SECTION_SIZE: u64 = 16 * 1024 * 1024;
+
+pub struct WorkspacePlugin;
+
+impl Plugin for WorkspacePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<WorkspaceState>()
+            .add_systems(Update, process_workspace_request);
+    }
+}
+
+/// One part of a workspace bundle
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WorkspaceSection {
+    Genome,
+    Scene,
+    Layout,
+    Preferences,
+}
+
+impl WorkspaceSection {
+    pub const ALL: [WorkspaceSection; 4] = [Self::Genome, Self::Scene, Self::Layout, Self::Preferences];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Genome => "genome",
+            Self::Scene => "scene",
+            Self::Layout => "layout",
+            Self::Preferences => "preferences",
+        }
+    }
+
+    fn entry_name(self) -> &'static str {
+        match self {
+            Self::Genome => "genome.json",
+            Self::Scene => "scene.ron",
+            Self::Layout => "dock_state.ron",
+            Self::Preferences => "preferences.ron",
+        }
+    }
+}
+
+/// Positions of the draggable spheres, matched back up by name
+#[derive(Serialize, Deserialize)]
+pub struct SceneSnapshot {
+    pub spheres: Vec<SphereSnapshot>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct SphereSnapshot {
+    pub name: String,
+    pub translation: [f32; 3],
+}
+
+pub enum WorkspaceRequest {
+    Export(PathBuf),
+    Import(PathBuf),
+}
+
+/// Export or import waiting for the next update, set from the File dialogs
+#[derive(Resource, Default)]
+pub struct WorkspaceState {
+    pub pending: Option<WorkspaceRequest>,
+    /// Import waiting for [`show_import_workspace_dialog`], because the genome has unsaved changes
+    pub confirm_import: Option<PathBuf>,
+}
+
+impl WorkspaceState {
+    /// Import the bundle at `path`, asking first if it would replace a genome with unsaved changes
+    pub fn import(&mut self, path: PathBuf, current_genome: &CurrentGenome) {
+        if current_genome.is_dirty() {
+            self.confirm_import = Some(path);
+        } else {
+            self.pending = Some(WorkspaceRequest::Import(path));
+        }
+    }
+}
+
+/// Confirmation before an import replaces a genome with unsaved changes
+pub fn show_import_workspace_dialog(ctx: &bevy_egui::egui::Context, workspace: &mut WorkspaceState, current_genome: &CurrentGenome) {
+    let Some(path) = &workspace.confirm_import else {
+        return;
+    };
+    let result = crate::widgets::modal_dialog(ctx, "Import Workspace", |ui| {
+        ui.label(format!("{} has unsaved changes that the genome in {} will replace.", current_genome.genome.name, path.display()));
+        ui.label("Save the genome first to keep them.");
+        None
+    });
+
+    match result {
+        crate::widgets::DialogResult::Open => {}
+        crate::widgets::DialogResult::Confirmed => {
+            workspace.pending = workspace.confirm_import.take().map(WorkspaceRequest::Import);
+        }
+        crate::widgets::DialogResult::Cancelled => workspace.confirm_import = None,
+    }
+}
+
+/// Write each section's text as its own zip entry
+pub fn write_workspace_bundle(
+    path: &Path,
+    sections: &[(WorkspaceSection, String)],
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let file = std::fs::File::create(path)?;
+    let mut archive = zip::ZipWriter::new(file);
+    let options = zip::write::SimpleFileOptions::default()
+        .compression_method(zip::CompressionMethod::Deflated);
+
+    for (section, text) in sections {
+        archive.start_file(section.entry_name(), options)?;
+        archive.write_all(text.as_bytes())?;
+    }
+
+    archive.finish()?;
+    Ok(())
+}
+
+/// Text of each section, or why it couldn't be read
+type SectionTexts = Vec<(WorkspaceSection, Result<String, String>)>;
+
+/// Text of every section in the bundle at `path`, in [`WorkspaceSection::ALL`] order.
+/// A missing or unreadable section is an error for that section only.
+pub fn read_workspace_bundle(
+    path: &Path,
+) -> Result<SectionTexts, Box<dyn std::error::Error + Send + Sync>> {
+    let file = std::fs::File::open(path)?;
+    let mut archive = zip::ZipArchive::new(file)?;
+
+    let sections = WorkspaceSection::ALL.into_iter()
+        .map(|section| {
+            let text = match archive.by_name(section.entry_name()) {
+                Ok(entry) if entry.size() > MAX_SECTION_SIZE => {
+                    Err(format!("larger than {} MiB", MAX_SECTION_SIZE / (1024 * 1024)))
+                }
+                Ok(entry) => {
+                    let mut text = String::new();
+                    entry.take(MAX_SECTION_SIZE).read_to_string(&mut text)
+                        .map(|_| text)
+                        .map_err(|err| err.to_string())
+                }
+                Err(zip::result::ZipError::FileNotFound) => Err("missing from the bundle".to_string()),
+                Err(err) => Err(err.to_string()),
+            };
+            (section, text)
+        })
+        .collect();
+    Ok(sections)
+}
+
+/// Everything a bundle restores, parsed up front so nothing is applied from a section that
+/// doesn't load
+enum ParsedSection {
+    Genome(GenomeData),
+    Scene(SceneSnapshot),
+    Layout(Box<egui_dock::DockState<crate::dock::DockTab>>, Vec<String>),
+    Preferences(Preferences),
+}
+
+/// Text of each section as [`parse_section`] reads it back
+fn serialize_sections(
+    genome: &GenomeData,
+    snapshot: &SceneSnapshot,
+    layout: &egui_dock::DockState<crate::dock::DockTab>,
+    preferences: &Preferences,
+) -> [(WorkspaceSection, Result<String, String>); 4] {
+    let pretty = ron::ser::PrettyConfig::default;
+    [
+        (WorkspaceSection::Genome, serde_json::to_string_pretty(genome).map_err(|err| err.to_string())),
+        (WorkspaceSection::Scene, ron::ser::to_string_pretty(snapshot, pretty()).map_err(|err| err.to_string())),
+        (WorkspaceSection::Layout, ron::ser::to_string_pretty(layout, pretty()).map_err(|err| err.to_string())),
+        (WorkspaceSection::Preferences, ron::ser::to_string_pretty(preferences, pretty()).map_err(|err| err.to_string())),
+    ]
+}
+
+fn parse_section(section: WorkspaceSection, text: &str) -> Result<ParsedSection, String> {
+    match section {
+        WorkspaceSection::Genome => GenomeData::from_json(text).map(ParsedSection::Genome).map_err(|err| err.to_string()),
+        WorkspaceSection::Scene => ron::from_str(text).map(ParsedSection::Scene).map_err(|err| err.to_string()),
+        WorkspaceSection::Layout => parse_dock_state(text)
+            .map(|(tree, dropped)| ParsedSection::Layout(Box::new(tree), dropped))
+            .map_err(|err| err.to_string()),
+        WorkspaceSection::Preferences => Preferences::from_ron(text)
+            .map(|(preferences, _)| ParsedSection::Preferences(preferences))
+            .map_err(|err| err.to_string()),
+    }
+}
+
+fn process_workspace_request(
+    mut workspace: ResMut<WorkspaceState>,
+    mut current_genome: ResMut<CurrentGenome>,
+    mut dock_resource: ResMut<DockResource>,
+    mut preferences: ResMut<Preferences>,
+    mut spheres: Query<(&Name, &mut Transform), With<DraggableSphere>>,
+    mut toasts: ResMut<Toasts>,
+) {
+    let Some(request) = workspace.pending.take() else {
+        return;
+    };
+
+    let mut results: Vec<(WorkspaceSection, Result<(), String>)> = Vec::new();
+    let (verb, path) = match request {
+        WorkspaceRequest::Export(path) => {
+            let snapshot = SceneSnapshot {
+                spheres: spheres.iter()
+                    .map(|(name, transform)| SphereSnapshot {
+                        name: name.to_string(),
+                        translation: transform.translation.to_array(),
+                    })
+                    .collect(),
+            };
+            let serialized = serialize_sections(&current_genome.genome_for_save(), &snapshot, dock_resource.layout_to_save(), &preferences);
+
+            let mut sections = Vec::new();
+            for (section, text) in serialized {
+                match text {
+                    Ok(text) => sections.push((section, text)),
+                    Err(err) => results.push((section, Err(err))),
+                }
+            }
+            match write_workspace_bundle(&path, &sections) {
+                Ok(()) => results.extend(sections.iter().map(|(section, _)| (*section, Ok(())))),
+                Err(err) => {
+                    warn!("Failed to write workspace {:?}: {}", path, err);
+                    toasts.error(format!("Could not export workspace to {}: {}", path.display(), err));
+                    return;
+                }
+            }
+            ("Exported", path)
+        }
+        WorkspaceRequest::Import(path) => {
+            let sections = match read_workspace_bundle(&path) {
+                Ok(sections) => sections,
+                Err(err) => {
+                    warn!("Failed to read workspace {:?}: {}", path, err);
+                    toasts.error(format!("Could not import workspace from {}: {}", path.display(), err));
+                    return;
+                }
+            };
+
+            for (section, text) in sections {
+                let parsed = text.and_then(|text| parse_section(section, &text));
+                let result = parsed.and_then(|parsed| match parsed {
+                    ParsedSection::Genome(genome) => {
+                        current_genome.back_up();
+                        current_genome.open(genome);
+                        Ok(())
+                    }
+                    ParsedSection::Scene(snapshot) => {
+                        let mut unmatched = Vec::new();
+                        for sphere in snapshot.spheres {
+                            match spheres.iter_mut().find(|(name, _)| name.as_str() == sphere.name) {
+                                Some((_, mut transform)) => transform.translation = Vec3::from_array(sphere.translation),
+                                None => unmatched.push(sphere.name),
+                            }
+                        }
+                        if unmatched.is_empty() {
+                            Ok(())
+                        } else {
+                            Err(format!("no sphere named {}", unmatched.join(", ")))
+                        }
+                    }
+                    ParsedSection::Layout(mut tree, dropped) => {
+                        if !dropped.is_empty() {
+                            warn!("Dropped unknown panels from workspace layout: {}", dropped.join(", "));
+                            reopen_default_panels(&mut tree);
+                        }
+                        restore_placeholders(&mut tree);
+                        dock_resource.exit_solo();
+                        dock_resource.tree = *tree;
+                        Ok(())
+                    }
+                    ParsedSection::Preferences(loaded) => {
+                        *preferences = loaded;
+                        Ok(())
+                    }
+                });
+                results.push((section, result));
+            }
+            ("Imported", path)
+        }
+    };
+
+    let done: Vec<&str> = results.iter().filter(|(_, result)| result.is_ok()).map(|(section, _)| section.label()).collect();
+    if !done.is_empty() {
+        info!("{} workspace {:?}: {}", verb, path, done.join(", "));
+        toasts.info(format!("{} workspace {}: {}", verb, path.display(), done.join(", ")));
+    }
+    for (section, result) in &results {
+        if let Err(err) = result {
+            warn!("Workspace {} failed: {}", section.label(), err);
+            toasts.error(format!("Workspace {}: {}", section.label(), err));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bundle_reports_missing_sections_separately() {
+        let path = std::env::temp_dir().join(format!("workspace_bundle_test_{}.zip", std::process::id()));
+        let sections = [
+            (WorkspaceSection::Genome, "{}".to_string()),
+            (WorkspaceSection::Preferences, "()".to_string()),
+        ];
+
+        write_workspace_bundle(&path, &sections).unwrap();
+        let read = read_workspace_bundle(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let sections: Vec<WorkspaceSection> = read.iter().map(|(section, _)| *section).collect();
+        assert_eq!(sections, WorkspaceSection::ALL);
+        assert_eq!(read[0].1.as_deref(), Ok("{}"));
+        assert!(read[1].1.is_err());
+        assert!(read[2].1.is_err());
+        assert_eq!(read[3].1.as_deref(), Ok("()"));
+    }
+
+    #[test]
+    fn exported_sections_import_unchanged() {
+        let genome = GenomeData {
+            name: "Round Trip".to_string(),
+            modes: vec![crate::genome::ModeSettings::default(); 2],
+            ..Default::default()
+        };
+        let snapshot = SceneSnapshot {
+            spheres: vec![SphereSnapshot { name: "Sphere 1".to_string(), translation: [1.0, 0.5, -2.0] }],
+        };
+        let layout = crate::dock::create_default_layout(1280.0);
+        let preferences = Preferences { display_decimals: 3, ..Default::default() };
+
+        let path = std::env::temp_dir().join(format!("workspace_round_trip_{}.zip", std::process::id()));
+        let sections: Vec<(WorkspaceSection, String)> = serialize_sections(&genome, &snapshot, &layout, &preferences)
+            .into_iter()
+            .map(|(section, text)| (section, text.unwrap()))
+            .collect();
+        write_workspace_bundle(&path, &sections).unwrap();
+        let read = read_workspace_bundle(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        for (section, text) in read {
+            match parse_section(section, &text.unwrap()).unwrap() {
+                ParsedSection::Genome(loaded) => assert!(loaded == genome),
+                ParsedSection::Scene(loaded) => {
+                    assert_eq!(loaded.spheres.len(), 1);
+                    assert_eq!(loaded.spheres[0].name, "Sphere 1");
+                    assert_eq!(loaded.spheres[0].translation, [1.0, 0.5, -2.0]);
+                }
+                ParsedSection::Layout(loaded, dropped) => {
+                    assert!(dropped.is_empty());
+                    let panels = |tree: &egui_dock::DockState<crate::dock::DockTab>| -> Vec<_> {
+                        tree.iter_all_tabs().map(|(_, tab)| tab.panel.clone()).collect()
+                    };
+                    assert_eq!(panels(&loaded), panels(&layout));
+                }
+                ParsedSection::Preferences(loaded) => assert!(loaded == preferences),
+            }
+        }
+    }
+}